@@ -1,8 +1,8 @@
-use std::hint;
+use std::{hint, time::Duration};
 
 use criterion::{Criterion, criterion_group, criterion_main};
 use rand::{RngCore, SeedableRng};
-use sais_drum::SaisBuilder;
+use sais_drum::{BucketGranularity, SaisBuilder};
 
 fn large_random_text_vs_divsufsort(c: &mut Criterion) {
     let mut group = c.benchmark_group("vs-divsufsort");
@@ -27,7 +27,87 @@ fn large_random_text_vs_divsufsort(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, large_random_text_vs_divsufsort);
+fn bucket_granularities(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bucket-granularity");
+    group.sample_size(10);
+
+    let texts = [
+        ("dna", create_random_dna_text(10_000_000)),
+        ("random", create_random_text(10_000_000)),
+    ];
+
+    for (text_name, text) in &texts {
+        for (granularity_name, bucket_granularity) in [
+            ("characters", BucketGranularity::Characters),
+            (
+                "characters-and-types",
+                BucketGranularity::CharactersAndTypes,
+            ),
+        ] {
+            group.bench_with_input(
+                format!("{text_name}-{granularity_name}"),
+                text,
+                |b, text| {
+                    b.iter(|| {
+                        let suffix_array = SaisBuilder::<_, u32>::new()
+                            .with_bucket_granularity(bucket_granularity)
+                            .construct_suffix_array(text);
+                        hint::black_box(suffix_array);
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+// the small text threshold of SaisBuilder is chosen from this comparison
+fn small_texts_comparison_sort_vs_sais(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small-texts");
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_secs(1));
+
+    for len in [100, 150, 200, 250, 300, 400, 500] {
+        let texts = [
+            ("random", create_random_text(len)),
+            ("dna", create_random_dna_text(len)),
+            ("run", vec![b'A'; len]),
+            (
+                "tandem-repeat",
+                b"ACGTTG".repeat(len.div_ceil(6))[..len].to_vec(),
+            ),
+        ];
+
+        for (text_name, text) in &texts {
+            for (algorithm_name, small_text_threshold) in
+                [("comparison-sort", usize::MAX), ("sais", 0)]
+            {
+                group.bench_with_input(
+                    format!("{text_name}-{len}-{algorithm_name}"),
+                    text,
+                    |b, text| {
+                        b.iter(|| {
+                            let suffix_array = SaisBuilder::<_, u32>::new()
+                                .with_small_text_threshold(small_text_threshold)
+                                .construct_suffix_array(text);
+                            hint::black_box(suffix_array);
+                        })
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    large_random_text_vs_divsufsort,
+    bucket_granularities,
+    small_texts_comparison_sort_vs_sais
+);
 
 criterion_main!(benches);
 
@@ -39,3 +119,11 @@ fn create_random_text(len: usize) -> Vec<u8> {
 
     text
 }
+
+fn create_random_dna_text(len: usize) -> Vec<u8> {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0x0DDB1A5E5BAD5EEDu64);
+
+    (0..len)
+        .map(|_| b"ACGT"[(rng.next_u32() % 4) as usize])
+        .collect()
+}
@@ -0,0 +1,27 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    generate_c_header();
+}
+
+// the header is generated into OUT_DIR, a test checks that include/sais_drum.h is up to date
+#[cfg(feature = "capi")]
+fn generate_c_header() {
+    println!("cargo::rerun-if-changed=src/capi.rs");
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+
+    cbindgen::Builder::new()
+        .with_src("src/capi.rs")
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("SAIS_DRUM_H")
+        .with_sys_include("stdint.h")
+        .with_no_includes()
+        .with_cpp_compat(true)
+        .with_header(
+            "/* Generated by cbindgen from src/capi.rs, do not edit. */\n\
+             /* The functions have the signatures and semantics of the ones from libsais. */",
+        )
+        .generate()
+        .expect("could not generate the C header")
+        .write_to_file(format!("{out_dir}/sais_drum.h"));
+}
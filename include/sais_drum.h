@@ -0,0 +1,39 @@
+/* Generated by cbindgen from src/capi.rs, do not edit. */
+/* The functions have the signatures and semantics of the ones from libsais. */
+
+#ifndef SAIS_DRUM_H
+#define SAIS_DRUM_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+int32_t libsais(const uint8_t *T, int32_t *SA, int32_t n, int32_t fs, int32_t *freq);
+
+int32_t libsais16(const uint16_t *T, int32_t *SA, int32_t n, int32_t fs, int32_t *freq);
+
+int64_t libsais64(const uint8_t *T, int64_t *SA, int64_t n, int64_t fs, int64_t *freq);
+
+int32_t libsais_bwt(const uint8_t *T, uint8_t *U, int32_t *A, int32_t n, int32_t fs, int32_t *freq);
+
+int32_t libsais16_bwt(const uint16_t *T,
+                      uint16_t *U,
+                      int32_t *A,
+                      int32_t n,
+                      int32_t fs,
+                      int32_t *freq);
+
+int64_t libsais64_bwt(const uint8_t *T,
+                      uint8_t *U,
+                      int64_t *A,
+                      int64_t n,
+                      int64_t fs,
+                      int64_t *freq);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SAIS_DRUM_H */
@@ -0,0 +1,182 @@
+// Minimal absent words: words that do not occur in the text, but all of their proper factors do.
+// The alphabet is the one of the character type, all characters up to Character::max_char. The
+// MAWs of length 1 are the characters that do not occur in the text, they are only found for
+// character types that implement Character::from_rank.
+//
+// A minimal absent word of length at least 2 has the form aub with characters a and b, where au
+// and ub occur, but aub doesn't. Then u is followed by b and by another character or the end of
+// the text, so it is the empty string or the string of an LCP-interval. Such an interval has the
+// MAW aub for every child interval starting with b and every character a that appears before an
+// occurrence of u but before none of the child interval (Barton, Heliou, Mouchard and Pissis,
+// 2014). The sets of preceding characters are merged bottom-up, which takes O(n σ) time.
+
+use std::ops::Range;
+
+use crate::{
+    Character, IndexStorage,
+    enhanced_suffix_array::{EnhancedSuffixArray, LcpInterval},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinimalAbsentWord<C> {
+    pub first: C,
+    // an occurrence of the middle part u in the text
+    pub middle: Range<usize>,
+    // None for the characters that do not occur in the text, which are MAWs of length 1
+    pub last: Option<C>,
+}
+
+impl<C: Character> MinimalAbsentWord<C> {
+    pub fn word_len(&self) -> usize {
+        match self.last {
+            Some(_) => self.middle.len() + 2,
+            None => 1,
+        }
+    }
+
+    pub fn to_word(&self, text: &[C]) -> Vec<C> {
+        let mut word = Vec::with_capacity(self.word_len());
+        word.push(self.first);
+        word.extend_from_slice(&text[self.middle.clone()]);
+        word.extend(self.last);
+
+        word
+    }
+}
+
+// the MAWs in no particular order. The alphabet of the character type is enumerated, so it is
+// restricted to the size of u16.
+pub fn minimal_absent_words<C: Character, I: IndexStorage>(
+    enhanced_suffix_array: &EnhancedSuffixArray<'_, C, I>,
+) -> Vec<MinimalAbsentWord<C>> {
+    let max_rank = C::max_char().rank();
+    assert!(
+        max_rank <= u16::MAX as usize,
+        "the alphabet of the character type is too large"
+    );
+
+    let text = enhanced_suffix_array.text();
+    let suffix_array = enhanced_suffix_array.suffix_array();
+
+    let mut occurring_chars_by_rank = vec![None; max_rank + 1];
+    for &char in text {
+        occurring_chars_by_rank[char.rank()] = Some(char);
+    }
+
+    // the empty string occurs after every character of the text
+    let occurring_chars: Vec<C> = occurring_chars_by_rank.iter().flatten().copied().collect();
+
+    let mut absent_words: Vec<_> = (0..=max_rank)
+        .filter(|&rank| occurring_chars_by_rank[rank].is_none())
+        .filter_map(C::from_rank)
+        .map(|first| MinimalAbsentWord {
+            first,
+            middle: 0..0,
+            last: None,
+        })
+        .collect();
+
+    let preceding_chars_of_leaf = |interval: &LcpInterval| -> Vec<C> {
+        let suffix_index: usize = suffix_array[interval.start].as_();
+        suffix_index
+            .checked_sub(1)
+            .map(|index| text[index])
+            .into_iter()
+            .collect()
+    };
+
+    // the sorted sets of preceding characters of the intervals whose parent is not visited yet
+    let mut pending: Vec<Vec<C>> = Vec::new();
+
+    enhanced_suffix_array.bottom_up_traversal(|interval, children| {
+        // the inner children were visited last, in suffix array order
+        let num_inner_children = children.iter().filter(|child| !child.is_leaf()).count();
+        let mut inner_children = pending
+            .split_off(pending.len() - num_inner_children)
+            .into_iter();
+
+        let preceding_chars_of_children: Vec<Vec<C>> = children
+            .iter()
+            .map(|child| {
+                if child.is_leaf() {
+                    preceding_chars_of_leaf(child)
+                } else {
+                    inner_children.next().unwrap()
+                }
+            })
+            .collect();
+
+        let mut preceding_chars = preceding_chars_of_children.concat();
+        preceding_chars.sort_unstable();
+        preceding_chars.dedup();
+
+        let middle_start: usize = suffix_array[interval.start].as_();
+        let middle = middle_start..middle_start + interval.lcp;
+        let first_chars = if interval.lcp == 0 {
+            &occurring_chars
+        } else {
+            &preceding_chars
+        };
+
+        for (child, preceding_chars_of_child) in children.iter().zip(&preceding_chars_of_children) {
+            let suffix_index: usize = suffix_array[child.start].as_();
+
+            // the suffix that ends after the common prefix is not followed by a character
+            if let Some(&last) = text.get(suffix_index + interval.lcp) {
+                add_absent_words(
+                    &mut absent_words,
+                    first_chars,
+                    preceding_chars_of_child,
+                    &middle,
+                    last,
+                );
+            }
+        }
+
+        pending.push(preceding_chars);
+    });
+
+    // if the root is not a 0-interval, the empty string has the root as its only child
+    if let Some(root) = enhanced_suffix_array.root()
+        && root.lcp > 0
+    {
+        let preceding_chars_of_root = if root.is_leaf() {
+            preceding_chars_of_leaf(&root)
+        } else {
+            pending.pop().unwrap()
+        };
+
+        add_absent_words(
+            &mut absent_words,
+            &occurring_chars,
+            &preceding_chars_of_root,
+            &(0..0),
+            text[0],
+        );
+    }
+
+    absent_words
+}
+
+// the words first u last for the first characters that never precede u last, both sets are sorted
+fn add_absent_words<C: Character>(
+    absent_words: &mut Vec<MinimalAbsentWord<C>>,
+    first_chars: &[C],
+    preceding_chars: &[C],
+    middle: &Range<usize>,
+    last: C,
+) {
+    let mut preceding_chars = preceding_chars.iter().peekable();
+
+    for &first in first_chars {
+        while preceding_chars.next_if(|&&char| char < first).is_some() {}
+
+        if preceding_chars.next_if_eq(&&first).is_none() {
+            absent_words.push(MinimalAbsentWord {
+                first,
+                middle: middle.clone(),
+                last: Some(last),
+            });
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::Character;
+
+use num_traits::{NumCast, PrimInt};
+
+// the declared alphabet has to be at least this many times larger than the alphabet used by the text,
+// e.g. u8 texts of DNA with up to 64 different characters are compacted
+const MIN_ALPHABET_SIZE_RATIO_FOR_COMPACTION: usize = 4;
+
+// maps the characters that appear in a text to the dense range 0..alphabet_size,
+// which keeps their order and therefore does not change the suffix array
+pub struct CompactAlphabet {
+    // only valid at the ranks of characters that appear in the text
+    compact_ranks: Vec<u16>,
+    // the inverse mapping, from compact ranks to the ranks of the declared alphabet
+    declared_ranks: Vec<u16>,
+    alphabet_size: usize,
+}
+
+impl CompactAlphabet {
+    // expects the declared alphabet to fit into u16, like the buckets of SAIS
+    pub fn create_if_worthwhile<C: Character>(text: &[C], max_char: C) -> Option<Self> {
+        let declared_alphabet_size = max_char.rank() + 1;
+        assert!(declared_alphabet_size <= u16::MAX as usize + 1);
+
+        let mut compact_ranks = vec![0u16; declared_alphabet_size];
+        let mut alphabet_size = 0;
+
+        for char in text {
+            let entry = &mut compact_ranks[char.rank()];
+
+            if *entry == 0 {
+                *entry = 1;
+                alphabet_size += 1;
+            }
+        }
+
+        Self::from_presence_markers_if_worthwhile(compact_ranks, alphabet_size)
+    }
+
+    // like create_if_worthwhile, but the used alphabet is derived from known frequencies
+    // instead of a scan of the text
+    pub fn from_char_frequencies_if_worthwhile<I: PrimInt>(char_frequencies: &[I]) -> Option<Self> {
+        let declared_alphabet_size = char_frequencies.len();
+        assert!(declared_alphabet_size <= u16::MAX as usize + 1);
+
+        let compact_ranks: Vec<_> = char_frequencies
+            .iter()
+            .map(|frequency| !frequency.is_zero() as u16)
+            .collect();
+        let alphabet_size = compact_ranks.iter().filter(|&&entry| entry == 1).count();
+
+        Self::from_presence_markers_if_worthwhile(compact_ranks, alphabet_size)
+    }
+
+    // expects compact_ranks to contain 1 for the chars that appear in the text and 0 otherwise
+    fn from_presence_markers_if_worthwhile(
+        mut compact_ranks: Vec<u16>,
+        alphabet_size: usize,
+    ) -> Option<Self> {
+        // an empty text has nothing to compact
+        if alphabet_size == 0
+            || alphabet_size * MIN_ALPHABET_SIZE_RATIO_FOR_COMPACTION > compact_ranks.len()
+        {
+            return None;
+        }
+
+        let mut declared_ranks = Vec::with_capacity(alphabet_size);
+
+        // this turns the presence markers into the new ranks, which fit into u16,
+        // because the alphabet used by the text is smaller than the declared one
+        for (declared_rank, entry) in compact_ranks.iter_mut().enumerate() {
+            if *entry == 1 {
+                *entry = declared_ranks.len() as u16;
+                declared_ranks.push(declared_rank as u16);
+            }
+        }
+
+        Some(Self {
+            compact_ranks,
+            declared_ranks,
+            alphabet_size,
+        })
+    }
+
+    pub fn alphabet_size(&self) -> usize {
+        self.alphabet_size
+    }
+
+    // the target char type has to be able to represent all ranks of the compact alphabet
+    pub fn compact_text<C: Character, D: PrimInt>(&self, text: &[C]) -> Vec<D> {
+        assert!(self.alphabet_size - 1 <= D::max_value().to_usize().unwrap());
+
+        text.iter()
+            .map(|char| <D as NumCast>::from(self.compact_ranks[char.rank()]).unwrap())
+            .collect()
+    }
+
+    pub fn compact_char_frequencies<I: PrimInt>(&self, char_frequencies: &[I]) -> Vec<I> {
+        self.declared_ranks
+            .iter()
+            .map(|&declared_rank| char_frequencies[declared_rank as usize])
+            .collect()
+    }
+
+    // the frequencies of characters that don't appear in the text are set to zero
+    pub fn expand_char_frequencies<I: PrimInt>(
+        &self,
+        compact_char_frequencies: &[I],
+        char_frequencies: &mut [I],
+    ) {
+        char_frequencies.fill(I::zero());
+
+        for (&declared_rank, &frequency) in self.declared_ranks.iter().zip(compact_char_frequencies)
+        {
+            char_frequencies[declared_rank as usize] = frequency;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dna_u8_text_is_compacted() {
+        let text = b"ACGTNNACGTTGCA";
+
+        let compact_alphabet = CompactAlphabet::create_if_worthwhile(text, u8::MAX).unwrap();
+        assert_eq!(compact_alphabet.alphabet_size(), 5);
+
+        let compact_text: Vec<u8> = compact_alphabet.compact_text(text);
+        assert_eq!(compact_text, [0, 1, 2, 4, 3, 3, 0, 1, 2, 4, 4, 2, 1, 0]);
+
+        let mut char_frequencies = [0u32; 256];
+        for &char in text {
+            char_frequencies[char as usize] += 1;
+        }
+
+        let compact_alphabet =
+            CompactAlphabet::from_char_frequencies_if_worthwhile(&char_frequencies).unwrap();
+        assert_eq!(
+            compact_alphabet.compact_char_frequencies(&char_frequencies),
+            [3, 3, 3, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_dense_u8_text_is_not_compacted() {
+        let text: Vec<u8> = (0..=u8::MAX).collect();
+        assert!(CompactAlphabet::create_if_worthwhile(&text, u8::MAX).is_none());
+    }
+}
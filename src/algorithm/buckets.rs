@@ -5,9 +5,57 @@ use crate::{Character, IndexStorage};
 
 use std::iter;
 
+// for alphabets up to this size, the buckets are additionally split into their L- and S-type parts.
+// The additional persistent array has at most this many entries, and the bucket-granularity
+// benchmark shows that it is not slower on DNA and random u8 texts of 10^7 characters (a few
+// percent faster, close to the noise)
+const MAX_ALPHABET_SIZE_FOR_TYPE_BUCKETS: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BucketGranularity {
+    // one bucket per character
+    Characters,
+    // one bucket per character, each split into an L-type part followed by an S-type part
+    CharactersAndTypes,
+}
+
+impl BucketGranularity {
+    // the type buckets need an additional persistent array of bucket indices, which only pays off
+    // if the alphabet is small compared to typical text lengths
+    pub fn for_alphabet_size(alphabet_size: usize) -> Self {
+        if alphabet_size <= MAX_ALPHABET_SIZE_FOR_TYPE_BUCKETS {
+            Self::CharactersAndTypes
+        } else {
+            Self::Characters
+        }
+    }
+
+    pub fn num_persistent_bucket_arrays(self) -> usize {
+        match self {
+            Self::Characters => 1,
+            Self::CharactersAndTypes => 2,
+        }
+    }
+}
+
+// splits the persistent bucket buffer into the bucket start indices and, depending on the
+// granularity, the counts of L-type chars or start indices of the S-type parts of the buckets
+pub fn split_persistent_bucket_buffer<I>(
+    persistent_bucket_buffer: &mut [I],
+    num_buckets: usize,
+) -> (&mut [I], Option<&mut [I]>) {
+    let (bucket_start_indices, rest) = persistent_bucket_buffer.split_at_mut(num_buckets);
+    let maybe_s_type_bucket_start_indices = (!rest.is_empty()).then_some(rest);
+
+    (bucket_start_indices, maybe_s_type_bucket_start_indices)
+}
+
 // inclusive index, the virtual bucket of the sentinel (count 1, ends at 0) is NOT included
+// if L-type counts are given, they are transformed into the start indices of the S-type parts of the buckets
 pub fn counts_into_bucket_start_indices<I: IndexStorage>(
     persistent_bucket_start_indices_buffer: &mut [I],
+    maybe_l_type_counts_buffer: Option<&mut [I]>,
 ) {
     let mut sum = I::zero();
 
@@ -16,6 +64,17 @@ pub fn counts_into_bucket_start_indices<I: IndexStorage>(
         sum = sum + *value;
         *value = temp;
     }
+
+    let Some(l_type_counts_buffer) = maybe_l_type_counts_buffer else {
+        return;
+    };
+
+    for (&bucket_start_index, value) in persistent_bucket_start_indices_buffer
+        .iter()
+        .zip(l_type_counts_buffer)
+    {
+        *value = bucket_start_index + *value;
+    }
 }
 
 // inclusive index, except for empty buckets, there the end index is the start index - 1
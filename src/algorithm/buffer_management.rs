@@ -1,3 +1,4 @@
+use super::buckets::BucketGranularity;
 use super::util;
 use crate::IndexStorage;
 
@@ -168,18 +169,27 @@ impl<I: IndexStorage> BufferStack<I> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct BufferConfig {
     pub is_s_type_buffer_size: usize,
+    pub persistent_bucket_buffer_size: usize,
     pub is_s_type_buffer_in_main_buffer: bool,
     pub persistent_bucket_buffer_in_main_buffer: bool,
     pub working_bucket_buffer_in_main_buffer: bool,
 }
 
 impl BufferConfig {
-    pub fn calculate<I>(text_len: usize, main_buffer_len: usize, num_buckets: usize) -> Self {
+    pub fn calculate<I>(
+        text_len: usize,
+        main_buffer_len: usize,
+        num_buckets: usize,
+        bucket_granularity: BucketGranularity,
+    ) -> Self {
         let is_s_type_buffer_size = (text_len + 1).div_ceil(size_of::<I>() * 8);
-        let is_s_type_buffer_is_larger = is_s_type_buffer_size > num_buckets;
+        let persistent_bucket_buffer_size =
+            num_buckets * bucket_granularity.num_persistent_bucket_arrays();
+        let is_s_type_buffer_is_larger = is_s_type_buffer_size > persistent_bucket_buffer_size;
 
         let mut buffer_config = BufferConfig {
             is_s_type_buffer_size,
+            persistent_bucket_buffer_size,
             is_s_type_buffer_in_main_buffer: false,
             persistent_bucket_buffer_in_main_buffer: false,
             working_bucket_buffer_in_main_buffer: false,
@@ -188,29 +198,35 @@ impl BufferConfig {
         let mut remaining_surplus_buffer_len = main_buffer_len - text_len;
 
         if is_s_type_buffer_is_larger
-            && !(2 * num_buckets <= remaining_surplus_buffer_len
-                && is_s_type_buffer_size + num_buckets > remaining_surplus_buffer_len)
+            && !(persistent_bucket_buffer_size + num_buckets <= remaining_surplus_buffer_len
+                && is_s_type_buffer_size + persistent_bucket_buffer_size
+                    > remaining_surplus_buffer_len)
         {
             if remaining_surplus_buffer_len >= is_s_type_buffer_size {
                 buffer_config.is_s_type_buffer_in_main_buffer = true;
                 remaining_surplus_buffer_len -= is_s_type_buffer_size;
             }
 
-            if remaining_surplus_buffer_len >= num_buckets {
+            if remaining_surplus_buffer_len >= persistent_bucket_buffer_size {
                 buffer_config.persistent_bucket_buffer_in_main_buffer = true;
-                remaining_surplus_buffer_len -= num_buckets;
+                remaining_surplus_buffer_len -= persistent_bucket_buffer_size;
             }
 
-            if remaining_surplus_buffer_len >= num_buckets {
+            // the working bucket buffer can only be placed in front of the persistent one
+            if buffer_config.persistent_bucket_buffer_in_main_buffer
+                && remaining_surplus_buffer_len >= num_buckets
+            {
                 buffer_config.working_bucket_buffer_in_main_buffer = true;
             }
         } else {
-            if remaining_surplus_buffer_len >= num_buckets {
+            if remaining_surplus_buffer_len >= persistent_bucket_buffer_size {
                 buffer_config.persistent_bucket_buffer_in_main_buffer = true;
-                remaining_surplus_buffer_len -= num_buckets;
+                remaining_surplus_buffer_len -= persistent_bucket_buffer_size;
             }
 
-            if remaining_surplus_buffer_len >= num_buckets {
+            if buffer_config.persistent_bucket_buffer_in_main_buffer
+                && remaining_surplus_buffer_len >= num_buckets
+            {
                 buffer_config.working_bucket_buffer_in_main_buffer = true;
                 remaining_surplus_buffer_len -= num_buckets;
             }
@@ -223,6 +239,21 @@ impl BufferConfig {
         buffer_config
     }
 
+    // the surplus of the main buffer that is needed to place all buffers of the
+    // top level of the algorithm in the main buffer
+    #[cfg_attr(not(feature = "mmap"), allow(dead_code))]
+    pub fn surplus_len_for_main_buffer_only<I>(
+        text_len: usize,
+        num_buckets: usize,
+        bucket_granularity: BucketGranularity,
+    ) -> usize {
+        let is_s_type_buffer_size = (text_len + 1).div_ceil(size_of::<I>() * 8);
+        let persistent_bucket_buffer_size =
+            num_buckets * bucket_granularity.num_persistent_bucket_arrays();
+
+        is_s_type_buffer_size + persistent_bucket_buffer_size + num_buckets
+    }
+
     pub fn num_extra_buffers(&self) -> usize {
         [
             self.is_s_type_buffer_in_main_buffer,
@@ -238,12 +269,12 @@ impl BufferConfig {
 pub struct Buffers<'m, 'e, I> {
     pub remaining_main_buffer_without_persistent_buffers: &'m mut [I],
     pub is_s_type_buffer: &'e mut [I],
-    pub persistent_bucket_start_indices_buffer: &'e mut [I],
+    pub persistent_bucket_buffer: &'e mut [I],
     pub maybe_working_bucket_indices_buffer: Option<&'e mut [I]>,
 }
 
 // if buffer request mode is instatiate, then returned is_s_type_buffer and
-// persistent_bucket_start_indices_buffer are guarenteed to be filled with zeroes
+// persistent_bucket_buffer are guarenteed to be filled with zeroes
 pub fn instantiate_or_recover_buffers<'e, 'm: 'e, I: IndexStorage>(
     buffer_config: BufferConfig,
     main_buffer: &'m mut [I],
@@ -253,7 +284,7 @@ pub fn instantiate_or_recover_buffers<'e, 'm: 'e, I: IndexStorage>(
 ) -> Buffers<'m, 'e, I> {
     let mut remaining_main_buffer = main_buffer;
     let mut is_s_type_buffer = None;
-    let mut persistent_bucket_start_indices_buffer = None;
+    let mut persistent_bucket_buffer = None;
     let mut working_bucket_indices_buffer = None;
 
     if buffer_config.is_s_type_buffer_in_main_buffer {
@@ -269,15 +300,16 @@ pub fn instantiate_or_recover_buffers<'e, 'm: 'e, I: IndexStorage>(
     }
 
     if buffer_config.persistent_bucket_buffer_in_main_buffer {
-        let (remaining, buffer) =
-            remaining_main_buffer.split_at_mut(remaining_main_buffer.len() - num_buckets);
+        let (remaining, buffer) = remaining_main_buffer.split_at_mut(
+            remaining_main_buffer.len() - buffer_config.persistent_bucket_buffer_size,
+        );
 
         remaining_main_buffer = remaining;
 
         if buffer_request_mode == BufferRequestMode::Instatiate {
             buffer.fill(I::zero());
         }
-        persistent_bucket_start_indices_buffer = Some(buffer);
+        persistent_bucket_buffer = Some(buffer);
     }
 
     // the buffer that was popped before recursion needs to be pushed again
@@ -299,11 +331,12 @@ pub fn instantiate_or_recover_buffers<'e, 'm: 'e, I: IndexStorage>(
         }
         (true, false, true) => panic!("Unexpected internal bug in buffer instantiation"),
         (true, false, false) => {
-            [
-                persistent_bucket_start_indices_buffer,
-                working_bucket_indices_buffer,
-            ] = extra_buffers
-                .push_or_peek_two(num_buckets, num_buckets, buffer_request_mode)
+            [persistent_bucket_buffer, working_bucket_indices_buffer] = extra_buffers
+                .push_or_peek_two(
+                    buffer_config.persistent_bucket_buffer_size,
+                    num_buckets,
+                    buffer_request_mode,
+                )
                 .map(Some)
         }
         (false, true, true) => {
@@ -325,12 +358,12 @@ pub fn instantiate_or_recover_buffers<'e, 'm: 'e, I: IndexStorage>(
         (false, false, false) => {
             [
                 is_s_type_buffer,
-                persistent_bucket_start_indices_buffer,
+                persistent_bucket_buffer,
                 working_bucket_indices_buffer,
             ] = extra_buffers
                 .push_or_peek_three(
                     buffer_config.is_s_type_buffer_size,
-                    num_buckets,
+                    buffer_config.persistent_bucket_buffer_size,
                     num_buckets,
                     buffer_request_mode,
                 )
@@ -341,7 +374,7 @@ pub fn instantiate_or_recover_buffers<'e, 'm: 'e, I: IndexStorage>(
     Buffers {
         remaining_main_buffer_without_persistent_buffers: remaining_main_buffer,
         is_s_type_buffer: is_s_type_buffer.unwrap(),
-        persistent_bucket_start_indices_buffer: persistent_bucket_start_indices_buffer.unwrap(),
+        persistent_bucket_buffer: persistent_bucket_buffer.unwrap(),
         maybe_working_bucket_indices_buffer: working_bucket_indices_buffer,
     }
 }
@@ -417,11 +450,16 @@ mod tests {
         let main_buffer_len = 30;
         let num_buckets = 8;
 
-        let buffer_config =
-            BufferConfig::calculate::<usize>(text_len, main_buffer_len, num_buckets);
+        let buffer_config = BufferConfig::calculate::<usize>(
+            text_len,
+            main_buffer_len,
+            num_buckets,
+            BucketGranularity::Characters,
+        );
 
         let expected_buffer_config = BufferConfig {
             is_s_type_buffer_size: 1,
+            persistent_bucket_buffer_size: 8,
             is_s_type_buffer_in_main_buffer: true,
             persistent_bucket_buffer_in_main_buffer: true,
             working_bucket_buffer_in_main_buffer: false,
@@ -436,11 +474,16 @@ mod tests {
         let main_buffer_len = 36;
         let num_buckets = 8;
 
-        let buffer_config =
-            BufferConfig::calculate::<usize>(text_len, main_buffer_len, num_buckets);
+        let buffer_config = BufferConfig::calculate::<usize>(
+            text_len,
+            main_buffer_len,
+            num_buckets,
+            BucketGranularity::Characters,
+        );
 
         let expected_buffer_config = BufferConfig {
             is_s_type_buffer_size: 1,
+            persistent_bucket_buffer_size: 8,
             is_s_type_buffer_in_main_buffer: false,
             persistent_bucket_buffer_in_main_buffer: true,
             working_bucket_buffer_in_main_buffer: true,
@@ -448,4 +491,55 @@ mod tests {
 
         assert_eq!(buffer_config, expected_buffer_config);
     }
+
+    #[test]
+    fn test_buffer_config_calculate_type_buckets() {
+        let text_len = 20;
+        let main_buffer_len = 30;
+        let num_buckets = 8;
+
+        let buffer_config = BufferConfig::calculate::<usize>(
+            text_len,
+            main_buffer_len,
+            num_buckets,
+            BucketGranularity::CharactersAndTypes,
+        );
+
+        // the working bucket buffer would fit, but not without the persistent bucket buffer
+        let expected_buffer_config = BufferConfig {
+            is_s_type_buffer_size: 1,
+            persistent_bucket_buffer_size: 16,
+            is_s_type_buffer_in_main_buffer: true,
+            persistent_bucket_buffer_in_main_buffer: false,
+            working_bucket_buffer_in_main_buffer: false,
+        };
+
+        assert_eq!(buffer_config, expected_buffer_config);
+    }
+
+    #[test]
+    fn test_buffer_config_surplus_len_for_main_buffer_only() {
+        let text_len = 100;
+        let num_buckets = 8;
+
+        for bucket_granularity in [
+            BucketGranularity::Characters,
+            BucketGranularity::CharactersAndTypes,
+        ] {
+            let surplus_len = BufferConfig::surplus_len_for_main_buffer_only::<u8>(
+                text_len,
+                num_buckets,
+                bucket_granularity,
+            );
+
+            let buffer_config = BufferConfig::calculate::<u8>(
+                text_len,
+                text_len + surplus_len,
+                num_buckets,
+                bucket_granularity,
+            );
+
+            assert_eq!(buffer_config.num_extra_buffers(), 0);
+        }
+    }
 }
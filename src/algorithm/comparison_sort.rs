@@ -0,0 +1,23 @@
+use crate::{Character, IndexStorage};
+
+use num_traits::NumCast;
+
+// sorts the suffixes by directly comparing them. This has a worst case of O(n^2 log n) for highly
+// repetitive texts, but does not need any bucket arrays or buffer setup, which makes it faster than
+// SAIS for very small texts. Only texts below the small text threshold are sorted like this, which
+// bounds the worst case. expects the suffix array buffer to be of the same length as text
+pub fn construct_suffix_array<C: Character, I: IndexStorage>(
+    text: &[C],
+    suffix_array_buffer: &mut [I],
+) {
+    assert_eq!(text.len(), suffix_array_buffer.len());
+
+    for (suffix_index, entry) in suffix_array_buffer.iter_mut().enumerate() {
+        *entry = <I as NumCast>::from(suffix_index).unwrap();
+    }
+
+    // suffixes are never equal, so the unstable sort is fine
+    suffix_array_buffer.sort_unstable_by(|&first_suffix_index, &second_suffix_index| {
+        text[first_suffix_index.as_()..].cmp(&text[second_suffix_index.as_()..])
+    });
+}
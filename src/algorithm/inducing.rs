@@ -9,23 +9,19 @@ use num_traits::NumCast;
 pub fn induce_to_sort_lms_substrings<C: Character, I: IndexStorage>(
     suffix_array_buffer: &mut [I],
     bucket_start_indices: &[I],
+    maybe_s_type_bucket_start_indices: Option<&[I]>,
     working_bucket_indices_buffer: &mut [I],
     text_metadata: &TextMetadata<I>,
     text: &[C],
 ) {
-    working_bucket_indices_buffer.copy_from_slice(bucket_start_indices);
-
-    induce_from_virtual_sentinel(suffix_array_buffer, working_bucket_indices_buffer, text);
-
-    for (start, end) in buckets::iter_bucket_borders(bucket_start_indices, text.len()) {
-        induce_range_left_to_right(
-            num::range(start, end),
-            suffix_array_buffer,
-            working_bucket_indices_buffer,
-            text_metadata.is_s_type,
-            text,
-        );
-    }
+    induce_left_to_right(
+        suffix_array_buffer,
+        bucket_start_indices,
+        maybe_s_type_bucket_start_indices,
+        working_bucket_indices_buffer,
+        text_metadata.is_s_type,
+        text,
+    );
 
     buckets::write_bucket_end_indices_into_buffer(
         bucket_start_indices,
@@ -52,23 +48,19 @@ pub fn induce_to_sort_lms_substrings<C: Character, I: IndexStorage>(
 pub fn induce_to_finalize_suffix_array<C: Character, I: IndexStorage>(
     suffix_array_buffer: &mut [I],
     bucket_start_indices: &[I],
+    maybe_s_type_bucket_start_indices: Option<&[I]>,
     working_bucket_indices_buffer: &mut [I],
     text_metadata: &TextMetadata<I>,
     text: &[C],
 ) {
-    working_bucket_indices_buffer.copy_from_slice(bucket_start_indices);
-
-    induce_from_virtual_sentinel(suffix_array_buffer, working_bucket_indices_buffer, text);
-
-    for (start, end) in buckets::iter_bucket_borders(bucket_start_indices, text.len()) {
-        induce_range_left_to_right(
-            num::range(start, end),
-            suffix_array_buffer,
-            working_bucket_indices_buffer,
-            text_metadata.is_s_type,
-            text,
-        );
-    }
+    induce_left_to_right(
+        suffix_array_buffer,
+        bucket_start_indices,
+        maybe_s_type_bucket_start_indices,
+        working_bucket_indices_buffer,
+        text_metadata.is_s_type,
+        text,
+    );
 
     buckets::write_bucket_end_indices_into_buffer(
         bucket_start_indices,
@@ -90,6 +82,62 @@ pub fn induce_to_finalize_suffix_array<C: Character, I: IndexStorage>(
     // because the char before it is always L-type
 }
 
+// expects only LMS indices in the S-type parts of the buckets
+fn induce_left_to_right<C: Character, I: IndexStorage>(
+    suffix_array_buffer: &mut [I],
+    bucket_start_indices: &[I],
+    maybe_s_type_bucket_start_indices: Option<&[I]>,
+    working_bucket_indices_buffer: &mut [I],
+    is_s_type: &BitSlice<I>,
+    text: &[C],
+) {
+    working_bucket_indices_buffer.copy_from_slice(bucket_start_indices);
+
+    induce_from_virtual_sentinel(suffix_array_buffer, working_bucket_indices_buffer, text);
+
+    let bucket_borders = buckets::iter_bucket_borders(bucket_start_indices, text.len());
+
+    let Some(s_type_bucket_start_indices) = maybe_s_type_bucket_start_indices else {
+        for (start, end) in bucket_borders {
+            induce_range_left_to_right(
+                num::range(start, end),
+                suffix_array_buffer,
+                working_bucket_indices_buffer,
+                is_s_type,
+                text,
+            );
+        }
+
+        return;
+    };
+
+    for ((start, end), &s_type_start) in bucket_borders.zip(s_type_bucket_start_indices) {
+        induce_range_left_to_right(
+            num::range(start, s_type_start),
+            suffix_array_buffer,
+            working_bucket_indices_buffer,
+            is_s_type,
+            text,
+        );
+
+        // L-type suffixes are never induced into S-type parts of buckets. Therefore, in this scan, the
+        // S-type part only contains the LMS indices at its end and the empty entries before them can be skipped
+        let mut lms_start = end.as_();
+        while lms_start > s_type_start.as_() && suffix_array_buffer[lms_start - 1] != I::max_value()
+        {
+            lms_start -= 1;
+        }
+
+        induce_range_left_to_right(
+            num::range(<I as NumCast>::from(lms_start).unwrap(), end),
+            suffix_array_buffer,
+            working_bucket_indices_buffer,
+            is_s_type,
+            text,
+        );
+    }
+}
+
 // the virtual sentinel would normally be at first position of the suffix array
 fn induce_from_virtual_sentinel<C: Character, I: IndexStorage>(
     suffix_array_buffer: &mut [I],
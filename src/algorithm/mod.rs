@@ -1,8 +1,10 @@
 // #[cfg(test)]
 // mod tests;
 
+pub mod alphabet_compaction;
 mod buckets;
 pub mod buffer_management;
+pub mod comparison_sort;
 mod inducing;
 mod text_analysis;
 mod util;
@@ -15,15 +17,29 @@ use std::cmp;
 
 use text_analysis::TextMetadata;
 
+pub use buckets::BucketGranularity;
+
 // TODO SaisConfig for configuration of this algorithm that is not the public API
 
+// the buffers have one entry per bucket
+pub enum CharFrequencies<'a, I> {
+    // the chars are counted while the text is scanned
+    Count,
+    // like Count, the counts are additionally written into the buffer
+    CountInto(&'a mut [I]),
+    // the counts are known, the text is only scanned for the S/L-types
+    Known(&'a [I]),
+}
+
 // expects the main buffer to be of at least the same length as text
 // and the values at 0..text.len() of main_buffer to be NONE_VALUE
 pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     text: &[C],
     max_char: C,
+    bucket_granularity: BucketGranularity,
     main_buffer: &mut [I],
     extra_buffers: &mut BufferStack<I>,
+    char_frequencies: CharFrequencies<I>,
 ) {
     assert!(text.len() < <usize as NumCast>::from(I::max_value()).unwrap());
     assert!(text.len() < usize::MAX);
@@ -33,23 +49,94 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     }
 
     let num_buckets = max_char.rank() + 1;
-    let buffer_config = BufferConfig::calculate::<I>(text.len(), main_buffer.len(), num_buckets);
+    let buffer_config = BufferConfig::calculate::<I>(
+        text.len(),
+        main_buffer.len(),
+        num_buckets,
+        bucket_granularity,
+    );
+
+    let Buffers {
+        is_s_type_buffer,
+        persistent_bucket_buffer,
+        ..
+    } = buffer_management::instantiate_or_recover_buffers(
+        buffer_config,
+        main_buffer,
+        extra_buffers,
+        num_buckets,
+        BufferRequestMode::Instatiate,
+    );
+
+    let (persistent_bucket_start_indices_buffer, mut maybe_persistent_s_type_bucket_buffer) =
+        buckets::split_persistent_bucket_buffer(persistent_bucket_buffer, num_buckets);
+
+    // only the input text is scanned here. the reduced texts of the recursion levels are
+    // classified and counted while they are created
+    if let CharFrequencies::Known(known_char_frequencies) = char_frequencies {
+        persistent_bucket_start_indices_buffer.copy_from_slice(known_char_frequencies);
+
+        text_analysis::classify_s_l_types(
+            text,
+            maybe_persistent_s_type_bucket_buffer.as_deref_mut(),
+            is_s_type_buffer,
+        );
+    } else {
+        text_analysis::scan_for_counts_and_s_l_types(
+            text,
+            persistent_bucket_start_indices_buffer,
+            maybe_persistent_s_type_bucket_buffer.as_deref_mut(),
+            is_s_type_buffer,
+        );
+    }
+
+    // the counts are only available until they are turned into bucket indices
+    if let CharFrequencies::CountInto(char_frequencies_buffer) = char_frequencies {
+        char_frequencies_buffer.copy_from_slice(persistent_bucket_start_indices_buffer);
+    }
+
+    buckets::counts_into_bucket_start_indices(
+        persistent_bucket_start_indices_buffer,
+        maybe_persistent_s_type_bucket_buffer,
+    );
 
+    // the working bucket buffer is not needed for the scan, it is obtained again when recovering the buffers
+    buffer_management::setup_for_recursion(buffer_config, extra_buffers);
+
+    induced_sort_scanned_text(text, num_buckets, buffer_config, main_buffer, extra_buffers);
+}
+
+// expects the persistent buffers of buffer_config to be instantiated and filled with the S/L-types
+// and bucket start indices of the text. the working bucket buffer must not be on the extra buffer stack
+fn induced_sort_scanned_text<C: Character, I: IndexStorage>(
+    text: &[C],
+    num_buckets: usize,
+    buffer_config: BufferConfig,
+    main_buffer: &mut [I],
+    extra_buffers: &mut BufferStack<I>,
+) {
     let Buffers {
         remaining_main_buffer_without_persistent_buffers,
         // persistent means the buffer is kept during recursion, while the working buffer is reused
         // the s-type buffer is also persistent
         is_s_type_buffer,
-        persistent_bucket_start_indices_buffer,
+        persistent_bucket_buffer,
         maybe_working_bucket_indices_buffer,
     } = buffer_management::instantiate_or_recover_buffers(
         buffer_config,
         main_buffer,
         extra_buffers,
         num_buckets,
-        BufferRequestMode::Instatiate,
+        BufferRequestMode::Recover,
     );
 
+    let (persistent_bucket_start_indices_buffer, maybe_persistent_s_type_bucket_buffer) =
+        buckets::split_persistent_bucket_buffer(persistent_bucket_buffer, num_buckets);
+    let maybe_s_type_bucket_start_indices = maybe_persistent_s_type_bucket_buffer.as_deref();
+
+    let text_metadata =
+        TextMetadata::from_filled_buffer_and_parts(is_s_type_buffer, text[0].rank());
+
     // if the working buffer is allocated in the surplus main buffer, the value returned is None.
     // Then, this split needs to happen in this function to allow reobtaining the remaining main
     // buffer without persistent buffers later to use it fully in the recursion
@@ -66,14 +153,6 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
 
     let suffix_array_buffer = &mut final_remaining_main_buffer[..text.len()];
 
-    // TODO maybe skip this scan in recursion
-    let text_metadata = text_analysis::scan_for_counts_and_s_l_types(
-        text,
-        persistent_bucket_start_indices_buffer,
-        is_s_type_buffer,
-    );
-    buckets::counts_into_bucket_start_indices(persistent_bucket_start_indices_buffer);
-
     let num_lms_chars = buckets::place_text_order_lms_indices_into_buckets(
         suffix_array_buffer,
         persistent_bucket_start_indices_buffer,
@@ -85,19 +164,27 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     inducing::induce_to_sort_lms_substrings(
         suffix_array_buffer,
         persistent_bucket_start_indices_buffer,
+        maybe_s_type_bucket_start_indices,
         working_bucket_indices_buffer,
         &text_metadata,
         text,
     );
 
-    let (front, _, lms_indices) =
-        util::split_off_same_front_and_back_mut(suffix_array_buffer, num_lms_chars.as_());
-    // TODO maybe in the future this will be a step that gathers the scattered lms indices to the front
-    front.copy_from_slice(lms_indices);
+    // the sorted LMS indices stay at the end of the suffix array buffer. the names are written into
+    // the entries of its front half at the LMS indices divided by two (similar to libsais). This is
+    // sound, because there are no LMS chars at neighboring positions of the text, so the placement
+    // indices cannot collide and stay below text.len().div_ceil(2) <= text.len() - num_lms_chars.
+    let reduced_text_placement_len = text.len().div_ceil(2);
+    let (reduced_text_placement_buffer, _, sorted_lms_substring_indices) =
+        util::split_off_front_and_back_mut(
+            suffix_array_buffer,
+            reduced_text_placement_len,
+            num_lms_chars.as_(),
+        );
 
-    let num_different_names = create_reduced_text(
-        num_lms_chars,
-        remaining_main_buffer_without_persistent_buffers,
+    let num_different_names = name_lms_substrings(
+        sorted_lms_substring_indices,
+        reduced_text_placement_buffer,
         &text_metadata,
         text,
     );
@@ -105,21 +192,85 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     // text_metadata needs to be destructed, because it might borrow an extra buffer
     let (_, first_char_rank) = text_metadata.into_parts();
 
-    let (main_buffer_for_recursion, reduced_text) =
-        remaining_main_buffer_without_persistent_buffers.split_at_mut(
-            remaining_main_buffer_without_persistent_buffers.len() - num_lms_chars.as_(),
-        );
-
     buffer_management::setup_for_recursion(buffer_config, extra_buffers);
 
+    // the names are gathered to the front, where they form the reduced text. everything behind it
+    // is available to the recursion
     if num_different_names == num_lms_chars {
+        gather_reduced_text(
+            &mut remaining_main_buffer_without_persistent_buffers[..reduced_text_placement_len],
+        );
+        let (reduced_text, main_buffer_for_recursion) =
+            remaining_main_buffer_without_persistent_buffers.split_at_mut(num_lms_chars.as_());
+
         directly_construct_suffix_array(reduced_text, main_buffer_for_recursion);
     } else {
-        main_buffer_for_recursion[..num_lms_chars.as_()].fill(I::max_value());
+        let (reduced_text_placement_buffer, main_buffer_behind_placement_buffer) =
+            remaining_main_buffer_without_persistent_buffers
+                .split_at_mut(reduced_text_placement_len);
+
+        // the buffers of the reduced text metadata are filled while the names are gathered. So
+        // the surplus of the main buffer for the recursion that they may use is limited, such
+        // that they are placed behind the placement buffer, as well as behind the suffix array
+        // of the recursion
+        let reduced_text_len = num_lms_chars.as_();
+        let reduced_main_buffer_surplus_len = cmp::min(
+            main_buffer_behind_placement_buffer.len(),
+            reduced_text_placement_len + main_buffer_behind_placement_buffer.len()
+                - 2 * reduced_text_len,
+        );
+
+        let reduced_num_buckets = num_different_names.as_();
+        let reduced_buffer_config = BufferConfig::calculate::<I>(
+            reduced_text_len,
+            reduced_text_len + reduced_main_buffer_surplus_len,
+            reduced_num_buckets,
+            BucketGranularity::for_alphabet_size(reduced_num_buckets),
+        );
+
+        let Buffers {
+            is_s_type_buffer: reduced_is_s_type_buffer,
+            persistent_bucket_buffer: reduced_persistent_bucket_buffer,
+            ..
+        } = buffer_management::instantiate_or_recover_buffers(
+            reduced_buffer_config,
+            main_buffer_behind_placement_buffer,
+            extra_buffers,
+            reduced_num_buckets,
+            BufferRequestMode::Instatiate,
+        );
+
+        let (
+            reduced_persistent_bucket_start_indices_buffer,
+            mut maybe_reduced_persistent_s_type_bucket_buffer,
+        ) = buckets::split_persistent_bucket_buffer(
+            reduced_persistent_bucket_buffer,
+            reduced_num_buckets,
+        );
+
+        // the reduced text is classified and counted here, such that the recursion does not need
+        // to scan it again
+        text_analysis::gather_reduced_text_and_scan_for_counts_and_s_l_types(
+            reduced_text_placement_buffer,
+            reduced_persistent_bucket_start_indices_buffer,
+            maybe_reduced_persistent_s_type_bucket_buffer.as_deref_mut(),
+            reduced_is_s_type_buffer,
+        );
+        buckets::counts_into_bucket_start_indices(
+            reduced_persistent_bucket_start_indices_buffer,
+            maybe_reduced_persistent_s_type_bucket_buffer,
+        );
+
+        buffer_management::setup_for_recursion(reduced_buffer_config, extra_buffers);
 
-        suffix_array_induced_sort(
+        let (reduced_text, main_buffer_for_recursion) =
+            remaining_main_buffer_without_persistent_buffers.split_at_mut(reduced_text_len);
+        main_buffer_for_recursion[..reduced_text_len].fill(I::max_value());
+
+        induced_sort_scanned_text(
             reduced_text,
-            num_different_names - I::one(),
+            reduced_num_buckets,
+            reduced_buffer_config,
             main_buffer_for_recursion,
             extra_buffers,
         );
@@ -130,7 +281,7 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     let Buffers {
         remaining_main_buffer_without_persistent_buffers,
         is_s_type_buffer,
-        persistent_bucket_start_indices_buffer,
+        persistent_bucket_buffer,
         maybe_working_bucket_indices_buffer,
     } = buffer_management::instantiate_or_recover_buffers(
         buffer_config,
@@ -140,10 +291,18 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
         BufferRequestMode::Recover,
     );
 
+    let (persistent_bucket_start_indices_buffer, maybe_persistent_s_type_bucket_buffer) =
+        buckets::split_persistent_bucket_buffer(persistent_bucket_buffer, num_buckets);
+    let maybe_s_type_bucket_start_indices = maybe_persistent_s_type_bucket_buffer.as_deref();
+
     let text_metadata =
         TextMetadata::from_filled_buffer_and_parts(is_s_type_buffer, first_char_rank);
 
-    // reuse reduced text buffer for backtransformation table
+    // the suffix array of the reduced text is moved to the front, in place of the reduced text. It
+    // fits there together with the backtransformation table, because num_lms_chars <= text.len() / 2
+    remaining_main_buffer_without_persistent_buffers
+        .copy_within(num_lms_chars.as_()..2 * num_lms_chars.as_(), 0);
+
     let (main_buffer_for_recursion, backtransformation_table) =
         remaining_main_buffer_without_persistent_buffers.split_at_mut(
             remaining_main_buffer_without_persistent_buffers.len() - num_lms_chars.as_(),
@@ -181,6 +340,7 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     inducing::induce_to_finalize_suffix_array(
         suffix_array_buffer,
         persistent_bucket_start_indices_buffer,
+        maybe_s_type_bucket_start_indices,
         working_bucket_indices_buffer,
         &text_metadata,
         text,
@@ -189,36 +349,25 @@ pub fn suffix_array_induced_sort<C: Character, I: IndexStorage>(
     buffer_management::clean_up_extra_buffers(buffer_config, extra_buffers);
 }
 
-// reduced text is written to the end of the suffix array buffer
-// LMS indices at front of input suffix_array_buffer should be sorted according to
-// their LMS substrings (not necessarily according to their whole LMS suffixes)
-// returns number of different names in the text
-fn create_reduced_text<C: Character, I: IndexStorage>(
-    num_lms_chars: I,
-    remaining_main_buffer_without_persistent_buffers: &mut [I],
+// names are written into the placement buffer, at the positions of their LMS indices divided by two.
+// LMS indices should be sorted according to their LMS substrings (not necessarily according to their
+// whole LMS suffixes). returns number of different names in the text
+fn name_lms_substrings<C: Character, I: IndexStorage>(
+    sorted_lms_substring_indices: &[I],
+    reduced_text_placement_buffer: &mut [I],
     text_metadata: &TextMetadata<I>,
     text: &[C],
 ) -> I {
-    if num_lms_chars == I::zero() {
+    reduced_text_placement_buffer.fill(I::max_value());
+
+    if sorted_lms_substring_indices.is_empty() {
         return I::zero();
     }
 
-    let (sorted_lms_substring_indices, _, reduced_text_placement_buffer) =
-        util::split_off_front_and_back_mut(
-            remaining_main_buffer_without_persistent_buffers,
-            num_lms_chars.as_(),
-            text.len().div_ceil(2),
-        );
-    reduced_text_placement_buffer.fill(I::max_value());
-
     let mut current_name = I::zero();
     for index_of_sorted_lms_substring_indices in 0..sorted_lms_substring_indices.len() - 1 {
         let curr_lms_substring_index =
             sorted_lms_substring_indices[index_of_sorted_lms_substring_indices];
-
-        // This transformation allows to only use a buffer of half the text size when placing the lexicographical
-        // names into a buffer. It is sound because there cannot be LMS chars are directly neighboring positions
-        // of the text and therefore, shifting to the right by one cannot lead to a collision of indices.
         let placement_index = curr_lms_substring_index >> 1;
 
         reduced_text_placement_buffer[placement_index.as_()] = current_name;
@@ -239,22 +388,27 @@ fn create_reduced_text<C: Character, I: IndexStorage>(
     let last_placement_index = *sorted_lms_substring_indices.last().unwrap() >> 1;
     reduced_text_placement_buffer[last_placement_index.as_()] = current_name;
 
-    let mut write_index = reduced_text_placement_buffer.len() - 1;
-    for read_index in (0..reduced_text_placement_buffer.len()).rev() {
+    current_name + I::one()
+}
+
+// moves the names to the front of the placement buffer in text order, such that they form the
+// reduced text. a name is never moved behind its own position, so this works in place
+fn gather_reduced_text<I: IndexStorage>(reduced_text_placement_buffer: &mut [I]) {
+    let mut write_index = 0;
+
+    for read_index in 0..reduced_text_placement_buffer.len() {
         let maybe_lms_substring_name = reduced_text_placement_buffer[read_index];
 
         if maybe_lms_substring_name != I::max_value() {
             reduced_text_placement_buffer[write_index] = maybe_lms_substring_name;
-            write_index -= 1;
+            write_index += 1;
         }
     }
-
-    current_name + I::one()
 }
 
 // base case of recursion. this works, because the reduced text exclusively contains unique characters
 fn directly_construct_suffix_array<I: IndexStorage>(
-    reduced_text: &mut [I],
+    reduced_text: &[I],
     main_buffer_for_recursion: &mut [I],
 ) {
     for (reduced_text_suffix_index, &reduced_text_char) in reduced_text.iter().enumerate() {
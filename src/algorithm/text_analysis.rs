@@ -5,7 +5,13 @@ use std::cmp::Ordering;
 use bitvec::slice::BitSlice;
 
 // TODO: use first char rank
-// TODO: 3 levels of bucket detail: just chars, chars + lms chars, chars + lms chars + 2-mers
+
+#[cfg(test)]
+thread_local! {
+    // the number of whole text scans, such that tests can check that reduced texts are not
+    // scanned again after they are gathered
+    static NUM_TEXT_SCANS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
 
 pub struct TextMetadata<'a, I: IndexStorage> {
     pub is_s_type: &'a mut BitSlice<I>,
@@ -34,11 +40,16 @@ impl<'a, I: IndexStorage> TextMetadata<'a, I> {
     }
 }
 
+// if a buffer for the L-type counts is given, the L-type chars are additionally counted per bucket
 pub fn scan_for_counts_and_s_l_types<'a, C: Character, I: IndexStorage>(
     text: &[C],
     persistent_bucket_start_indices_buffer: &mut [I],
+    mut maybe_l_type_counts_buffer: Option<&mut [I]>,
     is_s_type_buffer: &'a mut [I],
 ) -> TextMetadata<'a, I> {
+    #[cfg(test)]
+    NUM_TEXT_SCANS.set(NUM_TEXT_SCANS.get() + 1);
+
     let is_s_type = BitSlice::from_slice_mut(is_s_type_buffer);
 
     // sentinel is by definiton S-type and the smallest character
@@ -57,6 +68,11 @@ pub fn scan_for_counts_and_s_l_types<'a, C: Character, I: IndexStorage>(
 
         is_s_type.set(text_index, current_char_is_s_type);
 
+        if !current_char_is_s_type && let Some(l_type_counts) = &mut maybe_l_type_counts_buffer {
+            let entry = &mut l_type_counts[char.rank()];
+            *entry = *entry + I::one();
+        }
+
         if text_index == 0 {
             break;
         }
@@ -71,3 +87,231 @@ pub fn scan_for_counts_and_s_l_types<'a, C: Character, I: IndexStorage>(
         first_char_rank,
     }
 }
+
+// for texts with known character counts, only the types are determined. Without the counting,
+// the loop body is a branch free comparison with the next character.
+// if a buffer for the L-type counts is given, the L-type chars are still counted per bucket
+pub fn classify_s_l_types<'a, C: Character, I: IndexStorage>(
+    text: &[C],
+    maybe_l_type_counts_buffer: Option<&mut [I]>,
+    is_s_type_buffer: &'a mut [I],
+) -> TextMetadata<'a, I> {
+    #[cfg(test)]
+    NUM_TEXT_SCANS.set(NUM_TEXT_SCANS.get() + 1);
+
+    let is_s_type = BitSlice::from_slice_mut(is_s_type_buffer);
+
+    // the closures are separate instantiations, such that the loop has no branch for the option
+    match maybe_l_type_counts_buffer {
+        Some(l_type_counts) => classify_with(text, is_s_type, |char: &C, char_is_s_type| {
+            if !char_is_s_type {
+                let entry = &mut l_type_counts[char.rank()];
+                *entry = *entry + I::one();
+            }
+        }),
+        None => classify_with(text, is_s_type, |_: &C, _| {}),
+    }
+
+    let first_char_rank = text[0].rank();
+
+    TextMetadata {
+        is_s_type,
+        first_char_rank,
+    }
+}
+
+fn classify_with<C: Character, I: IndexStorage>(
+    text: &[C],
+    is_s_type: &mut BitSlice<I>,
+    mut on_classified_char: impl FnMut(&C, bool),
+) {
+    // sentinel is by definiton S-type and the smallest character, so the last char is L-type
+    is_s_type.set(text.len(), true);
+    is_s_type.set(text.len() - 1, false);
+    on_classified_char(&text[text.len() - 1], false);
+
+    let mut next_char_is_s_type = false;
+
+    for (text_index, chars) in text.windows(2).enumerate().rev() {
+        let current_char_is_s_type =
+            (chars[0] < chars[1]) | ((chars[0] == chars[1]) & next_char_is_s_type);
+
+        is_s_type.set(text_index, current_char_is_s_type);
+        on_classified_char(&chars[0], current_char_is_s_type);
+
+        next_char_is_s_type = current_char_is_s_type;
+    }
+}
+
+// moves the names of a reduced text from the placement buffer (where unused entries are
+// NONE_VALUE) to its front, in text order, and counts and classifies them in the same pass.
+// The type of a name is only known at the next different name, so every run of equal names is
+// classified when it ends. expects the metadata buffers to be filled with zeroes and returns
+// the length of the reduced text
+pub fn gather_reduced_text_and_scan_for_counts_and_s_l_types<I: IndexStorage>(
+    reduced_text_placement_buffer: &mut [I],
+    persistent_bucket_start_indices_buffer: &mut [I],
+    mut maybe_l_type_counts_buffer: Option<&mut [I]>,
+    is_s_type_buffer: &mut [I],
+) -> usize {
+    let is_s_type = BitSlice::<I>::from_slice_mut(is_s_type_buffer);
+
+    let mut classify_run = |run_start: usize, run_end: usize, name: I, run_is_s_type: bool| {
+        if run_is_s_type {
+            is_s_type[run_start..run_end].fill(true);
+        } else if let Some(l_type_counts) = &mut maybe_l_type_counts_buffer {
+            let entry = &mut l_type_counts[name.as_()];
+            *entry = *entry + <I as num_traits::NumCast>::from(run_end - run_start).unwrap();
+        }
+    };
+
+    let mut write_index = 0;
+    let mut run_start = 0;
+
+    for read_index in 0..reduced_text_placement_buffer.len() {
+        let name = reduced_text_placement_buffer[read_index];
+
+        if name == I::max_value() {
+            continue;
+        }
+
+        reduced_text_placement_buffer[write_index] = name;
+
+        let entry = &mut persistent_bucket_start_indices_buffer[name.as_()];
+        *entry = *entry + I::one();
+
+        if write_index > 0 {
+            let previous_name = reduced_text_placement_buffer[write_index - 1];
+
+            if previous_name != name {
+                classify_run(run_start, write_index, previous_name, previous_name < name);
+                run_start = write_index;
+            }
+        }
+
+        write_index += 1;
+    }
+
+    // the last run is followed by the sentinel, which is by definition S-type and the smallest
+    // character
+    if write_index > 0 {
+        let last_name = reduced_text_placement_buffer[write_index - 1];
+        classify_run(run_start, write_index, last_name, false);
+    }
+
+    is_s_type.set(write_index, true);
+
+    write_index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classification_matches_scan() {
+        for text in [
+            &b"ababcabcabba"[..],
+            b"a",
+            b"aaaa",
+            b"dcba",
+            b"abcd",
+            b"mississippi",
+        ] {
+            let is_s_type_buffer_len = (text.len() + 1).div_ceil(u32::BITS as usize);
+
+            let mut scanned_counts = [0u32; 256];
+            let mut scanned_l_type_counts = [0u32; 256];
+            let mut scanned_is_s_type_buffer = vec![0u32; is_s_type_buffer_len];
+            scan_for_counts_and_s_l_types(
+                text,
+                &mut scanned_counts,
+                Some(&mut scanned_l_type_counts),
+                &mut scanned_is_s_type_buffer,
+            );
+
+            let mut classified_l_type_counts = [0u32; 256];
+            let mut classified_is_s_type_buffer = vec![0u32; is_s_type_buffer_len];
+            classify_s_l_types(
+                text,
+                Some(&mut classified_l_type_counts),
+                &mut classified_is_s_type_buffer,
+            );
+
+            assert_eq!(classified_is_s_type_buffer, scanned_is_s_type_buffer);
+            assert_eq!(classified_l_type_counts, scanned_l_type_counts);
+
+            let mut classified_is_s_type_buffer = vec![0u32; is_s_type_buffer_len];
+            classify_s_l_types::<u8, u32>(text, None, &mut classified_is_s_type_buffer);
+
+            assert_eq!(classified_is_s_type_buffer, scanned_is_s_type_buffer);
+        }
+    }
+
+    #[test]
+    fn test_gathering_matches_scan() {
+        let none = u32::MAX;
+
+        for placed_names in [
+            &[none, 2, none, 1, 1, none, 0, 2, 2, 3][..],
+            &[0],
+            &[1, none, 0, 0, 0, none],
+            &[0, 1, 2, none, none],
+            &[2, 2, 2],
+        ] {
+            let reduced_text: Vec<_> = placed_names
+                .iter()
+                .copied()
+                .filter(|&name| name != none)
+                .collect();
+            let num_names = *reduced_text.iter().max().unwrap() as usize + 1;
+            let is_s_type_buffer_len = (reduced_text.len() + 1).div_ceil(u32::BITS as usize);
+
+            let mut scanned_counts = vec![0u32; num_names];
+            let mut scanned_l_type_counts = vec![0u32; num_names];
+            let mut scanned_is_s_type_buffer = vec![0u32; is_s_type_buffer_len];
+            scan_for_counts_and_s_l_types(
+                &reduced_text,
+                &mut scanned_counts,
+                Some(&mut scanned_l_type_counts),
+                &mut scanned_is_s_type_buffer,
+            );
+
+            let mut placement_buffer = placed_names.to_vec();
+            let mut gathered_counts = vec![0u32; num_names];
+            let mut gathered_l_type_counts = vec![0u32; num_names];
+            let mut gathered_is_s_type_buffer = vec![0u32; is_s_type_buffer_len];
+            let reduced_text_len = gather_reduced_text_and_scan_for_counts_and_s_l_types(
+                &mut placement_buffer,
+                &mut gathered_counts,
+                Some(&mut gathered_l_type_counts),
+                &mut gathered_is_s_type_buffer,
+            );
+
+            assert_eq!(&placement_buffer[..reduced_text_len], reduced_text);
+            assert_eq!(gathered_counts, scanned_counts);
+            assert_eq!(gathered_l_type_counts, scanned_l_type_counts);
+            assert_eq!(gathered_is_s_type_buffer, scanned_is_s_type_buffer);
+        }
+    }
+
+    #[test]
+    fn test_only_the_input_text_is_scanned() {
+        // many equal LMS substrings lead to several recursion levels
+        let text: Vec<_> = b"abcab".iter().copied().cycle().take(5000).collect();
+
+        for bucket_granularity in [
+            crate::BucketGranularity::Characters,
+            crate::BucketGranularity::CharactersAndTypes,
+        ] {
+            NUM_TEXT_SCANS.set(0);
+
+            let suffix_array = crate::SaisBuilder::<u8, u32>::new()
+                .with_bucket_granularity(bucket_granularity)
+                .construct_suffix_array(&text);
+
+            assert_eq!(suffix_array.len(), text.len());
+            assert_eq!(NUM_TEXT_SCANS.get(), 1);
+        }
+    }
+}
@@ -8,13 +8,6 @@ pub fn split_off_front_and_back_mut<T>(
     (front, mid, back)
 }
 
-pub fn split_off_same_front_and_back_mut<T>(
-    slice: &mut [T],
-    offset: usize,
-) -> (&mut [T], &mut [T], &mut [T]) {
-    split_off_front_and_back_mut(slice, offset, offset)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,10 +19,5 @@ mod tests {
         assert_eq!(one, [1, 2]);
         assert_eq!(two, [3, 4, 5, 6]);
         assert_eq!(three, [7, 8, 9]);
-
-        let (one, two, three) = split_off_same_front_and_back_mut(&mut array, 4);
-        assert_eq!(one, [1, 2, 3, 4]);
-        assert_eq!(two, [5]);
-        assert_eq!(three, [6, 7, 8, 9]);
     }
 }
@@ -0,0 +1,476 @@
+// The sais-drum command line tool. Texts are read as plain bytes, suffix arrays are stored in the
+// file format of sais_drum::format. Timings and the peak heap usage are reported on stderr,
+// results on stdout.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use sais_drum::format::{SuffixArrayFile, SuffixArrayFileHeader, SuffixArrayFileWriter};
+use sais_drum::{IndexStorage, SaisBuilder, bwt, lcp, search, verify};
+
+type CliResult = Result<(), Box<dyn Error>>;
+
+#[global_allocator]
+static ALLOCATOR: PeakTrackingAllocator = PeakTrackingAllocator;
+
+#[derive(Parser)]
+#[command(name = "sais-drum", version, about = "Build and query suffix arrays")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Build the suffix array of a text file")]
+    Build {
+        text: PathBuf,
+        output: PathBuf,
+        #[arg(long, value_enum, default_value_t = IndexWidth::Auto)]
+        index_width: IndexWidth,
+        #[arg(long, help = "Also store the LCP array")]
+        lcp: bool,
+        #[arg(long, help = "Also store the BWT")]
+        bwt: bool,
+    },
+    #[command(about = "Write the BWT of a text file and print its primary index")]
+    Bwt {
+        text: PathBuf,
+        output: PathBuf,
+        #[arg(long, help = "Use this suffix array instead of building one")]
+        suffix_array: Option<PathBuf>,
+    },
+    #[command(about = "Write the LCP array of a text file as little endian integers")]
+    Lcp {
+        text: PathBuf,
+        output: PathBuf,
+        #[arg(long, help = "Use this suffix array instead of building one")]
+        suffix_array: Option<PathBuf>,
+    },
+    #[command(about = "Check a suffix array file against its text")]
+    Verify {
+        text: PathBuf,
+        suffix_array: PathBuf,
+    },
+    #[command(about = "Count or locate patterns in a text")]
+    Search {
+        text: PathBuf,
+        suffix_array: PathBuf,
+        #[arg(required = true)]
+        patterns: Vec<String>,
+        #[arg(long, help = "Print the positions of the occurrences")]
+        locate: bool,
+        #[arg(long, help = "Print at most this many positions per pattern")]
+        max_locations: Option<usize>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum IndexWidth {
+    // the smallest width that can represent all positions of the text
+    Auto,
+    #[value(name = "16")]
+    Bits16,
+    #[value(name = "32")]
+    Bits32,
+    #[value(name = "64")]
+    Bits64,
+}
+
+impl IndexWidth {
+    fn num_bytes(self, text_len: usize) -> usize {
+        match self {
+            // the maximum value of the index type is reserved by the algorithm
+            IndexWidth::Auto if text_len < u16::MAX as usize => 2,
+            IndexWidth::Auto if (text_len as u64) < u32::MAX as u64 => 4,
+            IndexWidth::Auto => 8,
+            IndexWidth::Bits16 => 2,
+            IndexWidth::Bits32 => 4,
+            IndexWidth::Bits64 => 8,
+        }
+    }
+}
+
+// calls a generic function with the index type that has the given width in bytes
+macro_rules! with_index_type {
+    ($num_bytes:expr, $function:ident($($argument:expr),*)) => {
+        match $num_bytes {
+            2 => $function::<u16>($($argument),*),
+            4 => $function::<u32>($($argument),*),
+            8 => $function::<u64>($($argument),*),
+            num_bytes => Err(format!("unsupported index width of {num_bytes} bytes").into()),
+        }
+    };
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let start = Instant::now();
+
+    let result = match cli.command {
+        Command::Build {
+            text,
+            output,
+            index_width,
+            lcp,
+            bwt,
+        } => build(&text, &output, index_width, lcp, bwt),
+        Command::Bwt {
+            text,
+            output,
+            suffix_array,
+        } => write_bwt(&text, &output, suffix_array.as_deref()),
+        Command::Lcp {
+            text,
+            output,
+            suffix_array,
+        } => write_lcp(&text, &output, suffix_array.as_deref()),
+        Command::Verify { text, suffix_array } => verify(&text, &suffix_array),
+        Command::Search {
+            text,
+            suffix_array,
+            patterns,
+            locate,
+            max_locations,
+        } => search(
+            &text,
+            &suffix_array,
+            &patterns,
+            locate.then_some(max_locations.unwrap_or(usize::MAX)),
+        ),
+    };
+
+    eprintln!("total time: {:.3?}", start.elapsed());
+    eprintln!("peak heap memory: {}", format_bytes(ALLOCATOR.peak()));
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn build(
+    text_path: &Path,
+    output_path: &Path,
+    index_width: IndexWidth,
+    with_lcp: bool,
+    with_bwt: bool,
+) -> CliResult {
+    let text = read_text(text_path)?;
+    let num_bytes = index_width.num_bytes(text.len());
+
+    // the maximum value of the index type is reserved by the algorithm
+    if text.len() as u128 >= (1u128 << (8 * num_bytes)) - 1 {
+        return Err(format!(
+            "text of {} characters does not fit {}-bit indices",
+            text.len(),
+            8 * num_bytes
+        )
+        .into());
+    }
+
+    with_index_type!(
+        num_bytes,
+        build_with_index_type(&text, output_path, with_lcp, with_bwt)
+    )
+}
+
+fn build_with_index_type<I: IndexStorage>(
+    text: &[u8],
+    output_path: &Path,
+    with_lcp: bool,
+    with_bwt: bool,
+) -> CliResult {
+    let suffix_array = construct_suffix_array::<I>(text);
+
+    let lcp = with_lcp.then(|| {
+        timed("LCP construction", || {
+            lcp::construct_lcp_array(text, &suffix_array)
+        })
+    });
+    let bwt = with_bwt.then(|| {
+        timed("BWT construction", || {
+            bwt::construct_bwt(text, &suffix_array)
+        })
+    });
+
+    let mut writer = SuffixArrayFileWriter::new(text, &suffix_array);
+    if let Some(lcp) = &lcp {
+        writer.with_lcp(lcp);
+    }
+    if let Some((bwt, primary_index)) = &bwt {
+        writer.with_bwt(bwt, *primary_index as u64);
+    }
+
+    timed("writing the output", || writer.write_to_file(output_path))?;
+
+    Ok(())
+}
+
+fn write_bwt(text_path: &Path, output_path: &Path, suffix_array_path: Option<&Path>) -> CliResult {
+    let text = read_text(text_path)?;
+
+    with_index_type!(
+        index_width_for(&text, suffix_array_path)?,
+        write_bwt_with_index_type(&text, output_path, suffix_array_path)
+    )
+}
+
+fn write_bwt_with_index_type<I: IndexStorage>(
+    text: &[u8],
+    output_path: &Path,
+    suffix_array_path: Option<&Path>,
+) -> CliResult {
+    let suffix_array = load_or_construct_suffix_array::<I>(text, suffix_array_path)?;
+    let (bwt, primary_index) = timed("BWT construction", || {
+        bwt::construct_bwt(text, &suffix_array)
+    });
+
+    timed("writing the output", || fs::write(output_path, &bwt))?;
+    println!("primary index: {primary_index}");
+
+    Ok(())
+}
+
+fn write_lcp(text_path: &Path, output_path: &Path, suffix_array_path: Option<&Path>) -> CliResult {
+    let text = read_text(text_path)?;
+
+    with_index_type!(
+        index_width_for(&text, suffix_array_path)?,
+        write_lcp_with_index_type(&text, output_path, suffix_array_path)
+    )
+}
+
+fn write_lcp_with_index_type<I: IndexStorage>(
+    text: &[u8],
+    output_path: &Path,
+    suffix_array_path: Option<&Path>,
+) -> CliResult {
+    let suffix_array = load_or_construct_suffix_array::<I>(text, suffix_array_path)?;
+    let lcp = timed("LCP construction", || {
+        lcp::construct_lcp_array(text, &suffix_array)
+    });
+
+    timed("writing the output", || {
+        let mut writer = BufWriter::new(File::create(output_path)?);
+
+        for value in lcp {
+            writer.write_all(&value.to_u64().unwrap().to_le_bytes()[..size_of::<I>()])?;
+        }
+
+        writer.flush()
+    })?;
+
+    Ok(())
+}
+
+fn verify(text_path: &Path, suffix_array_path: &Path) -> CliResult {
+    let text = read_text(text_path)?;
+    let header = SuffixArrayFileHeader::read_from_file(suffix_array_path)?;
+
+    if !header.matches_text(&text) {
+        return Err("the suffix array file was built for a different text".into());
+    }
+
+    with_index_type!(
+        header.index_width as usize,
+        verify_with_index_type(&text, suffix_array_path)
+    )
+}
+
+fn verify_with_index_type<I: IndexStorage>(text: &[u8], suffix_array_path: &Path) -> CliResult {
+    let suffix_array = read_suffix_array::<I>(text, suffix_array_path)?;
+
+    timed("verification", || {
+        verify::verify_suffix_array(text, &suffix_array)
+    })?;
+
+    println!("the suffix array is valid");
+
+    Ok(())
+}
+
+fn search(
+    text_path: &Path,
+    suffix_array_path: &Path,
+    patterns: &[String],
+    max_locations: Option<usize>,
+) -> CliResult {
+    let text = read_text(text_path)?;
+    let header = SuffixArrayFileHeader::read_from_file(suffix_array_path)?;
+
+    with_index_type!(
+        header.index_width as usize,
+        search_with_index_type(&text, suffix_array_path, patterns, max_locations)
+    )
+}
+
+// prints one line per pattern with the pattern, the number of occurrences and optionally the
+// positions, separated by tabs
+fn search_with_index_type<I: IndexStorage>(
+    text: &[u8],
+    suffix_array_path: &Path,
+    patterns: &[String],
+    max_locations: Option<usize>,
+) -> CliResult {
+    let suffix_array = read_suffix_array::<I>(text, suffix_array_path)?;
+    let mut output = std::io::stdout().lock();
+
+    let search_start = Instant::now();
+
+    for pattern in patterns {
+        let range = search::pattern_range(text, &suffix_array, pattern.as_bytes());
+        write!(output, "{pattern}\t{}", range.len())?;
+
+        if let Some(max_locations) = max_locations {
+            let mut positions: Vec<_> = suffix_array[range]
+                .iter()
+                .map(|&position| position.as_())
+                .collect();
+            positions.sort_unstable();
+
+            for position in positions.into_iter().take(max_locations) {
+                write!(output, "\t{position}")?;
+            }
+        }
+
+        writeln!(output)?;
+    }
+
+    eprintln!("search: {:.3?}", search_start.elapsed());
+
+    Ok(())
+}
+
+fn read_text(path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
+    let text = timed("reading the text", || fs::read(path))
+        .map_err(|error| format!("could not read {}: {error}", path.display()))?;
+
+    Ok(text)
+}
+
+fn construct_suffix_array<I: IndexStorage>(text: &[u8]) -> Vec<I> {
+    timed("suffix array construction", || {
+        SaisBuilder::<u8, I>::new().construct_suffix_array(text)
+    })
+}
+
+fn read_suffix_array<I: IndexStorage>(
+    text: &[u8],
+    suffix_array_path: &Path,
+) -> Result<Vec<I>, Box<dyn Error>> {
+    let file = timed("reading the suffix array", || {
+        SuffixArrayFile::<u8, I>::read_from_file(suffix_array_path)
+    })?;
+
+    if !file.header().matches_text(text) {
+        return Err("the suffix array file was built for a different text".into());
+    }
+
+    Ok(file.into_suffix_array())
+}
+
+fn load_or_construct_suffix_array<I: IndexStorage>(
+    text: &[u8],
+    suffix_array_path: Option<&Path>,
+) -> Result<Vec<I>, Box<dyn Error>> {
+    match suffix_array_path {
+        Some(path) => read_suffix_array(text, path),
+        None => Ok(construct_suffix_array(text)),
+    }
+}
+
+fn index_width_for(text: &[u8], suffix_array_path: Option<&Path>) -> Result<usize, Box<dyn Error>> {
+    match suffix_array_path {
+        Some(path) => Ok(SuffixArrayFileHeader::read_from_file(path)?.index_width as usize),
+        None => Ok(IndexWidth::Auto.num_bytes(text.len())),
+    }
+}
+
+fn timed<T>(step: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    eprintln!("{step}: {:.3?}", start.elapsed());
+
+    result
+}
+
+fn format_bytes(num_bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = num_bytes as f64;
+    let mut unit_index = 0;
+
+    while value >= 1024.0 && unit_index < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit_index += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit_index])
+}
+
+// wraps the system allocator and records the largest number of bytes that were allocated at
+// the same time
+struct PeakTrackingAllocator;
+
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+impl PeakTrackingAllocator {
+    fn peak(&self) -> usize {
+        PEAK_BYTES.load(Ordering::Relaxed)
+    }
+
+    fn record_allocation(num_bytes: usize) {
+        let current = CURRENT_BYTES.fetch_add(num_bytes, Ordering::Relaxed) + num_bytes;
+        PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_deallocation(num_bytes: usize) {
+        CURRENT_BYTES.fetch_sub(num_bytes, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: all calls are forwarded to the system allocator
+unsafe impl GlobalAlloc for PeakTrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            Self::record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            Self::record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        Self::record_deallocation(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            Self::record_deallocation(layout.size());
+            Self::record_allocation(new_size);
+        }
+        new_ptr
+    }
+}
@@ -0,0 +1,69 @@
+use crate::IndexStorage;
+
+// the Burrows-Wheeler transform of the text with a virtual sentinel, in the layout of libsais: the
+// sentinel itself is left out of the output and its position in the full transform is returned
+// as the primary index. The first character is always the last one of the text, because the
+// sentinel suffix is the smallest.
+pub fn construct_bwt<C: Copy, I: IndexStorage>(text: &[C], suffix_array: &[I]) -> (Vec<C>, usize) {
+    assert_eq!(text.len(), suffix_array.len());
+
+    let Some(&last_char) = text.last() else {
+        return (Vec::new(), 0);
+    };
+
+    let mut bwt = Vec::with_capacity(text.len());
+    bwt.push(last_char);
+    let mut primary_index = 0;
+
+    for &suffix_index in suffix_array {
+        let suffix_index: usize = suffix_index.as_();
+
+        if suffix_index == 0 {
+            primary_index = bwt.len();
+        } else {
+            bwt.push(text[suffix_index - 1]);
+        }
+    }
+
+    (bwt, primary_index)
+}
+
+// reconstructs the text from the output of construct_bwt
+pub fn invert_bwt<C: Copy + Ord>(bwt: &[C], primary_index: usize) -> Vec<C> {
+    if bwt.is_empty() {
+        return Vec::new();
+    }
+
+    assert!(primary_index >= 1 && primary_index <= bwt.len());
+
+    // the rows of the full transform are the bwt positions with the sentinel row inserted
+    // at primary_index. The LF mapping is computed by stable sorting of the characters.
+    let mut rows_by_char: Vec<_> = (0..bwt.len()).collect();
+    rows_by_char.sort_by_key(|&position| bwt[position]);
+
+    let full_row = |position: usize| {
+        if position < primary_index {
+            position
+        } else {
+            position + 1
+        }
+    };
+
+    let mut lf = vec![0; bwt.len() + 1];
+    for (rank, &position) in rows_by_char.iter().enumerate() {
+        lf[full_row(position)] = rank + 1;
+    }
+
+    // the sentinel row precedes the first suffix, the text is read backwards from there
+    let mut text = Vec::with_capacity(bwt.len());
+    let mut row = 0;
+
+    for _ in 0..bwt.len() {
+        let position = if row < primary_index { row } else { row - 1 };
+        text.push(bwt[position]);
+        row = lf[row];
+    }
+
+    text.reverse();
+    text
+}
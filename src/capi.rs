@@ -0,0 +1,206 @@
+// C functions with the signatures and semantics of libsais, such that sais-drum can be linked
+// into C and C++ programs instead of it. The declarations are in include/sais_drum.h, a static or
+// dynamic library can be built with e.g.
+//
+//   cargo rustc --release --lib --features capi --crate-type staticlib
+//
+// As in libsais, the suffix array buffers have to provide n + fs values, where the fs free
+// values are used as additional working space. The functions return -1 for invalid arguments.
+// All pointers have to be valid for the given lengths, freq may be null.
+
+// the parameter names of libsais are kept for the header, the safety contract is the one of
+// libsais and described above
+#![allow(non_snake_case, clippy::missing_safety_doc)]
+
+use std::slice;
+
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage, SaisBuilder};
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn libsais(
+    T: *const u8,
+    SA: *mut i32,
+    n: i32,
+    fs: i32,
+    freq: *mut i32,
+) -> i32 {
+    // SAFETY: the caller has to uphold the contract of libsais
+    unsafe {
+        construct_suffix_array::<u8, u32>(T, SA.cast(), n as i64, fs as i64, freq.cast()) as i32
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn libsais16(
+    T: *const u16,
+    SA: *mut i32,
+    n: i32,
+    fs: i32,
+    freq: *mut i32,
+) -> i32 {
+    // SAFETY: the caller has to uphold the contract of libsais
+    unsafe {
+        construct_suffix_array::<u16, u32>(T, SA.cast(), n as i64, fs as i64, freq.cast()) as i32
+    }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn libsais64(
+    T: *const u8,
+    SA: *mut i64,
+    n: i64,
+    fs: i64,
+    freq: *mut i64,
+) -> i64 {
+    // SAFETY: the caller has to uphold the contract of libsais
+    unsafe { construct_suffix_array::<u8, u64>(T, SA.cast(), n, fs, freq.cast()) }
+}
+
+// returns the primary index of the BWT. U may be the same buffer as T.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn libsais_bwt(
+    T: *const u8,
+    U: *mut u8,
+    A: *mut i32,
+    n: i32,
+    fs: i32,
+    freq: *mut i32,
+) -> i32 {
+    // SAFETY: the caller has to uphold the contract of libsais
+    unsafe { construct_bwt::<u8, u32>(T, U, A.cast(), n as i64, fs as i64, freq.cast()) as i32 }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn libsais16_bwt(
+    T: *const u16,
+    U: *mut u16,
+    A: *mut i32,
+    n: i32,
+    fs: i32,
+    freq: *mut i32,
+) -> i32 {
+    // SAFETY: the caller has to uphold the contract of libsais
+    unsafe { construct_bwt::<u16, u32>(T, U, A.cast(), n as i64, fs as i64, freq.cast()) as i32 }
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn libsais64_bwt(
+    T: *const u8,
+    U: *mut u8,
+    A: *mut i64,
+    n: i64,
+    fs: i64,
+    freq: *mut i64,
+) -> i64 {
+    // SAFETY: the caller has to uphold the contract of libsais
+    unsafe { construct_bwt::<u8, u64>(T, U, A.cast(), n, fs, freq.cast()) }
+}
+
+fn checked_lengths(n: i64, fs: i64) -> Option<(usize, usize)> {
+    let text_len = usize::try_from(n).ok()?;
+    let free_space_len = usize::try_from(fs).ok()?;
+    text_len.checked_add(free_space_len)?;
+
+    Some((text_len, free_space_len))
+}
+
+// SAFETY: text has to point to n characters, suffix_array to n + fs values and freq has to be
+// null or point to one value per character of the alphabet of C
+unsafe fn construct_suffix_array<C: Character + IndexStorage, I: IndexStorage>(
+    text: *const C,
+    suffix_array: *mut I,
+    n: i64,
+    fs: i64,
+    freq: *mut I,
+) -> i64 {
+    let Some((text_len, free_space_len)) = checked_lengths(n, fs) else {
+        return -1;
+    };
+
+    if text.is_null() || suffix_array.is_null() {
+        return -1;
+    }
+
+    let text = unsafe { slice::from_raw_parts(text, text_len) };
+    let suffix_array_buffer =
+        unsafe { slice::from_raw_parts_mut(suffix_array, text_len + free_space_len) };
+
+    unsafe { construct_into_buffer(text, suffix_array_buffer, freq) };
+
+    0
+}
+
+// SAFETY: like construct_suffix_array, and bwt has to point to n characters. bwt may alias text.
+unsafe fn construct_bwt<C: Character + IndexStorage, I: IndexStorage>(
+    text_ptr: *const C,
+    bwt_ptr: *mut C,
+    temp: *mut I,
+    n: i64,
+    fs: i64,
+    freq: *mut I,
+) -> i64 {
+    let Some((text_len, free_space_len)) = checked_lengths(n, fs) else {
+        return -1;
+    };
+
+    if text_ptr.is_null() || bwt_ptr.is_null() || temp.is_null() {
+        return -1;
+    }
+
+    let text = unsafe { slice::from_raw_parts(text_ptr, text_len) };
+    let buffer = unsafe { slice::from_raw_parts_mut(temp, text_len + free_space_len) };
+    unsafe { construct_into_buffer(text, buffer, freq) };
+
+    let Some(&last_char) = text.last() else {
+        return 0;
+    };
+
+    // the suffix array is replaced by the preceding characters, such that the text is not needed
+    // anymore when the output is written. This way, the output may overwrite the text.
+    let mut primary_index = 0;
+
+    for (position, value) in buffer[..text_len].iter_mut().enumerate() {
+        let suffix_index: usize = value.as_();
+
+        if suffix_index == 0 {
+            primary_index = position + 1;
+        } else {
+            *value = <I as NumCast>::from(text[suffix_index - 1]).unwrap();
+        }
+    }
+
+    let bwt = unsafe { slice::from_raw_parts_mut(bwt_ptr, text_len) };
+    bwt[0] = last_char;
+
+    let preceding_chars = buffer[..primary_index - 1]
+        .iter()
+        .chain(&buffer[primary_index..text_len]);
+
+    for (bwt_char, &value) in bwt[1..].iter_mut().zip(preceding_chars) {
+        *bwt_char = <C as NumCast>::from(value).unwrap();
+    }
+
+    primary_index as i64
+}
+
+// SAFETY: freq has to be null or point to one value per character of the alphabet of C
+unsafe fn construct_into_buffer<C: Character, I: IndexStorage>(
+    text: &[C],
+    suffix_array_buffer: &mut [I],
+    freq: *mut I,
+) {
+    let builder = SaisBuilder::<C, I>::new();
+
+    if freq.is_null() {
+        builder.construct_suffix_array_inplace(text, suffix_array_buffer);
+    } else {
+        let char_frequencies = unsafe { slice::from_raw_parts_mut(freq, C::max_char().rank() + 1) };
+        builder.construct_suffix_array_inplace_with_char_frequencies(
+            text,
+            suffix_array_buffer,
+            char_frequencies,
+        );
+    }
+}
@@ -0,0 +1,140 @@
+// Index arrays are serialized as little endian bytes in binary formats, such that every value
+// only takes the size of its type (e.g. postcard would otherwise use varints of up to 3 bytes
+// for u16). usize values always take 8 bytes, such that the encoding doesn't depend on the
+// platform. Human readable formats get a plain sequence of numbers.
+
+use std::any::TypeId;
+use std::fmt;
+use std::marker::PhantomData;
+
+use num_traits::NumCast;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::IndexStorage;
+
+// the number of bytes of a value in the binary encoding
+fn encoded_width<T: 'static>() -> usize {
+    if TypeId::of::<T>() == TypeId::of::<usize>() {
+        size_of::<u64>()
+    } else {
+        size_of::<T>()
+    }
+}
+
+pub mod array {
+    use super::*;
+
+    pub fn serialize<T: IndexStorage + 'static, S: Serializer>(
+        values: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        CompactArrayRef(values).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: IndexStorage + 'static, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        CompactArray::deserialize(deserializer).map(|array| array.0)
+    }
+}
+
+pub mod optional_array {
+    use super::*;
+
+    pub fn serialize<T: IndexStorage + 'static, S: Serializer>(
+        values: &Option<Vec<T>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        values.as_deref().map(CompactArrayRef).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: IndexStorage + 'static, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<T>>, D::Error> {
+        Option::<CompactArray<T>>::deserialize(deserializer).map(|array| array.map(|array| array.0))
+    }
+}
+
+struct CompactArrayRef<'a, T>(&'a [T]);
+
+impl<T: IndexStorage + 'static> Serialize for CompactArrayRef<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.collect_seq(self.0.iter().map(|value| value.to_u64().unwrap()));
+        }
+
+        let width = encoded_width::<T>();
+        let mut bytes = Vec::with_capacity(self.0.len() * width);
+        for value in self.0 {
+            bytes.extend_from_slice(&value.to_u64().unwrap().to_le_bytes()[..width]);
+        }
+
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+struct CompactArray<T>(Vec<T>);
+
+impl<'de, T: IndexStorage + 'static> Deserialize<'de> for CompactArray<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = CompactArrayVisitor(PhantomData);
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_seq(visitor)
+        } else {
+            deserializer.deserialize_bytes(visitor)
+        }
+    }
+}
+
+struct CompactArrayVisitor<T>(PhantomData<T>);
+
+impl<'de, T: IndexStorage + 'static> Visitor<'de> for CompactArrayVisitor<T> {
+    type Value = CompactArray<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "an array of unsigned integers with {} bytes",
+            encoded_width::<T>()
+        )
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        let width = encoded_width::<T>();
+
+        if !bytes.len().is_multiple_of(width) {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+
+        // only usize values can be out of range, on platforms where it has less than 8 bytes
+        let values = bytes
+            .chunks_exact(width)
+            .map(|value_bytes| {
+                let mut u64_bytes = [0; 8];
+                u64_bytes[..value_bytes.len()].copy_from_slice(value_bytes);
+                let value = u64::from_le_bytes(u64_bytes);
+
+                <T as NumCast>::from(value)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(CompactArray(values))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(value) = seq.next_element::<u64>()? {
+            let value = <T as NumCast>::from(value)
+                .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))?;
+
+            values.push(value);
+        }
+
+        Ok(CompactArray(values))
+    }
+}
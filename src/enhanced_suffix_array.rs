@@ -0,0 +1,488 @@
+// Enhanced suffix arrays (Abouelhoda, Kurtz and Ohlebusch, 2004): the suffix array, the LCP array
+// and the child table, which allow to simulate bottom-up and top-down traversals of the suffix
+// tree via LCP-intervals.
+//
+// An l-interval [i..j] is a range of the suffix array with i < j, where all suffixes share a
+// prefix of length l, and at least one pair of neighbors has an LCP value of exactly l. The
+// positions k in i+1..=j with lcp[k] = l are its l-indices, they separate the child intervals.
+// Positions outside of the suffix array are treated as having an LCP value of -1, such that the
+// whole suffix array is always an interval (the root).
+
+use std::ops::Range;
+
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage, SaisBuilder, lcp};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LcpInterval {
+    // the length of the common prefix of all suffixes of the interval. For leaves, this is the
+    // length of the suffix
+    pub lcp: usize,
+    pub start: usize,
+    // exclusive
+    pub end: usize,
+}
+
+impl LcpInterval {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    // a single suffix, corresponds to a leaf of the suffix tree
+    pub fn is_leaf(&self) -> bool {
+        self.len() == 1
+    }
+}
+
+pub struct EnhancedSuffixArray<'a, C, I> {
+    text: &'a [C],
+    suffix_array: Vec<I>,
+    lcp: Vec<I>,
+    // up, down and next-l-index values in a single array. They never collide (see
+    // construct_child_table) and are told apart by comparisons with the LCP values.
+    child_table: Vec<I>,
+    // the l-indices of every interval, stored contiguously in suffix array order, such that the
+    // children can be binary searched. Every index except 0 is an l-index of exactly one interval
+    child_l_indices: Vec<I>,
+    // the position of the l-indices of an interval in child_l_indices, at its first l-index
+    child_l_indices_starts: Vec<I>,
+}
+
+impl<'a, C: Character, I: IndexStorage> EnhancedSuffixArray<'a, C, I> {
+    pub fn construct(text: &'a [C], builder: &SaisBuilder<C, I>) -> Self {
+        let suffix_array = builder.construct_suffix_array(text);
+        let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+        Self::new(text, suffix_array, lcp)
+    }
+
+    pub fn new(text: &'a [C], suffix_array: Vec<I>, lcp: Vec<I>) -> Self {
+        assert_eq!(text.len(), suffix_array.len());
+        assert_eq!(text.len(), lcp.len());
+
+        let mut enhanced_suffix_array = Self {
+            text,
+            suffix_array,
+            lcp,
+            child_table: Vec::new(),
+            child_l_indices: Vec::new(),
+            child_l_indices_starts: Vec::new(),
+        };
+        enhanced_suffix_array.construct_child_table();
+        enhanced_suffix_array.construct_child_l_indices();
+
+        enhanced_suffix_array
+    }
+
+    pub fn text(&self) -> &'a [C] {
+        self.text
+    }
+
+    pub fn suffix_array(&self) -> &[I] {
+        &self.suffix_array
+    }
+
+    pub fn lcp(&self) -> &[I] {
+        &self.lcp
+    }
+
+    pub fn child_table(&self) -> &[I] {
+        &self.child_table
+    }
+
+    pub fn into_parts(self) -> (Vec<I>, Vec<I>, Vec<I>) {
+        (self.suffix_array, self.lcp, self.child_table)
+    }
+
+    // the interval of the whole suffix array, None for the empty text
+    pub fn root(&self) -> Option<LcpInterval> {
+        (!self.text.is_empty()).then(|| self.interval(0, self.text.len() - 1))
+    }
+
+    // the child intervals in suffix array order, leaves included. Leaves have no children.
+    pub fn child_intervals(&self, interval: &LcpInterval) -> ChildIntervals<'_, 'a, C, I> {
+        let (next_start, next_l_index) = if interval.is_leaf() {
+            (interval.end, None)
+        } else {
+            (
+                interval.start,
+                Some(self.first_l_index(interval.start, interval.end - 1)),
+            )
+        };
+
+        ChildIntervals {
+            enhanced_suffix_array: self,
+            next_start,
+            next_l_index,
+            end: interval.end,
+        }
+    }
+
+    // the child interval whose suffixes continue with the given character after the common
+    // prefix of the interval. The children are sorted by that character, so they are binary
+    // searched in O(log σ) time.
+    pub fn child_interval_with_char(&self, interval: &LcpInterval, char: C) -> Option<LcpInterval> {
+        if interval.is_leaf() {
+            return None;
+        }
+
+        // the children start at the start of the interval and at its l-indices
+        let l_indices = self.child_l_indices_of(interval);
+        let num_smaller_l_indices = l_indices.partition_point(|l_index| {
+            self.char_at_depth(l_index.as_(), interval.lcp) < Some(char)
+        });
+
+        let child = if let Some(l_index) = l_indices.get(num_smaller_l_indices)
+            && self.char_at_depth(l_index.as_(), interval.lcp) == Some(char)
+        {
+            num_smaller_l_indices + 1
+        } else if num_smaller_l_indices == 0
+            // the suffix that ends after the common prefix is the first child, if it exists
+            && self.char_at_depth(interval.start, interval.lcp) == Some(char)
+        {
+            0
+        } else {
+            return None;
+        };
+
+        let child_start = match child {
+            0 => interval.start,
+            _ => l_indices[child - 1].as_(),
+        };
+        let child_end = l_indices
+            .get(child)
+            .map_or(interval.end, |l_index| l_index.as_());
+
+        Some(self.interval(child_start, child_end - 1))
+    }
+
+    // the interval of suffixes that start with the pattern, found by descending from the root.
+    // The returned interval is the one of the node at or below the end of the pattern, so its lcp
+    // value can be larger than the pattern length.
+    pub fn find_pattern(&self, pattern: &[C]) -> Option<LcpInterval> {
+        let mut interval = self.root()?;
+        let mut depth = 0;
+
+        loop {
+            let suffix = &self.text[self.suffix_array[interval.start].as_()..];
+            let compared_depth = interval.lcp.min(pattern.len());
+
+            if suffix.get(depth..compared_depth)? != &pattern[depth..compared_depth] {
+                return None;
+            }
+
+            if pattern.len() <= interval.lcp {
+                return Some(interval);
+            }
+
+            depth = interval.lcp;
+            interval = self.child_interval_with_char(&interval, pattern[depth])?;
+        }
+    }
+
+    // visits every non-leaf interval before its children. If the callback returns false, the
+    // children of the interval are skipped.
+    pub fn top_down_traversal(&self, mut visit: impl FnMut(&LcpInterval) -> bool) {
+        let Some(root) = self.root() else {
+            return;
+        };
+
+        let mut stack = vec![root];
+
+        while let Some(interval) = stack.pop() {
+            if interval.is_leaf() || !visit(&interval) {
+                continue;
+            }
+
+            let num_intervals_before = stack.len();
+            stack.extend(self.child_intervals(&interval));
+
+            // the children are visited in suffix array order
+            stack[num_intervals_before..].reverse();
+        }
+    }
+
+    // visits every non-leaf interval after its children, together with the child intervals in
+    // suffix array order (leaves included). Only the LCP array is needed for this traversal.
+    pub fn bottom_up_traversal(&self, mut visit: impl FnMut(&LcpInterval, &[LcpInterval])) {
+        struct OpenInterval {
+            lcp: isize,
+            start: usize,
+            children: Vec<LcpInterval>,
+        }
+
+        let len = self.text.len();
+
+        // the bottom of the stack has the virtual LCP value -1 and is never closed
+        let mut stack = vec![OpenInterval {
+            lcp: -1,
+            start: 0,
+            children: Vec::new(),
+        }];
+
+        for index in 1..=len {
+            let lcp_value = self.signed_lcp(index);
+            let mut start = index - 1;
+            let mut pending_leaf = Some(self.leaf(index - 1));
+            let mut last_closed_interval = None;
+
+            while lcp_value < stack.last().unwrap().lcp {
+                let mut open_interval = stack.pop().unwrap();
+
+                // the leaf at index - 1 belongs to the deepest interval that contains it
+                open_interval.children.extend(pending_leaf.take());
+
+                let interval = LcpInterval {
+                    lcp: open_interval.lcp as usize,
+                    start: open_interval.start,
+                    end: index,
+                };
+                visit(&interval, &open_interval.children);
+                start = open_interval.start;
+
+                let parent = stack.last_mut().unwrap();
+                if lcp_value <= parent.lcp {
+                    parent.children.push(interval);
+                    last_closed_interval = None;
+                } else {
+                    last_closed_interval = Some(interval);
+                }
+            }
+
+            let top = stack.last_mut().unwrap();
+
+            if lcp_value > top.lcp {
+                stack.push(OpenInterval {
+                    lcp: lcp_value,
+                    start,
+                    children: last_closed_interval
+                        .into_iter()
+                        .chain(pending_leaf)
+                        .collect(),
+                });
+            } else {
+                top.children.extend(pending_leaf);
+            }
+        }
+    }
+
+    // the algorithms of Abouelhoda et al. for the up/down and next-l-index values, adapted to
+    // a table of n entries. up(i) is stored at i - 1, down(i) and next-l-index(i) at i.
+    // - up(i) is only defined if lcp(i - 1) > lcp(i). Then i - 1 has neither a down value, which
+    //   would need lcp(i) > lcp(i - 1), nor a next l-index, because lcp(i) is smaller.
+    // - down(i) is only used if i has no next l-index, so the latter can take precedence.
+    fn construct_child_table(&mut self) {
+        let len = self.text.len();
+        self.child_table = vec![I::max_value(); len];
+
+        let mut stack = vec![0];
+        let mut maybe_last_index = None;
+
+        for index in 1..=len {
+            let lcp_value = self.signed_lcp(index);
+
+            while lcp_value < self.signed_lcp(*stack.last().unwrap()) {
+                let last_index = stack.pop().unwrap();
+                maybe_last_index = Some(last_index);
+                let top = *stack.last().unwrap();
+
+                if lcp_value <= self.signed_lcp(top)
+                    && self.signed_lcp(top) != self.signed_lcp(last_index)
+                {
+                    // down value of top
+                    self.child_table[top] = to_index(last_index);
+                }
+            }
+
+            if let Some(last_index) = maybe_last_index.take() {
+                // up value of index
+                self.child_table[index - 1] = to_index(last_index);
+            }
+
+            stack.push(index);
+        }
+
+        stack.clear();
+        stack.push(0);
+
+        for index in 1..len {
+            let lcp_value = self.signed_lcp(index);
+
+            while lcp_value < self.signed_lcp(*stack.last().unwrap()) {
+                stack.pop();
+            }
+
+            if lcp_value == self.signed_lcp(*stack.last().unwrap()) {
+                let last_index = stack.pop().unwrap();
+                // next l-index of last_index
+                self.child_table[last_index] = to_index(index);
+            }
+
+            stack.push(index);
+        }
+    }
+
+    // groups the l-indices by their intervals, along the chains of next l-indices
+    fn construct_child_l_indices(&mut self) {
+        let len = self.text.len();
+
+        let mut is_first_l_index = vec![true; len];
+        for index in 1..len {
+            if let Some(next_l_index) = self.next_l_index(index) {
+                is_first_l_index[next_l_index] = false;
+            }
+        }
+
+        let mut child_l_indices = Vec::with_capacity(len.saturating_sub(1));
+        let mut child_l_indices_starts = vec![I::max_value(); len];
+
+        for first_l_index in (1..len).filter(|&index| is_first_l_index[index]) {
+            child_l_indices_starts[first_l_index] = to_index(child_l_indices.len());
+
+            let mut maybe_l_index = Some(first_l_index);
+            while let Some(l_index) = maybe_l_index {
+                child_l_indices.push(to_index(l_index));
+                maybe_l_index = self.next_l_index(l_index);
+            }
+        }
+
+        self.child_l_indices = child_l_indices;
+        self.child_l_indices_starts = child_l_indices_starts;
+    }
+
+    // expects a non-leaf interval. The end of its l-indices is found by an exponential search,
+    // because the following ones belong to other intervals, which have other LCP values or lie
+    // outside of the interval
+    fn child_l_indices_of(&self, interval: &LcpInterval) -> &[I] {
+        let first_l_index = self.first_l_index(interval.start, interval.end - 1);
+        let l_indices = &self.child_l_indices[self.child_l_indices_starts[first_l_index].as_()..];
+
+        let is_l_index_of_interval = |l_index: &I| {
+            let l_index: usize = l_index.as_();
+            interval.range().contains(&l_index) && self.lcp[l_index].as_() == interval.lcp
+        };
+
+        let mut bound = 1;
+        while bound < l_indices.len() && is_l_index_of_interval(&l_indices[bound]) {
+            bound *= 2;
+        }
+
+        let searched_start = bound / 2;
+        let searched_end = bound.min(l_indices.len());
+        let num_l_indices = searched_start
+            + l_indices[searched_start..searched_end].partition_point(is_l_index_of_interval);
+
+        &l_indices[..num_l_indices]
+    }
+
+    // expects first < last
+    fn first_l_index(&self, first: usize, last: usize) -> usize {
+        match self.up(last + 1) {
+            Some(up) if first < up && up <= last => up,
+            _ => self.down(first).unwrap(),
+        }
+    }
+
+    fn up(&self, index: usize) -> Option<usize> {
+        (self.signed_lcp(index - 1) > self.signed_lcp(index))
+            .then(|| self.child_table[index - 1].as_())
+    }
+
+    fn down(&self, index: usize) -> Option<usize> {
+        let value: usize = self.child_table[index].as_();
+
+        (value != I::max_value().as_()
+            && value > index
+            && self.signed_lcp(value) > self.signed_lcp(index))
+        .then_some(value)
+    }
+
+    fn next_l_index(&self, index: usize) -> Option<usize> {
+        let value: usize = self.child_table[index].as_();
+
+        (value != I::max_value().as_()
+            && value > index
+            && self.signed_lcp(value) == self.signed_lcp(index))
+        .then_some(value)
+    }
+
+    // expects first <= last
+    fn interval(&self, first: usize, last: usize) -> LcpInterval {
+        if first == last {
+            return self.leaf(first);
+        }
+
+        LcpInterval {
+            lcp: self.lcp[self.first_l_index(first, last)].as_(),
+            start: first,
+            end: last + 1,
+        }
+    }
+
+    fn leaf(&self, index: usize) -> LcpInterval {
+        LcpInterval {
+            lcp: self.text.len() - self.suffix_array[index].as_(),
+            start: index,
+            end: index + 1,
+        }
+    }
+
+    fn char_at_depth(&self, index: usize, depth: usize) -> Option<C> {
+        self.text
+            .get(self.suffix_array[index].as_() + depth)
+            .copied()
+    }
+
+    // -1 outside of the array and at index 0
+    fn signed_lcp(&self, index: usize) -> isize {
+        if index == 0 || index >= self.text.len() {
+            -1
+        } else {
+            self.lcp[index].as_() as isize
+        }
+    }
+}
+
+fn to_index<I: IndexStorage>(value: usize) -> I {
+    <I as NumCast>::from(value).unwrap()
+}
+
+pub struct ChildIntervals<'e, 'a, C, I> {
+    enhanced_suffix_array: &'e EnhancedSuffixArray<'a, C, I>,
+    next_start: usize,
+    next_l_index: Option<usize>,
+    end: usize,
+}
+
+impl<C: Character, I: IndexStorage> Iterator for ChildIntervals<'_, '_, C, I> {
+    type Item = LcpInterval;
+
+    fn next(&mut self) -> Option<LcpInterval> {
+        if self.next_start >= self.end {
+            return None;
+        }
+
+        let start = self.next_start;
+
+        let child_end = match self.next_l_index {
+            Some(l_index) => {
+                self.next_l_index = self.enhanced_suffix_array.next_l_index(l_index);
+                l_index
+            }
+            None => self.end,
+        };
+
+        self.next_start = child_end;
+
+        Some(self.enhanced_suffix_array.interval(start, child_end - 1))
+    }
+}
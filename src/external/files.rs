@@ -0,0 +1,409 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const U64_SIZE: usize = size_of::<u64>();
+
+pub fn temp_file(temp_dir: &Path) -> io::Result<File> {
+    tempfile::tempfile_in(temp_dir)
+}
+
+// a value that can be streamed through a sequence, forwards and backwards
+pub trait Record: Sized {
+    fn write_to<W: Write>(&self, writer: &mut SequenceWriter<W>) -> io::Result<()>;
+
+    fn read_from(reader: &mut SequenceReader) -> io::Result<Option<Self>>;
+
+    fn read_reverse_from(reader: &mut ReverseSequenceReader) -> io::Result<Option<Self>>;
+
+    // the number of bytes that the record occupies on the heap, for the memory budget
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl Record for u64 {
+    fn write_to<W: Write>(&self, writer: &mut SequenceWriter<W>) -> io::Result<()> {
+        writer.write(*self)
+    }
+
+    fn read_from(reader: &mut SequenceReader) -> io::Result<Option<Self>> {
+        reader.next()
+    }
+
+    fn read_reverse_from(reader: &mut ReverseSequenceReader) -> io::Result<Option<Self>> {
+        reader.next()
+    }
+}
+
+impl Record for (u64, u64) {
+    fn write_to<W: Write>(&self, writer: &mut SequenceWriter<W>) -> io::Result<()> {
+        writer.write(self.0)?;
+        writer.write(self.1)
+    }
+
+    fn read_from(reader: &mut SequenceReader) -> io::Result<Option<Self>> {
+        let Some(first) = reader.next()? else {
+            return Ok(None);
+        };
+
+        Ok(Some((first, reader.next_of_record()?)))
+    }
+
+    fn read_reverse_from(reader: &mut ReverseSequenceReader) -> io::Result<Option<Self>> {
+        let Some(second) = reader.next()? else {
+            return Ok(None);
+        };
+
+        Ok(Some((reader.next_of_record()?, second)))
+    }
+}
+
+// a sequence of u64 values that was written to a (temporary) file. The readers seek before
+// every chunk they read, so multiple readers of the same sequence can be used at the same time
+pub struct Sequence {
+    file: File,
+    len: u64,
+}
+
+impl Sequence {
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn reader(&self, io_buffer_size: usize) -> io::Result<SequenceReader> {
+        Ok(SequenceReader {
+            file: self.file.try_clone()?,
+            chunk: Vec::new(),
+            chunk_len: chunk_len(io_buffer_size, U64_SIZE),
+            num_read_from_file: 0,
+            len: self.len,
+        })
+    }
+
+    pub fn reverse_reader(&self, io_buffer_size: usize) -> io::Result<ReverseSequenceReader> {
+        ReverseSequenceReader::new(&self.file, self.len, U64_SIZE, io_buffer_size)
+    }
+
+    // overwrites the content of values with the num_values values from the given position on
+    pub fn read_at(&self, start: u64, num_values: usize, values: &mut Vec<u64>) -> io::Result<()> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(start * U64_SIZE as u64))?;
+
+        read_values(&mut file, num_values, U64_SIZE, values)
+    }
+
+    pub fn records<T: Record>(&self, io_buffer_size: usize) -> io::Result<RecordReader<T>> {
+        Ok(RecordReader {
+            reader: self.reader(io_buffer_size)?,
+            peeked: None,
+        })
+    }
+
+    pub fn reverse_records<T: Record>(
+        &self,
+        io_buffer_size: usize,
+    ) -> io::Result<ReverseRecordReader<T>> {
+        Ok(ReverseRecordReader {
+            reader: self.reverse_reader(io_buffer_size)?,
+            peeked: None,
+        })
+    }
+}
+
+pub struct SequenceWriter<W: Write> {
+    writer: BufWriter<W>,
+    len: u64,
+}
+
+impl SequenceWriter<File> {
+    pub fn create_temp(temp_dir: &Path, io_buffer_size: usize) -> io::Result<Self> {
+        Ok(Self::new(temp_file(temp_dir)?, io_buffer_size))
+    }
+
+    pub fn finish(self) -> io::Result<Sequence> {
+        let len = self.len;
+        let file = self
+            .writer
+            .into_inner()
+            .map_err(|error| error.into_error())?;
+
+        Ok(Sequence { file, len })
+    }
+}
+
+impl<W: Write> SequenceWriter<W> {
+    pub fn new(inner: W, io_buffer_size: usize) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(io_buffer_size, inner),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn write(&mut self, value: u64) -> io::Result<()> {
+        self.len += 1;
+        self.writer.write_all(&value.to_le_bytes())
+    }
+
+    pub fn write_record<T: Record>(&mut self, record: &T) -> io::Result<()> {
+        record.write_to(self)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+pub struct SequenceReader {
+    file: File,
+    // the values of a chunk are stored in reverse file order and popped from the back
+    chunk: Vec<u64>,
+    chunk_len: usize,
+    num_read_from_file: u64,
+    len: u64,
+}
+
+impl SequenceReader {
+    pub fn next(&mut self) -> io::Result<Option<u64>> {
+        if self.chunk.is_empty() {
+            if self.num_read_from_file == self.len {
+                return Ok(None);
+            }
+
+            let num_values = (self.len - self.num_read_from_file).min(self.chunk_len as u64);
+
+            self.file
+                .seek(SeekFrom::Start(self.num_read_from_file * U64_SIZE as u64))?;
+            read_values(
+                &mut self.file,
+                num_values as usize,
+                U64_SIZE,
+                &mut self.chunk,
+            )?;
+
+            self.num_read_from_file += num_values;
+            self.chunk.reverse();
+        }
+
+        Ok(self.chunk.pop())
+    }
+
+    // for the values after the first one of a record
+    pub fn next_of_record(&mut self) -> io::Result<u64> {
+        self.next()?.ok_or_else(incomplete_record)
+    }
+}
+
+// reads values from back to front, in chunks. The values have a width of up to 8 bytes
+pub struct ReverseSequenceReader {
+    file: File,
+    // the values of a chunk are stored in file order and popped from the back
+    chunk: Vec<u64>,
+    chunk_len: usize,
+    value_width: usize,
+    remaining_in_file: u64,
+}
+
+impl ReverseSequenceReader {
+    pub fn new(
+        file: &File,
+        len: u64,
+        value_width: usize,
+        io_buffer_size: usize,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            file: file.try_clone()?,
+            chunk: Vec::new(),
+            chunk_len: chunk_len(io_buffer_size, value_width),
+            value_width,
+            remaining_in_file: len,
+        })
+    }
+
+    pub fn next(&mut self) -> io::Result<Option<u64>> {
+        if self.chunk.is_empty() {
+            if self.remaining_in_file == 0 {
+                return Ok(None);
+            }
+
+            let num_values = self.remaining_in_file.min(self.chunk_len as u64);
+            self.remaining_in_file -= num_values;
+
+            self.file.seek(SeekFrom::Start(
+                self.remaining_in_file * self.value_width as u64,
+            ))?;
+            read_values(
+                &mut self.file,
+                num_values as usize,
+                self.value_width,
+                &mut self.chunk,
+            )?;
+        }
+
+        Ok(self.chunk.pop())
+    }
+
+    // for the values after the first one of a record, in reverse
+    pub fn next_of_record(&mut self) -> io::Result<u64> {
+        self.next()?.ok_or_else(incomplete_record)
+    }
+}
+
+pub struct RecordReader<T> {
+    reader: SequenceReader,
+    peeked: Option<T>,
+}
+
+impl<T: Record> RecordReader<T> {
+    pub fn next(&mut self) -> io::Result<Option<T>> {
+        match self.peeked.take() {
+            Some(record) => Ok(Some(record)),
+            None => T::read_from(&mut self.reader),
+        }
+    }
+
+    // puts a record back in front of the remaining ones
+    pub fn with_peeked(mut self, record: T) -> Self {
+        assert!(self.peeked.is_none());
+        self.peeked = Some(record);
+        self
+    }
+
+    pub fn peek(&mut self) -> io::Result<Option<&T>> {
+        if self.peeked.is_none() {
+            self.peeked = T::read_from(&mut self.reader)?;
+        }
+
+        Ok(self.peeked.as_ref())
+    }
+}
+
+pub struct ReverseRecordReader<T> {
+    reader: ReverseSequenceReader,
+    peeked: Option<T>,
+}
+
+impl<T: Record> ReverseRecordReader<T> {
+    pub fn next(&mut self) -> io::Result<Option<T>> {
+        match self.peeked.take() {
+            Some(record) => Ok(Some(record)),
+            None => T::read_reverse_from(&mut self.reader),
+        }
+    }
+
+    pub fn peek(&mut self) -> io::Result<Option<&T>> {
+        if self.peeked.is_none() {
+            self.peeked = T::read_reverse_from(&mut self.reader)?;
+        }
+
+        Ok(self.peeked.as_ref())
+    }
+}
+
+fn chunk_len(io_buffer_size: usize, value_width: usize) -> usize {
+    // reading a chunk needs a buffer for the bytes and one for the values
+    (io_buffer_size / (U64_SIZE + value_width)).max(1)
+}
+
+fn incomplete_record() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "records are always stored completely",
+    )
+}
+
+// overwrites the content of values
+fn read_values(
+    file: &mut impl Read,
+    num_values: usize,
+    value_width: usize,
+    values: &mut Vec<u64>,
+) -> io::Result<()> {
+    let mut bytes = vec![0; num_values * value_width];
+    file.read_exact(&mut bytes)?;
+
+    values.clear();
+    values.extend(bytes.chunks_exact(value_width).map(|value_bytes| {
+        let mut u64_bytes = [0; U64_SIZE];
+        u64_bytes[..value_width].copy_from_slice(value_bytes);
+        u64::from_le_bytes(u64_bytes)
+    }));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_readers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let mut writer = SequenceWriter::create_temp(temp_dir.path(), 16).unwrap();
+        for value in 0..10 {
+            writer.write(value).unwrap();
+        }
+        let sequence = writer.finish().unwrap();
+        assert_eq!(sequence.len(), 10);
+
+        let mut reader = sequence.reader(16).unwrap();
+        let mut reverse_reader = sequence.reverse_reader(24).unwrap();
+
+        for value in 0..10 {
+            assert_eq!(reader.next().unwrap(), Some(value));
+            assert_eq!(reverse_reader.next().unwrap(), Some(9 - value));
+        }
+
+        assert_eq!(reader.next().unwrap(), None);
+        assert_eq!(reverse_reader.next().unwrap(), None);
+
+        let mut values = vec![42];
+        sequence.read_at(3, 4, &mut values).unwrap();
+        assert_eq!(values, [3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_record_readers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let mut writer = SequenceWriter::create_temp(temp_dir.path(), 16).unwrap();
+        for value in 0..10 {
+            writer.write_record(&(value, 100 + value)).unwrap();
+        }
+        let sequence = writer.finish().unwrap();
+
+        let mut reader = sequence.records::<(u64, u64)>(16).unwrap();
+        let mut reverse_reader = sequence.reverse_records::<(u64, u64)>(24).unwrap();
+
+        for value in 0..10 {
+            assert_eq!(reader.peek().unwrap(), Some(&(value, 100 + value)));
+            assert_eq!(reader.next().unwrap(), Some((value, 100 + value)));
+            assert_eq!(
+                reverse_reader.next().unwrap(),
+                Some((9 - value, 109 - value))
+            );
+        }
+
+        assert_eq!(reader.next().unwrap(), None);
+        assert_eq!(reverse_reader.next().unwrap(), None);
+    }
+
+    #[test]
+    fn test_reverse_byte_reader() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut file = temp_file(temp_dir.path()).unwrap();
+        file.write_all(b"abcdefg").unwrap();
+
+        let mut reader = ReverseSequenceReader::new(&file, 7, 1, 27).unwrap();
+
+        for char in b"gfedcba" {
+            assert_eq!(reader.next().unwrap(), Some(*char as u64));
+        }
+        assert_eq!(reader.next().unwrap(), None);
+    }
+}
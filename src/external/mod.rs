@@ -0,0 +1,112 @@
+// External memory suffix array construction in the spirit of eSAIS:
+// T. Bingmann, J. Fischer and V. Osipov: Inducing Suffix and LCP Arrays in External Memory (2013)
+//
+// The text is only read sequentially, from back to front. All other data is streamed through
+// sorted runs in temporary files. The memory budget is respected approximately, it is exceeded
+// by small constant amounts for very small budgets.
+
+mod files;
+mod queue;
+mod sais;
+mod sort;
+mod text;
+
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use files::SequenceWriter;
+use text::ByteText;
+
+const DEFAULT_MEMORY_BUDGET: usize = 1 << 30;
+
+pub struct ExternalSaisBuilder {
+    temp_dir: PathBuf,
+    memory_budget: usize,
+}
+
+impl ExternalSaisBuilder {
+    pub fn new() -> Self {
+        Self {
+            temp_dir: std::env::temp_dir(),
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+        }
+    }
+
+    // the directory where the temporary files are created. It needs free space of several
+    // times the size of the suffix array
+    pub fn with_temp_dir(&mut self, temp_dir: impl Into<PathBuf>) -> &mut Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    // the approximate number of bytes of main memory that the construction is allowed to use
+    pub fn with_memory_budget(&mut self, memory_budget: usize) -> &mut Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    // the text file is interpreted as bytes and must not be modified during the construction.
+    // the suffix array is written as little endian u64 values to the output file
+    pub fn construct_suffix_array_file(
+        &self,
+        text_path: impl AsRef<Path>,
+        output_path: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        let context = ExternalContext::new(self.temp_dir.clone(), self.memory_budget);
+
+        let text = ByteText::new(File::open(text_path)?)?;
+        let mut output = SequenceWriter::new(File::create(output_path)?, context.io_buffer_size());
+
+        sais::construct_suffix_array(&text, &mut output, &context)
+    }
+}
+
+impl Default for ExternalSaisBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// derives the sizes of the in-memory parts of the algorithm from the memory budget
+struct ExternalContext {
+    temp_dir: PathBuf,
+    memory_budget: usize,
+}
+
+impl ExternalContext {
+    fn new(temp_dir: PathBuf, memory_budget: usize) -> Self {
+        Self {
+            temp_dir,
+            memory_budget,
+        }
+    }
+
+    fn memory_budget(&self) -> u64 {
+        self.memory_budget as u64
+    }
+
+    // the number of bytes of the records that a sorter or a priority queue keeps in memory
+    fn item_memory(&self) -> usize {
+        (self.memory_budget / 4).max(1 << 12)
+    }
+
+    fn io_buffer_size(&self) -> usize {
+        (self.memory_budget / 64).clamp(512, 1 << 20)
+    }
+
+    // the number of values of its chain that a record holds in memory, the rest of a longer chain
+    // is kept in a temporary file
+    fn max_chain_len(&self) -> usize {
+        (self.io_buffer_size() / 32) & !1
+    }
+
+    // the number of sorted runs that are read at the same time
+    fn merge_fan_in(&self) -> usize {
+        (self.memory_budget / (8 * self.io_buffer_size())).max(2)
+    }
+
+    fn create_temp_sequence(&self) -> io::Result<SequenceWriter<File>> {
+        SequenceWriter::create_temp(&self.temp_dir, self.io_buffer_size())
+    }
+}
@@ -0,0 +1,218 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io;
+
+use super::ExternalContext;
+use super::files::{Record, RecordReader};
+use super::sort::merge_runs;
+
+// a min priority queue that keeps its newest records in memory and writes them to a sorted run
+// when the memory budget is used up. The smallest record is then either in memory or at the
+// head of a run. If there are too many runs to read them at the same time, the smallest ones
+// are merged.
+pub struct PriorityQueue<'a, T> {
+    context: &'a ExternalContext,
+    heap: BinaryHeap<Reverse<T>>,
+    heap_size: usize,
+    max_heap_size: usize,
+    // None for the runs that were read completely, such that their buffers are freed
+    runs: Vec<Option<Run<T>>>,
+    // the heads of the runs with the indices of their runs
+    run_heads: BinaryHeap<Reverse<(T, usize)>>,
+}
+
+struct Run<T> {
+    reader: RecordReader<T>,
+    // the number of records that are not yet in run_heads
+    num_remaining: u64,
+}
+
+impl<'a, T: Record + Ord> PriorityQueue<'a, T> {
+    pub fn new(context: &'a ExternalContext) -> Self {
+        // half of the memory for the records themselves and half for their heap allocations
+        let item_memory = context.item_memory() / 2;
+
+        Self {
+            context,
+            heap: BinaryHeap::with_capacity((item_memory / size_of::<T>()).max(1)),
+            heap_size: 0,
+            max_heap_size: item_memory,
+            runs: Vec::new(),
+            run_heads: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, record: T) -> io::Result<()> {
+        let heap_is_full = self.heap.len() == self.heap.capacity()
+            || self.heap_size + record.heap_size() > self.max_heap_size;
+
+        if !self.heap.is_empty() && heap_is_full {
+            self.write_heap_to_run()?;
+        }
+
+        self.heap_size += record.heap_size();
+        self.heap.push(Reverse(record));
+
+        Ok(())
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        match (self.heap.peek(), self.run_heads.peek()) {
+            (Some(Reverse(record)), Some(Reverse((run_head, _)))) => Some(record.min(run_head)),
+            (Some(Reverse(record)), None) => Some(record),
+            (None, Some(Reverse((run_head, _)))) => Some(run_head),
+            (None, None) => None,
+        }
+    }
+
+    pub fn pop(&mut self) -> io::Result<Option<T>> {
+        let take_from_runs = match (self.heap.peek(), self.run_heads.peek()) {
+            (Some(Reverse(record)), Some(Reverse((run_head, _)))) => run_head < record,
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+
+        if !take_from_runs {
+            let record = self.heap.pop().map(|Reverse(record)| record);
+
+            if let Some(record) = &record {
+                self.heap_size -= record.heap_size();
+            }
+
+            return Ok(record);
+        }
+
+        let Reverse((record, run_index)) = self.run_heads.pop().unwrap();
+        self.advance_run(run_index)?;
+
+        Ok(Some(record))
+    }
+
+    fn advance_run(&mut self, run_index: usize) -> io::Result<()> {
+        let run = self.runs[run_index].as_mut().unwrap();
+
+        match run.reader.next()? {
+            Some(record) => {
+                run.num_remaining -= 1;
+                self.run_heads.push(Reverse((record, run_index)));
+            }
+            None => self.runs[run_index] = None,
+        }
+
+        Ok(())
+    }
+
+    fn push_run(&mut self, run: Run<T>) -> io::Result<()> {
+        self.runs.push(Some(run));
+        self.advance_run(self.runs.len() - 1)
+    }
+
+    fn write_heap_to_run(&mut self) -> io::Result<()> {
+        if self.runs.iter().flatten().count() >= self.context.merge_fan_in() {
+            self.merge_smallest_runs()?;
+        }
+
+        let mut records = std::mem::take(&mut self.heap).into_vec();
+        records.sort_unstable_by(|Reverse(first), Reverse(second)| first.cmp(second));
+        let num_records = records.len() as u64;
+
+        let mut writer = self.context.create_temp_sequence()?;
+        for Reverse(record) in records.drain(..) {
+            writer.write_record(&record)?;
+        }
+
+        self.heap = BinaryHeap::from(records);
+        self.heap_size = 0;
+
+        let run = writer.finish()?;
+        self.push_run(Run {
+            reader: run.records(self.context.io_buffer_size())?,
+            num_remaining: num_records,
+        })
+    }
+
+    // merges the smaller half of the runs, such that every record is only merged a logarithmic
+    // number of times
+    fn merge_smallest_runs(&mut self) -> io::Result<()> {
+        let mut heads: Vec<_> = self.runs.iter().map(|_| None).collect();
+        for Reverse((head, run_index)) in std::mem::take(&mut self.run_heads) {
+            heads[run_index] = Some(head);
+        }
+
+        // every run that was not read completely has a head
+        let mut runs: Vec<_> = std::mem::take(&mut self.runs)
+            .into_iter()
+            .zip(heads)
+            .filter_map(|(run, head)| Some((run?, head?)))
+            .collect();
+        runs.sort_unstable_by_key(|(run, _)| run.num_remaining);
+
+        let num_runs_to_merge = (runs.len() / 2).max(2).min(runs.len());
+        let runs_to_keep = runs.split_off(num_runs_to_merge);
+
+        let mut num_records = 0;
+        let mut readers = Vec::with_capacity(runs.len());
+        for (run, head) in runs {
+            num_records += run.num_remaining + 1;
+            readers.push(run.reader.with_peeked(head));
+        }
+
+        let merged_run = merge_runs(readers, self.context)?;
+
+        for (run, head) in runs_to_keep {
+            self.run_heads.push(Reverse((head, self.runs.len())));
+            self.runs.push(Some(run));
+        }
+
+        self.push_run(Run {
+            reader: merged_run.records(self.context.io_buffer_size())?,
+            num_remaining: num_records,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_queue() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let context = ExternalContext::new(temp_dir.path().to_path_buf(), 0);
+        let mut queue = PriorityQueue::new(&context);
+        let mut expected_queue = BinaryHeap::new();
+
+        for i in 0..20_000u64 {
+            let pair = ((i * 7919) % 1009, i);
+            queue.push(pair).unwrap();
+            expected_queue.push(Reverse(pair));
+
+            if i % 3 == 0 {
+                let expected = expected_queue.pop().map(|Reverse(pair)| pair);
+                assert_eq!(queue.pop().unwrap(), expected);
+            }
+        }
+
+        while let Some(Reverse(pair)) = expected_queue.pop() {
+            assert_eq!(queue.pop().unwrap(), Some(pair));
+        }
+        assert_eq!(queue.pop().unwrap(), None);
+    }
+
+    #[test]
+    fn test_priority_queue_sorts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let context = ExternalContext::new(temp_dir.path().to_path_buf(), 0);
+        let mut queue = PriorityQueue::new(&context);
+
+        for i in 0..20_000u64 {
+            queue.push(((i * 7919) % 20_011, i)).unwrap();
+        }
+
+        let mut previous = None;
+        while let Some(pair) = queue.pop().unwrap() {
+            assert!(previous < Some(pair));
+            previous = Some(pair);
+        }
+    }
+}
@@ -0,0 +1,713 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, Write};
+
+use super::ExternalContext;
+use super::files::{Record, ReverseSequenceReader, Sequence, SequenceReader, SequenceWriter};
+use super::queue::PriorityQueue;
+use super::sort::Sorter;
+use super::text::{ExternalText, ReducedText};
+
+// The same inducing scans as the in-memory SAIS, but the suffix array is never held in memory
+// and the text is only read once per recursion level, from back to front.
+//
+// Every suffix is induced by the one after it. So the text falls apart into chains: an LMS
+// suffix (or the virtual sentinel) induces the L-type suffixes before it, the first of which
+// induces the S-type suffixes before it, down to the previous LMS position. The backwards scan
+// stores the characters and types of every chain, run length encoded, in the record of the
+// LMS suffix that starts it. The inducing scans pop the suffixes from priority queues in the
+// order in which the in-memory algorithm visits them, and the record of a suffix tells the
+// character and type of the one before it. It is then pushed with the rest of the chain. A
+// record only holds the start of a long chain, the rest is read from the chain tails when it is
+// needed, such that long runs of distinct characters in the reduced texts fit into the budget.
+//
+// The first pair of scans starts with the LMS suffixes sorted by their first character and
+// sorts the LMS substrings, which are named on the way. The second pair starts with the LMS
+// suffixes sorted by the suffix array of the reduced text and produces the L- and S-type
+// suffixes in suffix array order. They are merged at the end.
+pub fn construct_suffix_array<T: ExternalText, W: Write>(
+    text: &T,
+    output: &mut SequenceWriter<W>,
+    context: &ExternalContext,
+) -> io::Result<()> {
+    if text.len() == 0 {
+        return output.flush();
+    }
+
+    if text.in_memory_construction_size() <= context.memory_budget() {
+        for index in text.construct_suffix_array_in_memory()? {
+            output.write(index)?;
+        }
+
+        return output.flush();
+    }
+
+    let (lms_suffixes, chain_tails) = lms_suffixes_with_chains(text, context)?;
+    let lms_ranks = rank_lms_suffixes(&lms_suffixes, &chain_tails, context)?;
+    let sorted_lms_suffixes = sort_lms_suffixes(&lms_suffixes, &lms_ranks, context)?;
+    drop(lms_suffixes);
+    drop(lms_ranks);
+
+    let mut namer = Namer::new();
+
+    let mut l_type_suffixes = context.create_temp_sequence()?;
+    let inducers = induce_l_type_suffixes(
+        &sorted_lms_suffixes,
+        &chain_tails,
+        &mut namer,
+        |suffix| l_type_suffixes.write_record(&(suffix.char, suffix.index)),
+        context,
+    )?;
+    let l_type_suffixes = l_type_suffixes.finish()?;
+    drop(sorted_lms_suffixes);
+
+    let mut s_type_suffixes = context.create_temp_sequence()?;
+    induce_s_type_suffixes(
+        &inducers,
+        &chain_tails,
+        &mut namer,
+        |suffix, _| s_type_suffixes.write_record(&(suffix.char, suffix.index)),
+        context,
+    )?;
+    let s_type_suffixes = s_type_suffixes.finish()?;
+    drop(inducers);
+
+    merge_l_and_s_type_suffixes(&l_type_suffixes, &s_type_suffixes, output, context)
+}
+
+// the characters are shifted by one, such that the virtual sentinel is the smallest character
+const SENTINEL: u64 = 0;
+
+// a suffix with the rest of its chain
+#[derive(Debug)]
+struct Suffix {
+    index: u64,
+    char: u64,
+    // the name of its class of equal LMS prefixes. While the suffix is queued, the name of the
+    // suffix that induced it
+    name: u64,
+    // the runs of the chain before the suffix, pairs of a character and count << 1 | is_s_type.
+    // The run right before the suffix is at the back
+    preceding_runs: Vec<u64>,
+    // the position and the number of values of the runs in the chain tails that come after the
+    // preceding runs. They are only used once the preceding runs are induced
+    tail_start: u64,
+    tail_len: u64,
+}
+
+impl Suffix {
+    fn new(index: u64, char: u64) -> Self {
+        Self {
+            index,
+            char,
+            name: 0,
+            preceding_runs: Vec::new(),
+            tail_start: 0,
+            tail_len: 0,
+        }
+    }
+
+    // the character and type of the suffix before this one, if it belongs to the chain
+    fn preceding(&self) -> Option<(u64, bool)> {
+        let [char, count_and_type] = self.preceding_runs.last_chunk()?;
+        Some((*char, count_and_type & 1 == 1))
+    }
+
+    // the suffix before this one, which takes over the rest of the chain
+    fn into_preceding(mut self, inducer_name: u64, chain_tails: &ChainTails) -> io::Result<Self> {
+        let (char, _) = self.preceding().unwrap();
+
+        let count_and_type = self.preceding_runs.last_mut().unwrap();
+        *count_and_type -= 2;
+        if *count_and_type >> 1 == 0 {
+            self.preceding_runs.truncate(self.preceding_runs.len() - 2);
+        }
+
+        if self.preceding_runs.is_empty() && self.tail_len > 0 {
+            let num_values = self.tail_len.min(chain_tails.max_chain_len as u64);
+            chain_tails.runs.read_at(
+                self.tail_start,
+                num_values as usize,
+                &mut self.preceding_runs,
+            )?;
+            reverse_runs(&mut self.preceding_runs);
+
+            self.tail_start += num_values;
+            self.tail_len -= num_values;
+        }
+
+        Ok(Self {
+            index: self.index - 1,
+            char,
+            name: inducer_name,
+            ..self
+        })
+    }
+
+    // while the chain is collected, the run right before the suffix is at the front. Once the
+    // record is full, every further run is moved to the chain tails when it can no longer grow
+    fn push_to_chain(
+        &mut self,
+        char: u64,
+        is_s_type: bool,
+        chain_tails: &mut SequenceWriter<File>,
+        max_chain_len: usize,
+    ) -> io::Result<()> {
+        if let [.., last_char, count_and_type] = self.preceding_runs.as_mut_slice()
+            && *last_char == char
+            && (*count_and_type & 1 == 1) == is_s_type
+        {
+            *count_and_type += 2;
+            return Ok(());
+        }
+
+        if self.preceding_runs.len() > max_chain_len {
+            self.move_last_run_to_tail(chain_tails)?;
+        }
+
+        self.preceding_runs.push(char);
+        self.preceding_runs.push(2 | is_s_type as u64);
+
+        Ok(())
+    }
+
+    fn move_last_run_to_tail(&mut self, chain_tails: &mut SequenceWriter<File>) -> io::Result<()> {
+        if self.tail_len == 0 {
+            self.tail_start = chain_tails.len();
+        }
+
+        for value in self.preceding_runs.drain(self.preceding_runs.len() - 2..) {
+            chain_tails.write(value)?;
+        }
+        self.tail_len += 2;
+
+        Ok(())
+    }
+
+    fn finish_chain(
+        mut self,
+        chain_tails: &mut SequenceWriter<File>,
+        max_chain_len: usize,
+    ) -> io::Result<Self> {
+        if self.preceding_runs.len() > max_chain_len {
+            self.move_last_run_to_tail(chain_tails)?;
+        }
+
+        reverse_runs(&mut self.preceding_runs);
+        self.preceding_runs.shrink_to_fit();
+
+        Ok(self)
+    }
+}
+
+fn reverse_runs(runs: &mut [u64]) {
+    runs.reverse();
+    for run in runs.chunks_exact_mut(2) {
+        run.swap(0, 1);
+    }
+}
+
+// the runs of the chains that do not fit into their records, in the order in which they are
+// induced
+struct ChainTails {
+    runs: Sequence,
+    max_chain_len: usize,
+}
+
+// the runs are framed by their length, such that the record can also be read backwards
+impl Record for Suffix {
+    fn write_to<W: Write>(&self, writer: &mut SequenceWriter<W>) -> io::Result<()> {
+        let num_values = self.preceding_runs.len() as u64;
+
+        writer.write(num_values)?;
+        writer.write(self.index)?;
+        writer.write(self.char)?;
+        writer.write(self.name)?;
+        writer.write(self.tail_start)?;
+        writer.write(self.tail_len)?;
+        for &value in &self.preceding_runs {
+            writer.write(value)?;
+        }
+        writer.write(num_values)
+    }
+
+    fn read_from(reader: &mut SequenceReader) -> io::Result<Option<Self>> {
+        let Some(num_values) = reader.next()? else {
+            return Ok(None);
+        };
+
+        let index = reader.next_of_record()?;
+        let char = reader.next_of_record()?;
+        let name = reader.next_of_record()?;
+        let tail_start = reader.next_of_record()?;
+        let tail_len = reader.next_of_record()?;
+
+        let mut preceding_runs = Vec::with_capacity(num_values as usize);
+        for _ in 0..num_values {
+            preceding_runs.push(reader.next_of_record()?);
+        }
+        reader.next_of_record()?;
+
+        Ok(Some(Self {
+            index,
+            char,
+            name,
+            preceding_runs,
+            tail_start,
+            tail_len,
+        }))
+    }
+
+    fn read_reverse_from(reader: &mut ReverseSequenceReader) -> io::Result<Option<Self>> {
+        let Some(num_values) = reader.next()? else {
+            return Ok(None);
+        };
+
+        let mut preceding_runs = Vec::with_capacity(num_values as usize);
+        for _ in 0..num_values {
+            preceding_runs.push(reader.next_of_record()?);
+        }
+        preceding_runs.reverse();
+
+        let tail_len = reader.next_of_record()?;
+        let tail_start = reader.next_of_record()?;
+        let name = reader.next_of_record()?;
+        let char = reader.next_of_record()?;
+        let index = reader.next_of_record()?;
+        reader.next_of_record()?;
+
+        Ok(Some(Self {
+            index,
+            char,
+            name,
+            preceding_runs,
+            tail_start,
+            tail_len,
+        }))
+    }
+
+    fn heap_size(&self) -> usize {
+        self.preceding_runs.capacity() * size_of::<u64>()
+    }
+}
+
+// a suffix in a priority queue or a sorter, only the key is compared
+#[derive(Debug)]
+struct Keyed {
+    key: (u64, u64),
+    suffix: Suffix,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl Record for Keyed {
+    fn write_to<W: Write>(&self, writer: &mut SequenceWriter<W>) -> io::Result<()> {
+        writer.write_record(&self.key)?;
+        writer.write_record(&self.suffix)
+    }
+
+    fn read_from(reader: &mut SequenceReader) -> io::Result<Option<Self>> {
+        let Some(key) = <(u64, u64)>::read_from(reader)? else {
+            return Ok(None);
+        };
+        let suffix = Suffix::read_from(reader)?.expect("records are always stored completely");
+
+        Ok(Some(Self { key, suffix }))
+    }
+
+    fn read_reverse_from(reader: &mut ReverseSequenceReader) -> io::Result<Option<Self>> {
+        let Some(suffix) = Suffix::read_reverse_from(reader)? else {
+            return Ok(None);
+        };
+        let key =
+            <(u64, u64)>::read_reverse_from(reader)?.expect("records are always stored completely");
+
+        Ok(Some(Self { key, suffix }))
+    }
+
+    fn heap_size(&self) -> usize {
+        self.suffix.heap_size()
+    }
+}
+
+// gives the same name to consecutive suffixes of the same class. A class is a character and
+// the name of the inducing suffix, every inducing suffix has a unique name.
+struct Namer {
+    previous_class: Option<(u64, u64)>,
+    num_names: u64,
+}
+
+// the inducing name of the LMS suffixes that start the L-type scan
+const LMS_CLASS: u64 = u64::MAX;
+
+impl Namer {
+    fn new() -> Self {
+        Self {
+            previous_class: None,
+            num_names: 0,
+        }
+    }
+
+    fn name(&mut self, class: (u64, u64)) -> u64 {
+        if self.previous_class != Some(class) {
+            self.previous_class = Some(class);
+            self.num_names += 1;
+        }
+
+        self.num_names - 1
+    }
+}
+
+// scans the text from back to front and returns the LMS suffixes with their chains in
+// descending order, starting with the virtual sentinel
+fn lms_suffixes_with_chains<T: ExternalText>(
+    text: &T,
+    context: &ExternalContext,
+) -> io::Result<(Sequence, ChainTails)> {
+    let mut reader = text.reverse_reader(context.io_buffer_size())?;
+    let mut lms_suffixes = context.create_temp_sequence()?;
+    let mut chain_tails = context.create_temp_sequence()?;
+    let max_chain_len = context.max_chain_len();
+
+    let mut lms_suffix = Suffix::new(text.len(), SENTINEL);
+    let mut index = text.len();
+
+    // the last suffix is always L-type, because of the virtual sentinel
+    let mut next_char = SENTINEL;
+    let mut next_is_s_type = false;
+
+    while let Some(char) = reader.next()? {
+        index -= 1;
+
+        let char = char + 1;
+        let is_s_type = char < next_char || (char == next_char && next_is_s_type);
+
+        if next_is_s_type && !is_s_type {
+            // the next suffix is an LMS suffix, which ends the current chain and starts a new one
+            lms_suffixes
+                .write_record(&lms_suffix.finish_chain(&mut chain_tails, max_chain_len)?)?;
+            lms_suffix = Suffix::new(index + 1, next_char);
+        }
+
+        lms_suffix.push_to_chain(char, is_s_type, &mut chain_tails, max_chain_len)?;
+
+        next_char = char;
+        next_is_s_type = is_s_type;
+    }
+
+    lms_suffixes.write_record(&lms_suffix.finish_chain(&mut chain_tails, max_chain_len)?)?;
+
+    let chain_tails = ChainTails {
+        runs: chain_tails.finish()?,
+        max_chain_len,
+    };
+
+    Ok((lms_suffixes.finish()?, chain_tails))
+}
+
+// sorts and names the LMS substrings and returns the ranks of the LMS suffixes in text order,
+// from the recursion if the names are not unique
+fn rank_lms_suffixes(
+    lms_suffixes: &Sequence,
+    chain_tails: &ChainTails,
+    context: &ExternalContext,
+) -> io::Result<Sequence> {
+    let mut sorter = Sorter::new(context);
+    let mut reader = lms_suffixes.records::<Suffix>(context.io_buffer_size())?;
+
+    while let Some(lms_suffix) = reader.next()? {
+        sorter.push(Keyed {
+            key: (lms_suffix.char, lms_suffix.index),
+            suffix: lms_suffix,
+        })?;
+    }
+
+    drop(reader);
+    let lms_suffixes_by_first_char = sorter.finish()?;
+
+    let mut namer = Namer::new();
+    let inducers = induce_l_type_suffixes(
+        &lms_suffixes_by_first_char,
+        chain_tails,
+        &mut namer,
+        |_| Ok(()),
+        context,
+    )?;
+    drop(lms_suffixes_by_first_char);
+
+    // the LMS suffixes end their chains, except for the first suffix of the text
+    let mut reverse_sorted_lms_substrings = context.create_temp_sequence()?;
+    induce_s_type_suffixes(
+        &inducers,
+        chain_tails,
+        &mut namer,
+        |suffix, name| {
+            if suffix.preceding().is_none() && suffix.index > 0 {
+                reverse_sorted_lms_substrings.write_record(&(suffix.index, name))?;
+            }
+
+            Ok(())
+        },
+        context,
+    )?;
+    let reverse_sorted_lms_substrings = reverse_sorted_lms_substrings.finish()?;
+    drop(inducers);
+
+    let (reduced_text, num_names) = reduced_text(&reverse_sorted_lms_substrings, context)?;
+    let num_lms_substrings = reduced_text.len();
+    drop(reverse_sorted_lms_substrings);
+
+    // if all LMS substrings are unique, they are sorted like their LMS suffixes
+    if num_names == num_lms_substrings {
+        return Ok(reduced_text);
+    }
+
+    let mut reduced_suffix_array = context.create_temp_sequence()?;
+    construct_suffix_array(
+        &ReducedText::new(reduced_text, num_names),
+        &mut reduced_suffix_array,
+        context,
+    )?;
+    let reduced_suffix_array = reduced_suffix_array.finish()?;
+
+    let mut sorter = Sorter::new(context);
+    let mut reader = reduced_suffix_array.reader(context.io_buffer_size())?;
+    let mut rank = 0;
+
+    while let Some(reduced_index) = reader.next()? {
+        sorter.push((reduced_index, rank))?;
+        rank += 1;
+    }
+
+    drop(reader);
+    let reduced_indices_and_ranks = sorter.finish()?;
+    drop(reduced_suffix_array);
+
+    let mut reader = reduced_indices_and_ranks.records::<(u64, u64)>(context.io_buffer_size())?;
+    let mut ranks = context.create_temp_sequence()?;
+
+    while let Some((_, rank)) = reader.next()? {
+        ranks.write(rank)?;
+    }
+
+    ranks.finish()
+}
+
+// the names of the LMS substrings in text order, such that they are sorted like the substrings
+fn reduced_text(
+    reverse_sorted_lms_substrings: &Sequence,
+    context: &ExternalContext,
+) -> io::Result<(Sequence, u64)> {
+    let mut reader =
+        reverse_sorted_lms_substrings.reverse_records::<(u64, u64)>(context.io_buffer_size())?;
+    let mut sorter = Sorter::new(context);
+
+    let mut previous_class_name = None;
+    let mut num_names = 0;
+
+    while let Some((index, class_name)) = reader.next()? {
+        if previous_class_name != Some(class_name) {
+            previous_class_name = Some(class_name);
+            num_names += 1;
+        }
+
+        sorter.push((index, num_names - 1))?;
+    }
+
+    drop(reader);
+    let indices_and_names = sorter.finish()?;
+
+    let mut reader = indices_and_names.records::<(u64, u64)>(context.io_buffer_size())?;
+    let mut reduced_text = context.create_temp_sequence()?;
+
+    while let Some((_, name)) = reader.next()? {
+        reduced_text.write(name)?;
+    }
+
+    Ok((reduced_text.finish()?, num_names))
+}
+
+// sorts the LMS suffixes by their ranks, the virtual sentinel comes first
+fn sort_lms_suffixes(
+    lms_suffixes: &Sequence,
+    lms_ranks: &Sequence,
+    context: &ExternalContext,
+) -> io::Result<Sequence> {
+    // ascending text order, the virtual sentinel comes last
+    let mut lms_suffixes = lms_suffixes.reverse_records::<Suffix>(context.io_buffer_size())?;
+    let mut lms_ranks = lms_ranks.reader(context.io_buffer_size())?;
+    let mut sorter = Sorter::new(context);
+
+    while let Some(lms_suffix) = lms_suffixes.next()? {
+        let rank = if lms_suffix.char == SENTINEL {
+            0
+        } else {
+            lms_ranks.next_of_record()? + 1
+        };
+
+        sorter.push(Keyed {
+            key: (rank, 0),
+            suffix: lms_suffix,
+        })?;
+    }
+
+    drop(lms_suffixes);
+    drop(lms_ranks);
+
+    sorter.finish()
+}
+
+// scans the suffixes from small to large. The LMS suffixes are sorted by at least their first
+// character, the queue contains the induced L-type suffixes ordered by their character and the
+// time at which they were induced. Returns the L-type suffixes that induce S-type suffixes, in
+// the order of the scan.
+fn induce_l_type_suffixes(
+    sorted_lms_suffixes: &Sequence,
+    chain_tails: &ChainTails,
+    namer: &mut Namer,
+    mut on_l_type_suffix: impl FnMut(&Suffix) -> io::Result<()>,
+    context: &ExternalContext,
+) -> io::Result<Sequence> {
+    let mut lms_suffixes = sorted_lms_suffixes.records::<Keyed>(context.io_buffer_size())?;
+    let mut queue = PriorityQueue::<Keyed>::new(context);
+    let mut inducers = context.create_temp_sequence()?;
+    let mut time = 0;
+
+    loop {
+        // the L-type suffixes come first in their buckets
+        let next_is_l_type = match (queue.peek(), lms_suffixes.peek()?) {
+            (Some(l_type_suffix), Some(lms_suffix)) => {
+                l_type_suffix.suffix.char <= lms_suffix.suffix.char
+            }
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+
+        let suffix = if next_is_l_type {
+            let mut suffix = queue.pop()?.unwrap().suffix;
+            on_l_type_suffix(&suffix)?;
+            suffix.name = namer.name((suffix.char, suffix.name));
+            suffix
+        } else {
+            let mut suffix = lms_suffixes.next()?.unwrap().suffix;
+            suffix.name = namer.name((suffix.char, LMS_CLASS));
+            suffix
+        };
+
+        time += 1;
+
+        match suffix.preceding() {
+            Some((char, false)) => {
+                let name = suffix.name;
+                queue.push(Keyed {
+                    key: (char, time),
+                    suffix: suffix.into_preceding(name, chain_tails)?,
+                })?;
+            }
+            Some((_, true)) => inducers.write_record(&suffix)?,
+            None => {}
+        }
+    }
+
+    inducers.finish()
+}
+
+// scans the suffixes from large to small. The inducers are the L-type suffixes with S-type
+// suffixes before them, the queue contains the induced S-type suffixes ordered by their
+// character and the time at which they were induced, both descending.
+fn induce_s_type_suffixes(
+    inducers: &Sequence,
+    chain_tails: &ChainTails,
+    namer: &mut Namer,
+    mut on_s_type_suffix: impl FnMut(&Suffix, u64) -> io::Result<()>,
+    context: &ExternalContext,
+) -> io::Result<()> {
+    let mut inducers = inducers.reverse_records::<Suffix>(context.io_buffer_size())?;
+    let mut queue = PriorityQueue::<Keyed>::new(context);
+    let mut time = 0;
+
+    namer.previous_class = None;
+
+    loop {
+        // the S-type suffixes come last in their buckets
+        let next_is_s_type = match (queue.peek(), inducers.peek()?) {
+            (Some(s_type_suffix), Some(inducer)) => s_type_suffix.suffix.char >= inducer.char,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+
+        let suffix = if next_is_s_type {
+            let mut suffix = queue.pop()?.unwrap().suffix;
+            suffix.name = namer.name((suffix.char, suffix.name));
+            on_s_type_suffix(&suffix, suffix.name)?;
+            suffix
+        } else {
+            inducers.next()?.unwrap()
+        };
+
+        time += 1;
+
+        // the chain of an S-type suffix with an L-type suffix before it ends at this LMS suffix
+        if let Some((char, true)) = suffix.preceding() {
+            let name = suffix.name;
+            queue.push(Keyed {
+                key: (u64::MAX - char, time),
+                suffix: suffix.into_preceding(name, chain_tails)?,
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+// L-type suffixes come first in every bucket
+fn merge_l_and_s_type_suffixes<W: Write>(
+    l_type_suffixes: &Sequence,
+    reverse_s_type_suffixes: &Sequence,
+    output: &mut SequenceWriter<W>,
+    context: &ExternalContext,
+) -> io::Result<()> {
+    let mut l_type_suffixes = l_type_suffixes.records::<(u64, u64)>(context.io_buffer_size())?;
+    let mut s_type_suffixes =
+        reverse_s_type_suffixes.reverse_records::<(u64, u64)>(context.io_buffer_size())?;
+
+    loop {
+        let next_is_l_type = match (l_type_suffixes.peek()?, s_type_suffixes.peek()?) {
+            (Some((l_char, _)), Some((s_char, _))) => l_char <= s_char,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+
+        let (_, index) = if next_is_l_type {
+            l_type_suffixes.next()?
+        } else {
+            s_type_suffixes.next()?
+        }
+        .unwrap();
+
+        output.write(index)?;
+    }
+
+    output.flush()
+}
@@ -0,0 +1,181 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io;
+
+use super::ExternalContext;
+use super::files::{Record, RecordReader, Sequence};
+
+// sorts records in the order of their Ord implementation. Sorted runs that fit into the memory
+// budget are written to temporary files and merged afterwards.
+pub struct Sorter<'a, T> {
+    context: &'a ExternalContext,
+    buffer: ItemBuffer<T>,
+    runs: Vec<Sequence>,
+}
+
+impl<'a, T: Record + Ord> Sorter<'a, T> {
+    pub fn new(context: &'a ExternalContext) -> Self {
+        Self {
+            context,
+            buffer: ItemBuffer::new(context),
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, record: T) -> io::Result<()> {
+        if !self.buffer.has_room_for(&record) {
+            let run = self.write_sorted_buffer()?;
+            self.runs.push(run);
+        }
+
+        self.buffer.push(record);
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<Sequence> {
+        if !self.buffer.items.is_empty() || self.runs.is_empty() {
+            let run = self.write_sorted_buffer()?;
+            self.runs.push(run);
+        }
+
+        drop(self.buffer);
+
+        while self.runs.len() > 1 {
+            let fan_in = self.context.merge_fan_in();
+            let mut runs = std::mem::take(&mut self.runs).into_iter().peekable();
+
+            while runs.peek().is_some() {
+                let mut runs_to_merge: Vec<_> = runs.by_ref().take(fan_in).collect();
+
+                let merged_run = if runs_to_merge.len() == 1 {
+                    runs_to_merge.pop().unwrap()
+                } else {
+                    let readers = runs_to_merge
+                        .iter()
+                        .map(|run| run.records::<T>(self.context.io_buffer_size()))
+                        .collect::<io::Result<_>>()?;
+
+                    merge_runs(readers, self.context)?
+                };
+
+                self.runs.push(merged_run);
+            }
+        }
+
+        Ok(self.runs.pop().unwrap())
+    }
+
+    fn write_sorted_buffer(&mut self) -> io::Result<Sequence> {
+        self.buffer.items.sort_unstable();
+
+        let mut writer = self.context.create_temp_sequence()?;
+        for record in self.buffer.items.drain(..) {
+            writer.write_record(&record)?;
+        }
+
+        self.buffer.heap_size = 0;
+
+        writer.finish()
+    }
+}
+
+// the records that a sorter holds in memory. The capacity is reserved up front, such that the
+// vector does not grow beyond the memory budget.
+struct ItemBuffer<T> {
+    items: Vec<T>,
+    heap_size: usize,
+    max_heap_size: usize,
+}
+
+impl<T: Record> ItemBuffer<T> {
+    fn new(context: &ExternalContext) -> Self {
+        // half of the memory for the items themselves and half for their heap allocations
+        let item_memory = context.item_memory() / 2;
+
+        Self {
+            items: Vec::with_capacity((item_memory / size_of::<T>()).max(1)),
+            heap_size: 0,
+            max_heap_size: item_memory,
+        }
+    }
+
+    fn has_room_for(&self, record: &T) -> bool {
+        self.items.is_empty()
+            || (self.items.len() < self.items.capacity()
+                && self.heap_size + record.heap_size() <= self.max_heap_size)
+    }
+
+    fn push(&mut self, record: T) {
+        self.heap_size += record.heap_size();
+        self.items.push(record);
+    }
+}
+
+// merges sorted runs into a single one
+pub fn merge_runs<T: Record + Ord>(
+    mut readers: Vec<RecordReader<T>>,
+    context: &ExternalContext,
+) -> io::Result<Sequence> {
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (run_index, reader) in readers.iter_mut().enumerate() {
+        if let Some(record) = reader.next()? {
+            heap.push(Reverse((record, run_index)));
+        }
+    }
+
+    let mut writer = context.create_temp_sequence()?;
+
+    while let Some(Reverse((record, run_index))) = heap.pop() {
+        writer.write_record(&record)?;
+
+        if let Some(record) = readers[run_index].next()? {
+            heap.push(Reverse((record, run_index)));
+        }
+    }
+
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let context = ExternalContext::new(temp_dir.path().to_path_buf(), 0);
+
+        let pairs: Vec<_> = (0..1000u64).map(|i| ((i * 7919) % 101, i)).collect();
+
+        let mut sorter = Sorter::new(&context);
+        for &pair in &pairs {
+            sorter.push(pair).unwrap();
+        }
+        let sorted_pairs = sorter.finish().unwrap();
+
+        let mut expected = pairs;
+        expected.sort_unstable();
+
+        let mut reader = sorted_pairs
+            .records::<(u64, u64)>(context.io_buffer_size())
+            .unwrap();
+        for pair in expected {
+            assert_eq!(reader.next().unwrap(), Some(pair));
+        }
+        assert_eq!(reader.next().unwrap(), None);
+    }
+
+    #[test]
+    fn test_sorter_empty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let context = ExternalContext::new(temp_dir.path().to_path_buf(), 0);
+
+        let sorted_pairs = Sorter::<(u64, u64)>::new(&context).finish().unwrap();
+        let mut reader = sorted_pairs
+            .records::<(u64, u64)>(context.io_buffer_size())
+            .unwrap();
+
+        assert_eq!(reader.next().unwrap(), None);
+    }
+}
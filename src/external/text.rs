@@ -0,0 +1,113 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use super::files::{ReverseSequenceReader, Sequence};
+
+const U64_SIZE: usize = size_of::<u64>();
+
+// a text that is not loaded into memory. The construction only reads it from back to front,
+// in chunks, unless it is small enough to construct its suffix array in memory.
+pub trait ExternalText {
+    fn len(&self) -> u64;
+
+    fn alphabet_size(&self) -> u64;
+
+    fn reverse_reader(&self, io_buffer_size: usize) -> io::Result<ReverseSequenceReader>;
+
+    // approximate number of bytes needed to construct the suffix array in memory
+    fn in_memory_construction_size(&self) -> u64;
+
+    fn construct_suffix_array_in_memory(&self) -> io::Result<Vec<u64>>;
+}
+
+pub struct ByteText {
+    file: File,
+    len: u64,
+}
+
+impl ByteText {
+    pub fn new(file: File) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+
+        Ok(Self { file, len })
+    }
+}
+
+impl ExternalText for ByteText {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn alphabet_size(&self) -> u64 {
+        u8::MAX as u64 + 1
+    }
+
+    fn reverse_reader(&self, io_buffer_size: usize) -> io::Result<ReverseSequenceReader> {
+        ReverseSequenceReader::new(&self.file, self.len, 1, io_buffer_size)
+    }
+
+    fn in_memory_construction_size(&self) -> u64 {
+        // the text, the suffix array and a compacted copy of the text
+        self.len * (U64_SIZE as u64 + 2)
+    }
+
+    fn construct_suffix_array_in_memory(&self) -> io::Result<Vec<u64>> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(0))?;
+
+        let mut text = Vec::with_capacity(self.len as usize);
+        file.read_to_end(&mut text)?;
+
+        Ok(crate::SaisBuilder::<u8, u64>::new().construct_suffix_array(&text))
+    }
+}
+
+// the text of a recursion level, where every character is the name of an LMS substring
+pub struct ReducedText {
+    names: Sequence,
+    num_names: u64,
+}
+
+impl ReducedText {
+    pub fn new(names: Sequence, num_names: u64) -> Self {
+        Self { names, num_names }
+    }
+}
+
+impl ExternalText for ReducedText {
+    fn len(&self) -> u64 {
+        self.names.len()
+    }
+
+    fn alphabet_size(&self) -> u64 {
+        self.num_names
+    }
+
+    fn reverse_reader(&self, io_buffer_size: usize) -> io::Result<ReverseSequenceReader> {
+        self.names.reverse_reader(io_buffer_size)
+    }
+
+    fn in_memory_construction_size(&self) -> u64 {
+        (self.len() * 2 + self.alphabet_size() * 2) * U64_SIZE as u64
+    }
+
+    fn construct_suffix_array_in_memory(&self) -> io::Result<Vec<u64>> {
+        let mut reader = self.names.reader(U64_SIZE * 1024)?;
+        let mut text = Vec::with_capacity(self.len() as usize);
+        while let Some(name) = reader.next()? {
+            text.push(name);
+        }
+
+        let mut suffix_array_buffer = vec![u64::MAX; text.len()];
+
+        crate::construct_suffix_array_with_sais(
+            &text,
+            self.num_names - 1,
+            crate::BucketGranularity::for_alphabet_size(self.num_names as usize),
+            &mut suffix_array_buffer,
+            crate::algorithm::CharFrequencies::Count,
+        );
+
+        Ok(suffix_array_buffer)
+    }
+}
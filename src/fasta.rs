@@ -0,0 +1,415 @@
+// Reading FASTA/FASTQ records into a dense alphabet, such that the suffix array can be built
+// over the concatenation of all records. The code 0 is reserved for the separator that follows
+// every record, the residues are encoded as 1..=alphabet.max_code().
+
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Range;
+
+use crate::{IndexStorage, SaisBuilder};
+
+const SEPARATOR: u8 = 0;
+const INVALID: u8 = u8::MAX;
+
+const DNA_RESIDUES: &[u8] = b"ACGT";
+const IUPAC_AMBIGUITY_CODES: &[u8] = b"RYSWKMBDHVN";
+const PROTEIN_RESIDUES: &[u8] = b"ACDEFGHIKLMNPQRSTVWY";
+const PROTEIN_EXTRA_CODES: &[u8] = b"BZJUOX*";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alphabet {
+    // A, C, G, T (U is read as T), all IUPAC ambiguity codes are collapsed into N
+    Dna,
+    // A, C, G, T (U is read as T) and every IUPAC ambiguity code on its own
+    DnaIupac,
+    // the 20 standard amino acids, B, Z, J, U, O, X and the stop codon *
+    Protein,
+}
+
+impl Alphabet {
+    pub fn max_code(self) -> u8 {
+        match self {
+            Alphabet::Dna => DNA_RESIDUES.len() as u8 + 1,
+            Alphabet::DnaIupac => (DNA_RESIDUES.len() + IUPAC_AMBIGUITY_CODES.len()) as u8,
+            Alphabet::Protein => (PROTEIN_RESIDUES.len() + PROTEIN_EXTRA_CODES.len()) as u8,
+        }
+    }
+
+    // lowercase residues are encoded like uppercase ones
+    pub fn encode(self, residue: u8) -> Option<u8> {
+        let code = self.encoding_table()[residue.to_ascii_uppercase() as usize];
+        (code != INVALID).then_some(code)
+    }
+
+    pub fn decode(self, code: u8) -> Option<u8> {
+        if code == SEPARATOR || code > self.max_code() {
+            return None;
+        }
+
+        match self {
+            Alphabet::Dna if code as usize == DNA_RESIDUES.len() + 1 => Some(b'N'),
+            Alphabet::Dna | Alphabet::DnaIupac => DNA_RESIDUES
+                .iter()
+                .chain(IUPAC_AMBIGUITY_CODES)
+                .nth(code as usize - 1)
+                .copied(),
+            Alphabet::Protein => PROTEIN_RESIDUES
+                .iter()
+                .chain(PROTEIN_EXTRA_CODES)
+                .nth(code as usize - 1)
+                .copied(),
+        }
+    }
+
+    fn encoding_table(self) -> [u8; 256] {
+        let mut table = [INVALID; 256];
+
+        let residues: Vec<_> = match self {
+            Alphabet::Dna | Alphabet::DnaIupac => {
+                DNA_RESIDUES.iter().chain(IUPAC_AMBIGUITY_CODES).collect()
+            }
+            Alphabet::Protein => PROTEIN_RESIDUES.iter().chain(PROTEIN_EXTRA_CODES).collect(),
+        };
+
+        for (index, &residue) in residues.into_iter().enumerate() {
+            table[residue as usize] = (index as u8 + 1).min(self.max_code());
+        }
+
+        if self != Alphabet::Protein {
+            table[b'U' as usize] = table[b'T' as usize];
+        }
+
+        table
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    // the header up to the first whitespace
+    pub name: String,
+    pub sequence: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum FastaError {
+    Io(io::Error),
+    InvalidFormat {
+        line: usize,
+        message: String,
+    },
+    InvalidResidue {
+        record: String,
+        offset: usize,
+        residue: u8,
+    },
+}
+
+impl fmt::Display for FastaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FastaError::Io(error) => write!(f, "I/O error: {error}"),
+            FastaError::InvalidFormat { line, message } => {
+                write!(f, "invalid FASTA/FASTQ format in line {line}: {message}")
+            }
+            FastaError::InvalidResidue {
+                record,
+                offset,
+                residue,
+            } => write!(
+                f,
+                "invalid residue {:?} at offset {offset} of record {record}",
+                *residue as char
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FastaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FastaError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FastaError {
+    fn from(error: io::Error) -> Self {
+        FastaError::Io(error)
+    }
+}
+
+// the format is detected from the first character: '>' for FASTA, '@' for FASTQ. FASTA sequences
+// may span multiple lines, FASTQ records are expected to consist of exactly four lines.
+pub fn read_records(reader: impl Read) -> Result<Vec<Record>, FastaError> {
+    let mut lines = Lines::new(reader);
+
+    let Some(first_line) = lines.next_non_empty()? else {
+        return Ok(Vec::new());
+    };
+
+    match first_line.first() {
+        Some(b'>') => read_fasta_records(first_line, lines),
+        Some(b'@') => read_fastq_records(first_line, lines),
+        _ => Err(lines.format_error("expected a FASTA or FASTQ header")),
+    }
+}
+
+fn read_fasta_records<R: Read>(
+    first_line: Vec<u8>,
+    mut lines: Lines<R>,
+) -> Result<Vec<Record>, FastaError> {
+    let mut records = vec![Record {
+        name: record_name(&first_line),
+        sequence: Vec::new(),
+    }];
+
+    while let Some(line) = lines.next_non_empty()? {
+        if line[0] == b'>' {
+            records.push(Record {
+                name: record_name(&line),
+                sequence: Vec::new(),
+            });
+        } else if line[0] != b';' {
+            let sequence = &mut records.last_mut().unwrap().sequence;
+            sequence.extend(line.iter().filter(|byte| !byte.is_ascii_whitespace()));
+        }
+    }
+
+    Ok(records)
+}
+
+fn read_fastq_records<R: Read>(
+    first_line: Vec<u8>,
+    mut lines: Lines<R>,
+) -> Result<Vec<Record>, FastaError> {
+    let mut records = Vec::new();
+    let mut maybe_header = Some(first_line);
+
+    while let Some(header) = maybe_header {
+        if header[0] != b'@' {
+            return Err(lines.format_error("expected a FASTQ header starting with '@'"));
+        }
+
+        let sequence = lines
+            .next()?
+            .ok_or_else(|| lines.format_error("missing sequence line"))?;
+
+        let separator = lines
+            .next()?
+            .ok_or_else(|| lines.format_error("missing '+' line"))?;
+        if separator.first() != Some(&b'+') {
+            return Err(lines.format_error("expected a line starting with '+'"));
+        }
+
+        let quality = lines
+            .next()?
+            .ok_or_else(|| lines.format_error("missing quality line"))?;
+        if quality.len() != sequence.len() {
+            return Err(lines.format_error("the quality line has a different length"));
+        }
+
+        records.push(Record {
+            name: record_name(&header),
+            sequence,
+        });
+
+        maybe_header = lines.next_non_empty()?;
+    }
+
+    Ok(records)
+}
+
+fn record_name(header: &[u8]) -> String {
+    let name = header[1..]
+        .split(|byte| byte.is_ascii_whitespace())
+        .next()
+        .unwrap_or_default();
+
+    String::from_utf8_lossy(name).into_owned()
+}
+
+struct Lines<R> {
+    reader: BufReader<R>,
+    line_number: usize,
+}
+
+impl<R: Read> Lines<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            line_number: 0,
+        }
+    }
+
+    // without the line break
+    fn next(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut line = Vec::new();
+
+        if self.reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+
+        self.line_number += 1;
+
+        while let Some(b'\n' | b'\r') = line.last() {
+            line.pop();
+        }
+
+        Ok(Some(line))
+    }
+
+    fn next_non_empty(&mut self) -> io::Result<Option<Vec<u8>>> {
+        while let Some(line) = self.next()? {
+            if !line.is_empty() {
+                return Ok(Some(line));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn format_error(&self, message: &str) -> FastaError {
+        FastaError::InvalidFormat {
+            line: self.line_number,
+            message: message.to_string(),
+        }
+    }
+}
+
+// the encoded concatenation of records, each followed by a separator
+pub struct SequenceCollection {
+    alphabet: Alphabet,
+    text: Vec<u8>,
+    record_names: Vec<String>,
+    record_starts: Vec<usize>,
+}
+
+impl SequenceCollection {
+    pub fn read(reader: impl Read, alphabet: Alphabet) -> Result<Self, FastaError> {
+        Self::from_records(read_records(reader)?, alphabet)
+    }
+
+    pub fn from_records(
+        records: impl IntoIterator<Item = Record>,
+        alphabet: Alphabet,
+    ) -> Result<Self, FastaError> {
+        let encoding_table = alphabet.encoding_table();
+        let mut text = Vec::new();
+        let mut record_names = Vec::new();
+        let mut record_starts = Vec::new();
+
+        for record in records {
+            record_starts.push(text.len());
+
+            for (offset, &residue) in record.sequence.iter().enumerate() {
+                let code = encoding_table[residue.to_ascii_uppercase() as usize];
+
+                if code == INVALID {
+                    return Err(FastaError::InvalidResidue {
+                        record: record.name,
+                        offset,
+                        residue,
+                    });
+                }
+
+                text.push(code);
+            }
+
+            text.push(SEPARATOR);
+            record_names.push(record.name);
+        }
+
+        Ok(Self {
+            alphabet,
+            text,
+            record_names,
+            record_starts,
+        })
+    }
+
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    pub fn text(&self) -> &[u8] {
+        &self.text
+    }
+
+    pub fn num_records(&self) -> usize {
+        self.record_names.len()
+    }
+
+    pub fn record_name(&self, record_index: usize) -> &str {
+        &self.record_names[record_index]
+    }
+
+    // the range of the record in the text, without its separator
+    pub fn record_range(&self, record_index: usize) -> Range<usize> {
+        let start = self.record_starts[record_index];
+        let end = self
+            .record_starts
+            .get(record_index + 1)
+            .map_or(self.text.len(), |&next_start| next_start)
+            - 1;
+
+        start..end
+    }
+
+    // translates a text position into the record name and the offset in that record.
+    // returns None for the positions of separators.
+    pub fn locate(&self, text_position: usize) -> Option<(&str, usize)> {
+        if self
+            .text
+            .get(text_position)
+            .is_none_or(|&code| code == SEPARATOR)
+        {
+            return None;
+        }
+
+        let record_index = self
+            .record_starts
+            .partition_point(|&start| start <= text_position)
+            - 1;
+
+        Some((
+            &self.record_names[record_index],
+            text_position - self.record_starts[record_index],
+        ))
+    }
+
+    pub fn encode_pattern(&self, pattern: &[u8]) -> Result<Vec<u8>, FastaError> {
+        pattern
+            .iter()
+            .enumerate()
+            .map(|(offset, &residue)| {
+                self.alphabet
+                    .encode(residue)
+                    .ok_or(FastaError::InvalidResidue {
+                        record: String::from("pattern"),
+                        offset,
+                        residue,
+                    })
+            })
+            .collect()
+    }
+
+    // the suffix array of the whole text, including the suffixes that start at separators
+    pub fn concatenated_suffix_array<I: IndexStorage>(&self) -> Vec<I> {
+        SaisBuilder::<u8, I>::new()
+            .with_max_char(self.alphabet.max_code())
+            .construct_suffix_array(&self.text)
+    }
+
+    // the suffixes of all records, without the ones that start at separators. As all separators
+    // are equal, suffixes that are equal up to the end of their records are ordered by the
+    // following records.
+    pub fn generalized_suffix_array<I: IndexStorage>(&self) -> Vec<I> {
+        let mut suffix_array = self.concatenated_suffix_array::<I>();
+
+        // the separator suffixes are all at the front, because the separator is the smallest code
+        suffix_array.drain(..self.num_records());
+
+        suffix_array
+    }
+}
@@ -0,0 +1,1005 @@
+// Versioned binary file format for suffix arrays and their companion arrays.
+//
+// Layout (all header integers in the endianness recorded in the header):
+//
+//   offset  size  field
+//        0     8  magic number "SAISDRUM"
+//        8     2  format version
+//       10     1  index width in bytes
+//       11     1  character width in bytes (of text and BWT)
+//       12     1  endianness (0 = little, 1 = big)
+//       13     1  flags (bit 0: LCP, bit 1: BWT, bit 2: samples)
+//       14     2  reserved, zero
+//       16     8  text length
+//       24     8  alphabet size
+//       32     8  hash of the text
+//       40     8  BWT primary index
+//       48     8  sample rate
+//       56     8  number of samples
+//       64     8  checksum of the arrays
+//       72     8  checksum of the header bytes 0..72
+//
+// The header is followed by the suffix array, the LCP array, the BWT and the samples, as far as
+// they are stored. Every array starts at an offset that is a multiple of 8.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::IndexStorage;
+
+pub const FORMAT_VERSION: u16 = 1;
+
+const MAGIC_NUMBER: &[u8; 8] = b"SAISDRUM";
+const HEADER_LEN: usize = 80;
+const HEADER_CHECKSUM_OFFSET: usize = 72;
+const ALIGNMENT: usize = 8;
+// in values, for streams of unknown length
+const READ_CHUNK_LEN: usize = 1 << 16;
+
+const LCP_FLAG: u8 = 1 << 0;
+const BWT_FLAG: u8 = 1 << 1;
+const SAMPLES_FLAG: u8 = 1 << 2;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn native() -> Self {
+        if cfg!(target_endian = "little") {
+            Endianness::Little
+        } else {
+            Endianness::Big
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Endianness::Little),
+            1 => Some(Endianness::Big),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        }
+    }
+
+    fn u16_from_bytes(self, bytes: &[u8]) -> u16 {
+        let bytes = bytes.try_into().unwrap();
+
+        match self {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    fn u64_from_bytes(self, bytes: &[u8]) -> u64 {
+        let bytes = bytes.try_into().unwrap();
+
+        match self {
+            Endianness::Little => u64::from_le_bytes(bytes),
+            Endianness::Big => u64::from_be_bytes(bytes),
+        }
+    }
+
+    fn u16_to_bytes(self, value: u16) -> [u8; 2] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+
+    fn u64_to_bytes(self, value: u64) -> [u8; 8] {
+        match self {
+            Endianness::Little => value.to_le_bytes(),
+            Endianness::Big => value.to_be_bytes(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuffixArrayFileHeader {
+    pub version: u16,
+    pub index_width: u8,
+    pub char_width: u8,
+    pub endianness: Endianness,
+    pub has_lcp: bool,
+    pub has_bwt: bool,
+    pub has_samples: bool,
+    pub text_len: u64,
+    pub alphabet_size: u64,
+    pub text_hash: u64,
+    pub bwt_primary_index: u64,
+    pub sample_rate: u64,
+    pub num_samples: u64,
+    pub checksum: u64,
+}
+
+impl SuffixArrayFileHeader {
+    // only reads and validates the header, e.g. to find out the widths of a file
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, FormatError> {
+        Self::read_from(File::open(path)?)
+    }
+
+    pub fn read_from(mut reader: impl Read) -> Result<Self, FormatError> {
+        let mut header_bytes = [0; HEADER_LEN];
+        reader.read_exact(&mut header_bytes)?;
+
+        Self::from_bytes(&header_bytes)
+    }
+
+    fn to_bytes(self) -> [u8; HEADER_LEN] {
+        let endianness = self.endianness;
+        let mut bytes = [0; HEADER_LEN];
+
+        bytes[0..8].copy_from_slice(MAGIC_NUMBER);
+        bytes[8..10].copy_from_slice(&endianness.u16_to_bytes(self.version));
+        bytes[10] = self.index_width;
+        bytes[11] = self.char_width;
+        bytes[12] = endianness.to_byte();
+        bytes[13] = self.flags();
+
+        for (offset, value) in [
+            (16, self.text_len),
+            (24, self.alphabet_size),
+            (32, self.text_hash),
+            (40, self.bwt_primary_index),
+            (48, self.sample_rate),
+            (56, self.num_samples),
+            (64, self.checksum),
+        ] {
+            bytes[offset..offset + 8].copy_from_slice(&endianness.u64_to_bytes(value));
+        }
+
+        let header_checksum = fnv1a(FNV_OFFSET_BASIS, &bytes[..HEADER_CHECKSUM_OFFSET]);
+        bytes[HEADER_CHECKSUM_OFFSET..].copy_from_slice(&endianness.u64_to_bytes(header_checksum));
+
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Result<Self, FormatError> {
+        if &bytes[0..8] != MAGIC_NUMBER {
+            return Err(FormatError::InvalidMagicNumber);
+        }
+
+        let endianness = Endianness::from_byte(bytes[12]).ok_or(FormatError::InvalidHeader)?;
+
+        let stored_header_checksum = endianness.u64_from_bytes(&bytes[HEADER_CHECKSUM_OFFSET..]);
+        if fnv1a(FNV_OFFSET_BASIS, &bytes[..HEADER_CHECKSUM_OFFSET]) != stored_header_checksum {
+            return Err(FormatError::HeaderChecksumMismatch);
+        }
+
+        let version = endianness.u16_from_bytes(&bytes[8..10]);
+        if version != FORMAT_VERSION {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+
+        let flags = bytes[13];
+        if flags & !(LCP_FLAG | BWT_FLAG | SAMPLES_FLAG) != 0 {
+            return Err(FormatError::InvalidHeader);
+        }
+
+        let read_u64 = |offset: usize| endianness.u64_from_bytes(&bytes[offset..offset + 8]);
+
+        Ok(Self {
+            version,
+            index_width: bytes[10],
+            char_width: bytes[11],
+            endianness,
+            has_lcp: flags & LCP_FLAG != 0,
+            has_bwt: flags & BWT_FLAG != 0,
+            has_samples: flags & SAMPLES_FLAG != 0,
+            text_len: read_u64(16),
+            alphabet_size: read_u64(24),
+            text_hash: read_u64(32),
+            bwt_primary_index: read_u64(40),
+            sample_rate: read_u64(48),
+            num_samples: read_u64(56),
+            checksum: read_u64(64),
+        })
+    }
+
+    fn flags(&self) -> u8 {
+        let mut flags = 0;
+
+        if self.has_lcp {
+            flags |= LCP_FLAG;
+        }
+        if self.has_bwt {
+            flags |= BWT_FLAG;
+        }
+        if self.has_samples {
+            flags |= SAMPLES_FLAG;
+        }
+
+        flags
+    }
+
+    fn check_widths<C, I>(&self) -> Result<(), FormatError> {
+        if self.index_width as usize != size_of::<I>() {
+            return Err(FormatError::IndexWidthMismatch {
+                expected: size_of::<I>(),
+                found: self.index_width as usize,
+            });
+        }
+
+        if self.char_width as usize != size_of::<C>() {
+            return Err(FormatError::CharWidthMismatch {
+                expected: size_of::<C>(),
+                found: self.char_width as usize,
+            });
+        }
+
+        Ok(())
+    }
+
+    // the lengths in bytes of the stored arrays, in file order. The lengths come from the file, so
+    // they are checked for overflows
+    fn array_byte_lens(&self) -> Result<[usize; 4], FormatError> {
+        let byte_len = |len: u64, width: u8, is_stored: bool| {
+            if !is_stored {
+                return Ok(0);
+            }
+
+            usize::try_from(len)
+                .ok()
+                .and_then(|len| len.checked_mul(width as usize))
+                .ok_or(FormatError::InvalidHeader)
+        };
+
+        Ok([
+            byte_len(self.text_len, self.index_width, true)?,
+            byte_len(self.text_len, self.index_width, self.has_lcp)?,
+            byte_len(self.text_len, self.char_width, self.has_bwt)?,
+            byte_len(self.num_samples, self.index_width, self.has_samples)?,
+        ])
+    }
+
+    fn file_len(&self) -> Result<usize, FormatError> {
+        self.array_byte_lens()?
+            .iter()
+            .try_fold(HEADER_LEN, |file_len, &len| {
+                len.checked_next_multiple_of(ALIGNMENT)
+                    .and_then(|len| file_len.checked_add(len))
+            })
+            .ok_or(FormatError::InvalidHeader)
+    }
+
+    pub fn matches_text<C: IndexStorage>(&self, text: &[C]) -> bool {
+        self.text_len == text.len() as u64 && self.text_hash == text_hash(text)
+    }
+}
+
+#[derive(Debug)]
+pub enum FormatError {
+    Io(io::Error),
+    InvalidMagicNumber,
+    InvalidHeader,
+    HeaderChecksumMismatch,
+    UnsupportedVersion(u16),
+    IndexWidthMismatch { expected: usize, found: usize },
+    CharWidthMismatch { expected: usize, found: usize },
+    EndiannessMismatch,
+    UnexpectedFileLength { expected: usize, found: usize },
+    ChecksumMismatch,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Io(error) => write!(f, "I/O error: {error}"),
+            FormatError::InvalidMagicNumber => write!(f, "not a sais-drum suffix array file"),
+            FormatError::InvalidHeader => write!(f, "invalid header"),
+            FormatError::HeaderChecksumMismatch => write!(f, "the header checksum does not match"),
+            FormatError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {version}")
+            }
+            FormatError::IndexWidthMismatch { expected, found } => write!(
+                f,
+                "expected indices of {expected} bytes, but the file contains indices of {found} bytes"
+            ),
+            FormatError::CharWidthMismatch { expected, found } => write!(
+                f,
+                "expected characters of {expected} bytes, but the file contains characters of {found} bytes"
+            ),
+            FormatError::EndiannessMismatch => {
+                write!(f, "the file does not have the native endianness")
+            }
+            FormatError::UnexpectedFileLength { expected, found } => write!(
+                f,
+                "expected a file length of {expected} bytes, but found {found} bytes"
+            ),
+            FormatError::ChecksumMismatch => write!(f, "the checksum of the arrays does not match"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FormatError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FormatError {
+    fn from(error: io::Error) -> Self {
+        FormatError::Io(error)
+    }
+}
+
+// the hash that is stored in the header, independent of the platform
+pub fn text_hash<C: IndexStorage>(text: &[C]) -> u64 {
+    if Endianness::native() == Endianness::Little {
+        return fnv1a(FNV_OFFSET_BASIS, as_bytes(text));
+    }
+
+    text.iter().fold(FNV_OFFSET_BASIS, |hash, &char| {
+        fnv1a(hash, as_bytes(&[char.swap_bytes()]))
+    })
+}
+
+pub struct SuffixArrayFileWriter<'a, C, I> {
+    text: &'a [C],
+    suffix_array: &'a [I],
+    alphabet_size: Option<u64>,
+    lcp: Option<&'a [I]>,
+    bwt: Option<(&'a [C], u64)>,
+    samples: Option<(u64, &'a [I])>,
+}
+
+impl<'a, C: IndexStorage, I: IndexStorage> SuffixArrayFileWriter<'a, C, I> {
+    pub fn new(text: &'a [C], suffix_array: &'a [I]) -> Self {
+        assert_eq!(text.len(), suffix_array.len());
+
+        Self {
+            text,
+            suffix_array,
+            alphabet_size: None,
+            lcp: None,
+            bwt: None,
+            samples: None,
+        }
+    }
+
+    // by default, the alphabet size is derived from the largest character of the text
+    pub fn with_alphabet_size(&mut self, alphabet_size: u64) -> &mut Self {
+        self.alphabet_size = Some(alphabet_size);
+        self
+    }
+
+    pub fn with_lcp(&mut self, lcp: &'a [I]) -> &mut Self {
+        assert_eq!(lcp.len(), self.text.len());
+        self.lcp = Some(lcp);
+        self
+    }
+
+    pub fn with_bwt(&mut self, bwt: &'a [C], primary_index: u64) -> &mut Self {
+        assert_eq!(bwt.len(), self.text.len());
+        self.bwt = Some((bwt, primary_index));
+        self
+    }
+
+    pub fn with_samples(&mut self, sample_rate: u64, samples: &'a [I]) -> &mut Self {
+        self.samples = Some((sample_rate, samples));
+        self
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn write_to(&self, writer: impl Write) -> io::Result<()> {
+        self.write_with_endianness(writer, Endianness::native())
+    }
+
+    fn write_with_endianness(
+        &self,
+        mut writer: impl Write,
+        endianness: Endianness,
+    ) -> io::Result<()> {
+        let swap = endianness != Endianness::native();
+        let arrays = self.arrays_with_endianness(swap);
+        let checksum = arrays
+            .iter()
+            .fold(FNV_OFFSET_BASIS, |hash, array| fnv1a(hash, array));
+
+        let alphabet_size = self.alphabet_size.unwrap_or_else(|| {
+            self.text
+                .iter()
+                .max()
+                .map_or(0, |&max_char| max_char.as_() as u64 + 1)
+        });
+
+        let header = SuffixArrayFileHeader {
+            version: FORMAT_VERSION,
+            index_width: size_of::<I>() as u8,
+            char_width: size_of::<C>() as u8,
+            endianness,
+            has_lcp: self.lcp.is_some(),
+            has_bwt: self.bwt.is_some(),
+            has_samples: self.samples.is_some(),
+            text_len: self.text.len() as u64,
+            alphabet_size,
+            text_hash: text_hash(self.text),
+            bwt_primary_index: self.bwt.map_or(0, |(_, primary_index)| primary_index),
+            sample_rate: self.samples.map_or(0, |(sample_rate, _)| sample_rate),
+            num_samples: self.samples.map_or(0, |(_, samples)| samples.len() as u64),
+            checksum,
+        };
+
+        writer.write_all(&header.to_bytes())?;
+
+        for array in arrays {
+            writer.write_all(&array)?;
+            let padding_len = array.len().next_multiple_of(ALIGNMENT) - array.len();
+            writer.write_all(&[0; ALIGNMENT][..padding_len])?;
+        }
+
+        Ok(())
+    }
+
+    fn arrays_with_endianness(&self, swap: bool) -> Vec<Vec<u8>> {
+        let mut arrays = vec![bytes_with_endianness(self.suffix_array, swap)];
+
+        if let Some(lcp) = self.lcp {
+            arrays.push(bytes_with_endianness(lcp, swap));
+        }
+        if let Some((bwt, _)) = self.bwt {
+            arrays.push(bytes_with_endianness(bwt, swap));
+        }
+        if let Some((_, samples)) = self.samples {
+            arrays.push(bytes_with_endianness(samples, swap));
+        }
+
+        arrays
+    }
+}
+
+// a suffix array file that was read into memory
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        bound = "C: IndexStorage + 'static, I: IndexStorage + 'static",
+        try_from = "SerializedSuffixArrayFile<C, I>"
+    )
+)]
+pub struct SuffixArrayFile<C, I> {
+    header: SuffixArrayFileHeader,
+    #[cfg_attr(feature = "serde", serde(with = "crate::compact_serde::array"))]
+    suffix_array: Vec<I>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::compact_serde::optional_array")
+    )]
+    lcp: Option<Vec<I>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::compact_serde::optional_array")
+    )]
+    bwt: Option<Vec<C>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::compact_serde::optional_array")
+    )]
+    samples: Option<Vec<I>>,
+}
+
+// the fields of a deserialized SuffixArrayFile, before they are validated like in read_from
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(bound = "C: IndexStorage + 'static, I: IndexStorage + 'static")]
+struct SerializedSuffixArrayFile<C, I> {
+    header: SuffixArrayFileHeader,
+    #[serde(with = "crate::compact_serde::array")]
+    suffix_array: Vec<I>,
+    #[serde(with = "crate::compact_serde::optional_array")]
+    lcp: Option<Vec<I>>,
+    #[serde(with = "crate::compact_serde::optional_array")]
+    bwt: Option<Vec<C>>,
+    #[serde(with = "crate::compact_serde::optional_array")]
+    samples: Option<Vec<I>>,
+}
+
+#[cfg(feature = "serde")]
+impl<C: IndexStorage + 'static, I: IndexStorage + 'static> TryFrom<SerializedSuffixArrayFile<C, I>>
+    for SuffixArrayFile<C, I>
+{
+    type Error = FormatError;
+
+    // the same checks as for files, except for the widths. A serialized file with usize
+    // indices can come from a platform with another size of usize, the values were already
+    // checked to fit when they were deserialized.
+    fn try_from(serialized: SerializedSuffixArrayFile<C, I>) -> Result<Self, FormatError> {
+        let header = serialized.header;
+
+        if header.version != FORMAT_VERSION {
+            return Err(FormatError::UnsupportedVersion(header.version));
+        }
+
+        check_serialized_width::<I>(
+            header.index_width,
+            FormatError::IndexWidthMismatch {
+                expected: size_of::<I>(),
+                found: header.index_width as usize,
+            },
+        )?;
+        check_serialized_width::<C>(
+            header.char_width,
+            FormatError::CharWidthMismatch {
+                expected: size_of::<C>(),
+                found: header.char_width as usize,
+            },
+        )?;
+
+        let expected_lens = [
+            Some(header.text_len),
+            header.has_lcp.then_some(header.text_len),
+            header.has_bwt.then_some(header.text_len),
+            header.has_samples.then_some(header.num_samples),
+        ];
+        let lens = [
+            Some(serialized.suffix_array.len()),
+            serialized.lcp.as_ref().map(Vec::len),
+            serialized.bwt.as_ref().map(Vec::len),
+            serialized.samples.as_ref().map(Vec::len),
+        ];
+
+        for (expected_len, len) in expected_lens.into_iter().zip(lens) {
+            if expected_len != len.map(|len| len as u64) {
+                return Err(FormatError::InvalidHeader);
+            }
+        }
+
+        let index_width = header.index_width as usize;
+        let mut checksum = fnv1a_of_values(
+            FNV_OFFSET_BASIS,
+            &serialized.suffix_array,
+            index_width,
+            header.endianness,
+        );
+        if let Some(lcp) = &serialized.lcp {
+            checksum = fnv1a_of_values(checksum, lcp, index_width, header.endianness);
+        }
+        if let Some(bwt) = &serialized.bwt {
+            let char_width = header.char_width as usize;
+            checksum = fnv1a_of_values(checksum, bwt, char_width, header.endianness);
+        }
+        if let Some(samples) = &serialized.samples {
+            checksum = fnv1a_of_values(checksum, samples, index_width, header.endianness);
+        }
+
+        if checksum != header.checksum {
+            return Err(FormatError::ChecksumMismatch);
+        }
+
+        Ok(Self {
+            header,
+            suffix_array: serialized.suffix_array,
+            lcp: serialized.lcp,
+            bwt: serialized.bwt,
+            samples: serialized.samples,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+fn check_serialized_width<T: 'static>(width: u8, error: FormatError) -> Result<(), FormatError> {
+    let is_usize = std::any::TypeId::of::<T>() == std::any::TypeId::of::<usize>();
+
+    if width as usize == size_of::<T>() || (is_usize && matches!(width, 4 | 8)) {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+// the hash of the values as stored in a file with the given width and endianness
+#[cfg(feature = "serde")]
+fn fnv1a_of_values<T: IndexStorage>(
+    hash: u64,
+    values: &[T],
+    width: usize,
+    endianness: Endianness,
+) -> u64 {
+    values.iter().fold(hash, |hash, value| {
+        let value = value.to_u64().unwrap();
+
+        match endianness {
+            Endianness::Little => fnv1a(hash, &value.to_le_bytes()[..width]),
+            Endianness::Big => fnv1a(hash, &value.to_be_bytes()[8 - width..]),
+        }
+    })
+}
+
+impl<C: IndexStorage, I: IndexStorage> SuffixArrayFile<C, I> {
+    // the length of the file is compared with the header before any array is read
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, FormatError> {
+        let file = File::open(path)?;
+        let found_file_len = usize::try_from(file.metadata()?.len()).unwrap_or(usize::MAX);
+        let mut reader = BufReader::new(file);
+
+        let header = SuffixArrayFileHeader::read_from(&mut reader)?;
+        let expected_file_len = header.file_len()?;
+
+        if found_file_len != expected_file_len {
+            return Err(FormatError::UnexpectedFileLength {
+                expected: expected_file_len,
+                found: found_file_len,
+            });
+        }
+
+        Self::read_arrays(header, reader)
+    }
+
+    // files of both endiannesses can be read, the checksum is always verified. The length of a
+    // stream is not known in advance, so a header with wrong lengths leads to an error at the
+    // end of the stream
+    pub fn read_from(mut reader: impl Read) -> Result<Self, FormatError> {
+        let header = SuffixArrayFileHeader::read_from(&mut reader)?;
+        Self::read_arrays(header, reader)
+    }
+
+    fn read_arrays(
+        header: SuffixArrayFileHeader,
+        mut reader: impl Read,
+    ) -> Result<Self, FormatError> {
+        header.check_widths::<C, I>()?;
+
+        let swap = header.endianness != Endianness::native();
+        let [suffix_array_len, lcp_len, bwt_len, samples_len] = header.array_byte_lens()?;
+        let mut checksum = FNV_OFFSET_BASIS;
+
+        let suffix_array = read_array(&mut reader, suffix_array_len, swap, &mut checksum)?;
+        let lcp = header
+            .has_lcp
+            .then(|| read_array(&mut reader, lcp_len, swap, &mut checksum))
+            .transpose()?;
+        let bwt = header
+            .has_bwt
+            .then(|| read_array(&mut reader, bwt_len, swap, &mut checksum))
+            .transpose()?;
+        let samples = header
+            .has_samples
+            .then(|| read_array(&mut reader, samples_len, swap, &mut checksum))
+            .transpose()?;
+
+        if checksum != header.checksum {
+            return Err(FormatError::ChecksumMismatch);
+        }
+
+        Ok(Self {
+            header,
+            suffix_array,
+            lcp,
+            bwt,
+            samples,
+        })
+    }
+
+    pub fn header(&self) -> &SuffixArrayFileHeader {
+        &self.header
+    }
+
+    pub fn suffix_array(&self) -> &[I] {
+        &self.suffix_array
+    }
+
+    pub fn lcp(&self) -> Option<&[I]> {
+        self.lcp.as_deref()
+    }
+
+    pub fn bwt(&self) -> Option<&[C]> {
+        self.bwt.as_deref()
+    }
+
+    pub fn samples(&self) -> Option<&[I]> {
+        self.samples.as_deref()
+    }
+
+    pub fn into_suffix_array(self) -> Vec<I> {
+        self.suffix_array
+    }
+}
+
+// a zero-copy view of a suffix array file. The file must have the native endianness.
+#[cfg(feature = "mmap")]
+pub struct MappedSuffixArrayFile<C, I> {
+    mmap: memmap2::Mmap,
+    header: SuffixArrayFileHeader,
+    array_ranges: [std::ops::Range<usize>; 4],
+    _marker: std::marker::PhantomData<(C, I)>,
+}
+
+#[cfg(feature = "mmap")]
+impl<C: IndexStorage, I: IndexStorage> MappedSuffixArrayFile<C, I> {
+    // the file must not be modified while it is mapped. The checksum is not verified here,
+    // because that would require reading the whole file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FormatError> {
+        let file = File::open(path)?;
+
+        // SAFETY: see the documentation of this function
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        if mmap.len() < HEADER_LEN {
+            return Err(FormatError::UnexpectedFileLength {
+                expected: HEADER_LEN,
+                found: mmap.len(),
+            });
+        }
+
+        let header = SuffixArrayFileHeader::from_bytes(mmap[..HEADER_LEN].try_into().unwrap())?;
+        header.check_widths::<C, I>()?;
+
+        if header.endianness != Endianness::native() {
+            return Err(FormatError::EndiannessMismatch);
+        }
+
+        let expected_file_len = header.file_len()?;
+
+        if mmap.len() != expected_file_len {
+            return Err(FormatError::UnexpectedFileLength {
+                expected: expected_file_len,
+                found: mmap.len(),
+            });
+        }
+
+        let mut offset = HEADER_LEN;
+        let array_ranges = header.array_byte_lens()?.map(|len| {
+            let range = offset..offset + len;
+            offset += len.next_multiple_of(ALIGNMENT);
+            range
+        });
+
+        Ok(Self {
+            mmap,
+            header,
+            array_ranges,
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    pub fn verify_checksum(&self) -> bool {
+        let checksum = self
+            .array_ranges
+            .iter()
+            .fold(FNV_OFFSET_BASIS, |hash, range| {
+                fnv1a(hash, &self.mmap[range.clone()])
+            });
+
+        checksum == self.header.checksum
+    }
+
+    pub fn header(&self) -> &SuffixArrayFileHeader {
+        &self.header
+    }
+
+    pub fn suffix_array(&self) -> &[I] {
+        self.array(0)
+    }
+
+    pub fn lcp(&self) -> Option<&[I]> {
+        self.header.has_lcp.then(|| self.array(1))
+    }
+
+    pub fn bwt(&self) -> Option<&[C]> {
+        self.header.has_bwt.then(|| self.array(2))
+    }
+
+    pub fn samples(&self) -> Option<&[I]> {
+        self.header.has_samples.then(|| self.array(3))
+    }
+
+    fn array<T: IndexStorage>(&self, array_index: usize) -> &[T] {
+        let bytes = &self.mmap[self.array_ranges[array_index].clone()];
+
+        // SAFETY: the mapping is page aligned and all arrays start at a multiple of 8. The
+        // length was checked against the header and every bit pattern is a valid value.
+        unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len() / size_of::<T>()) }
+    }
+}
+
+fn read_array<T: IndexStorage>(
+    reader: &mut impl Read,
+    byte_len: usize,
+    swap: bool,
+    checksum: &mut u64,
+) -> io::Result<Vec<T>> {
+    let len = byte_len / size_of::<T>();
+
+    // the memory grows with the data that was actually read, such that a wrong length in the
+    // header can't lead to a huge allocation
+    let mut values = Vec::with_capacity(len.min(READ_CHUNK_LEN));
+
+    while values.len() < len {
+        let chunk_start = values.len();
+        let chunk_len = (len - chunk_start).min(READ_CHUNK_LEN);
+
+        values.resize(chunk_start + chunk_len, T::zero());
+        reader.read_exact(as_bytes_mut(&mut values[chunk_start..]))?;
+    }
+
+    let padding_len = byte_len.next_multiple_of(ALIGNMENT) - byte_len;
+    reader.read_exact(&mut [0; ALIGNMENT][..padding_len])?;
+
+    *checksum = fnv1a(*checksum, as_bytes(&values));
+
+    if swap {
+        for value in &mut values {
+            *value = value.swap_bytes();
+        }
+    }
+
+    Ok(values)
+}
+
+fn bytes_with_endianness<T: IndexStorage>(values: &[T], swap: bool) -> Vec<u8> {
+    if swap {
+        let swapped: Vec<_> = values.iter().map(|value| value.swap_bytes()).collect();
+        as_bytes(&swapped).to_vec()
+    } else {
+        as_bytes(values).to_vec()
+    }
+}
+
+fn as_bytes<T: IndexStorage>(values: &[T]) -> &[u8] {
+    // SAFETY: index storage types are plain integers without padding
+    unsafe { std::slice::from_raw_parts(values.as_ptr().cast(), size_of_val(values)) }
+}
+
+fn as_bytes_mut<T: IndexStorage>(values: &mut [T]) -> &mut [u8] {
+    // SAFETY: index storage types are plain integers without padding, and every bit pattern
+    // is a valid value
+    unsafe { std::slice::from_raw_parts_mut(values.as_mut_ptr().cast(), size_of_val(values)) }
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_roundtrip() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let header = SuffixArrayFileHeader {
+                version: FORMAT_VERSION,
+                index_width: 4,
+                char_width: 1,
+                endianness,
+                has_lcp: true,
+                has_bwt: false,
+                has_samples: true,
+                text_len: 42,
+                alphabet_size: 4,
+                text_hash: 123456789,
+                bwt_primary_index: 0,
+                sample_rate: 8,
+                num_samples: 6,
+                checksum: 987654321,
+            };
+
+            let header_bytes = header.to_bytes();
+            assert_eq!(
+                SuffixArrayFileHeader::from_bytes(&header_bytes).unwrap(),
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_foreign_endianness() {
+        let text = [3u16, 1, 2, 1, 0];
+        let suffix_array = [4u32, 3, 1, 2, 0];
+        let lcp = [0u32, 0, 1, 0, 0];
+        let bwt = [1u16, 2, 3, 1, 0];
+
+        let foreign_endianness = match Endianness::native() {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        };
+
+        let mut bytes = Vec::new();
+        SuffixArrayFileWriter::new(&text, &suffix_array)
+            .with_lcp(&lcp)
+            .with_bwt(&bwt, 4)
+            .write_with_endianness(&mut bytes, foreign_endianness)
+            .unwrap();
+
+        let file = SuffixArrayFile::<u16, u32>::read_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(file.header().endianness, foreign_endianness);
+        assert!(file.header().matches_text(&text));
+        assert_eq!(file.suffix_array(), suffix_array);
+        assert_eq!(file.lcp(), Some(lcp.as_slice()));
+        assert_eq!(file.bwt(), Some(bwt.as_slice()));
+        assert_eq!(file.header().bwt_primary_index, 4);
+        assert_eq!(file.samples(), None);
+    }
+
+    #[test]
+    fn test_overflowing_lengths_are_rejected() {
+        let mut header = SuffixArrayFileHeader {
+            version: FORMAT_VERSION,
+            index_width: 8,
+            char_width: 1,
+            endianness: Endianness::native(),
+            has_lcp: false,
+            has_bwt: false,
+            has_samples: true,
+            text_len: 0,
+            alphabet_size: 0,
+            text_hash: FNV_OFFSET_BASIS,
+            bwt_primary_index: 0,
+            sample_rate: 1,
+            num_samples: u64::MAX / 4,
+            checksum: FNV_OFFSET_BASIS,
+        };
+
+        let bytes = header.to_bytes();
+        assert!(matches!(
+            SuffixArrayFile::<u8, u64>::read_from(bytes.as_slice()),
+            Err(FormatError::InvalidHeader)
+        ));
+
+        // the multiplication does not overflow, but the sum with the header length does
+        header.num_samples = 0;
+        header.text_len = (usize::MAX / 8) as u64;
+        assert!(matches!(header.file_len(), Err(FormatError::InvalidHeader)));
+    }
+
+    #[test]
+    fn test_truncated_stream_with_huge_lengths_is_rejected() {
+        let header = SuffixArrayFileHeader {
+            version: FORMAT_VERSION,
+            index_width: 4,
+            char_width: 1,
+            endianness: Endianness::native(),
+            has_lcp: true,
+            has_bwt: false,
+            has_samples: false,
+            text_len: 1 << 30,
+            alphabet_size: 4,
+            text_hash: 0,
+            bwt_primary_index: 0,
+            sample_rate: 0,
+            num_samples: 0,
+            checksum: 0,
+        };
+
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend_from_slice(&[0; 100]);
+
+        assert!(matches!(
+            SuffixArrayFile::<u8, u32>::read_from(bytes.as_slice()),
+            Err(FormatError::Io(error)) if error.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn test_text_hash_is_fnv1a_of_little_endian_bytes() {
+        assert_eq!(text_hash::<u8>(&[]), FNV_OFFSET_BASIS);
+        assert_eq!(text_hash(&[0x0201u16]), text_hash(&[1u8, 2]));
+    }
+}
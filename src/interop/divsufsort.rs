@@ -0,0 +1,83 @@
+// The raw suffix array files of the mksary tool of libdivsufsort: a sequence of signed little
+// endian integers without any header. The width depends on how libdivsufsort was built.
+
+use std::io::{self, Read, Write};
+
+use super::{invalid_data, value_to_index};
+use crate::IndexStorage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RawIndexWidth {
+    Int32,
+    Int64,
+}
+
+impl RawIndexWidth {
+    fn num_bytes(self) -> usize {
+        match self {
+            RawIndexWidth::Int32 => 4,
+            RawIndexWidth::Int64 => 8,
+        }
+    }
+
+    fn max_value(self) -> u64 {
+        match self {
+            RawIndexWidth::Int32 => i32::MAX as u64,
+            RawIndexWidth::Int64 => i64::MAX as u64,
+        }
+    }
+}
+
+pub fn write_suffix_array<I: IndexStorage>(
+    mut writer: impl Write,
+    suffix_array: &[I],
+    index_width: RawIndexWidth,
+) -> io::Result<()> {
+    for &index in suffix_array {
+        let index = index.to_u64().unwrap();
+
+        if index > index_width.max_value() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("the index {index} does not fit into {index_width:?}"),
+            ));
+        }
+
+        let bytes = index.to_le_bytes();
+        writer.write_all(&bytes[..index_width.num_bytes()])?;
+    }
+
+    Ok(())
+}
+
+// reads until the end of the input
+pub fn read_suffix_array<I: IndexStorage>(
+    mut reader: impl Read,
+    index_width: RawIndexWidth,
+) -> io::Result<Vec<I>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if !bytes.len().is_multiple_of(index_width.num_bytes()) {
+        return Err(invalid_data(format!(
+            "the input length of {} bytes is not a multiple of the index width",
+            bytes.len()
+        )));
+    }
+
+    bytes
+        .chunks_exact(index_width.num_bytes())
+        .map(|index_bytes| {
+            let index: i64 = match index_width {
+                RawIndexWidth::Int32 => i32::from_le_bytes(index_bytes.try_into().unwrap()) as i64,
+                RawIndexWidth::Int64 => i64::from_le_bytes(index_bytes.try_into().unwrap()),
+            };
+
+            if index < 0 {
+                return Err(invalid_data(format!("negative index {index}")));
+            }
+
+            value_to_index(index as u64)
+        })
+        .collect()
+}
@@ -0,0 +1,23 @@
+// readers and writers for the file layouts of other suffix array libraries
+
+pub mod divsufsort;
+pub mod sdsl;
+
+use std::io;
+
+use num_traits::NumCast;
+
+use crate::IndexStorage;
+
+fn value_to_index<I: IndexStorage>(value: u64) -> io::Result<I> {
+    <I as NumCast>::from(value).ok_or_else(|| {
+        invalid_data(format!(
+            "the value {value} does not fit into indices of {} bytes",
+            size_of::<I>()
+        ))
+    })
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -0,0 +1,224 @@
+// The serialization of int_vector of sdsl-lite (version 2): the number of bits as u64, followed
+// by the width in bits as u8 (only for int_vector<0>, where the width is chosen at runtime) and
+// the bit-packed values in little endian u64 words. SA and LCP arrays are usually stored as
+// int_vector<0>, the BWT of a byte text as int_vector<8>.
+
+use std::io::{self, Read, Write};
+
+use super::{invalid_data, value_to_index};
+use crate::IndexStorage;
+
+const WORD_BITS: usize = u64::BITS as usize;
+// the number of words that are allocated before any of them is read
+const MAX_PREALLOCATED_WORDS: usize = 1 << 16;
+
+// writes an int_vector<0> with the smallest width that fits all values, like sdsl's bit_compress
+pub fn write_int_vector<I: IndexStorage>(mut writer: impl Write, values: &[I]) -> io::Result<()> {
+    let max_value = values
+        .iter()
+        .map(|value| value.to_u64().unwrap())
+        .max()
+        .unwrap_or(0);
+    let width = (u64::BITS - max_value.leading_zeros()).max(1) as u8;
+
+    writer.write_all(&bit_len(values.len(), width).to_le_bytes())?;
+    writer.write_all(&[width])?;
+    write_packed_words(writer, values, width)
+}
+
+// writes an int_vector<width>, the width is not stored
+pub fn write_fixed_width_int_vector<I: IndexStorage>(
+    mut writer: impl Write,
+    values: &[I],
+    width: u8,
+) -> io::Result<()> {
+    check_width(width)?;
+
+    if let Some(value) = values
+        .iter()
+        .map(|value| value.to_u64().unwrap())
+        .find(|&value| width < 64 && value >> width != 0)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("the value {value} does not fit into {width} bits"),
+        ));
+    }
+
+    writer.write_all(&bit_len(values.len(), width).to_le_bytes())?;
+    write_packed_words(writer, values, width)
+}
+
+// reads an int_vector<0>
+pub fn read_int_vector<I: IndexStorage>(mut reader: impl Read) -> io::Result<Vec<I>> {
+    let bit_len = read_u64(&mut reader)?;
+
+    let mut width = [0];
+    reader.read_exact(&mut width)?;
+
+    read_packed_words(reader, bit_len, width[0])
+}
+
+// reads an int_vector<width>
+pub fn read_fixed_width_int_vector<I: IndexStorage>(
+    mut reader: impl Read,
+    width: u8,
+) -> io::Result<Vec<I>> {
+    let bit_len = read_u64(&mut reader)?;
+    read_packed_words(reader, bit_len, width)
+}
+
+fn bit_len(num_values: usize, width: u8) -> u64 {
+    num_values as u64 * width as u64
+}
+
+fn check_width(width: u8) -> io::Result<()> {
+    if width == 0 || width > 64 {
+        return Err(invalid_data(format!("invalid int_vector width {width}")));
+    }
+
+    Ok(())
+}
+
+fn write_packed_words<I: IndexStorage>(
+    mut writer: impl Write,
+    values: &[I],
+    width: u8,
+) -> io::Result<()> {
+    let width = width as usize;
+    let mut words = vec![0u64; (values.len() * width).div_ceil(WORD_BITS)];
+
+    for (index, value) in values.iter().enumerate() {
+        let value = value.to_u64().unwrap();
+        let bit_index = index * width;
+        let (word_index, offset) = (bit_index / WORD_BITS, bit_index % WORD_BITS);
+
+        words[word_index] |= value << offset;
+
+        if offset + width > WORD_BITS {
+            words[word_index + 1] |= value >> (WORD_BITS - offset);
+        }
+    }
+
+    for word in words {
+        writer.write_all(&word.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn read_packed_words<I: IndexStorage>(
+    mut reader: impl Read,
+    bit_len: u64,
+    width: u8,
+) -> io::Result<Vec<I>> {
+    check_width(width)?;
+
+    if !bit_len.is_multiple_of(width as u64) {
+        return Err(invalid_data(format!(
+            "the number of bits {bit_len} is not a multiple of the width {width}"
+        )));
+    }
+
+    // the lengths come from the file, so the words are read incrementally instead of allocating
+    // them up front, and a truncated file is noticed before a large allocation
+    let num_words = usize::try_from(bit_len.div_ceil(WORD_BITS as u64))
+        .map_err(|_| invalid_data(format!("the int_vector of {bit_len} bits is too large")))?;
+    let mut words = Vec::with_capacity(num_words.min(MAX_PREALLOCATED_WORDS));
+
+    for num_words_read in 0..num_words {
+        match read_u64(&mut reader) {
+            Ok(word) => words.push(word),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(invalid_data(format!(
+                    "the int_vector ends after {num_words_read} of {num_words} words"
+                )));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let width = width as u64;
+    let mask = if width == WORD_BITS as u64 {
+        u64::MAX
+    } else {
+        (1 << width) - 1
+    };
+
+    (0..bit_len / width)
+        .map(|index| {
+            let bit_index = index * width;
+            let word_index = (bit_index / WORD_BITS as u64) as usize;
+            let offset = bit_index % WORD_BITS as u64;
+
+            let mut value = words[word_index] >> offset;
+            if offset + width > WORD_BITS as u64 {
+                value |= words[word_index + 1] << (WORD_BITS as u64 - offset);
+            }
+
+            value_to_index(value & mask)
+        })
+        .collect()
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_int_vector_layout() {
+        let mut bytes = Vec::new();
+        write_int_vector(&mut bytes, &[1u32, 2, 3]).unwrap();
+
+        let mut expected = 6u64.to_le_bytes().to_vec();
+        expected.push(2);
+        expected.extend((1u64 | 2 << 2 | 3 << 4).to_le_bytes());
+
+        assert_eq!(bytes, expected);
+        assert_eq!(read_int_vector::<u32>(bytes.as_slice()).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_values_across_word_boundaries() {
+        let values: Vec<u64> = (0..100u64)
+            .map(|i| i.wrapping_mul(0x9e3779b97f4a7c15) >> 3)
+            .collect();
+
+        for width in [61, 64] {
+            let mut bytes = Vec::new();
+            write_fixed_width_int_vector(&mut bytes, &values, width).unwrap();
+
+            let values_read: Vec<u64> =
+                read_fixed_width_int_vector(bytes.as_slice(), width).unwrap();
+            assert_eq!(values_read, values);
+        }
+    }
+
+    #[test]
+    fn test_value_too_large_for_width() {
+        let mut bytes = Vec::new();
+        assert!(write_fixed_width_int_vector(&mut bytes, &[256u32], 8).is_err());
+    }
+
+    #[test]
+    fn test_huge_bit_len_with_short_body() {
+        for bit_len in [u64::MAX, u64::MAX - 63, 1 << 40] {
+            let mut bytes = bit_len.to_le_bytes().to_vec();
+            bytes.push(64);
+            bytes.extend(42u64.to_le_bytes());
+
+            let error = read_int_vector::<u64>(bytes.as_slice()).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+            let error = read_fixed_width_int_vector::<u64>(&bytes[..8], 64).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
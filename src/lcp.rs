@@ -0,0 +1,45 @@
+use num_traits::NumCast;
+
+use crate::IndexStorage;
+
+// the LCP array contains at position i the length of the longest common prefix of the suffixes
+// at SA[i - 1] and SA[i], and 0 at position 0. It is computed with the phi variant of the Kasai
+// algorithm, which only needs a single helper array of the size of the suffix array.
+pub fn construct_lcp_array<C: Ord, I: IndexStorage>(text: &[C], suffix_array: &[I]) -> Vec<I> {
+    assert_eq!(text.len(), suffix_array.len());
+
+    // phi[SA[i]] = SA[i - 1], the values are then overwritten with the permuted LCP values
+    let mut plcp = vec![I::max_value(); text.len()];
+    for window in suffix_array.windows(2) {
+        plcp[window[1].as_()] = window[0];
+    }
+
+    let mut common_prefix_len = 0;
+
+    for suffix_index in 0..text.len() {
+        let previous_suffix = plcp[suffix_index];
+
+        if previous_suffix == I::max_value() {
+            plcp[suffix_index] = I::zero();
+            common_prefix_len = 0;
+            continue;
+        }
+
+        let previous_suffix: usize = previous_suffix.as_();
+
+        while suffix_index + common_prefix_len < text.len()
+            && previous_suffix + common_prefix_len < text.len()
+            && text[suffix_index + common_prefix_len] == text[previous_suffix + common_prefix_len]
+        {
+            common_prefix_len += 1;
+        }
+
+        plcp[suffix_index] = <I as NumCast>::from(common_prefix_len).unwrap();
+        common_prefix_len = common_prefix_len.saturating_sub(1);
+    }
+
+    suffix_array
+        .iter()
+        .map(|&suffix_index| plcp[suffix_index.as_()])
+        .collect()
+}
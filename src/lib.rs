@@ -1,4 +1,30 @@
+pub mod absent_words;
 mod algorithm;
+pub mod bwt;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "serde")]
+mod compact_serde;
+pub mod enhanced_suffix_array;
+#[cfg(feature = "external")]
+pub mod external;
+#[cfg(feature = "fasta")]
+pub mod fasta;
+pub mod format;
+pub mod interop;
+pub mod lcp;
+pub mod lz77;
+pub mod matches;
+pub mod matching_statistics;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod r_index;
+pub mod repeats;
+pub mod search;
+pub mod substrings;
+pub mod suffix_tree;
+pub mod unique_substrings;
+pub mod verify;
 
 use std::marker::PhantomData;
 
@@ -6,12 +32,22 @@ use bitvec::store::BitStore;
 use num::Integer;
 use num_traits::{AsPrimitive, NumCast, PrimInt, SaturatingSub, WrappingSub};
 
-use algorithm::buffer_management::BufferStack;
+use algorithm::{
+    CharFrequencies, alphabet_compaction::CompactAlphabet, buffer_management::BufferStack,
+};
+
+pub use algorithm::BucketGranularity;
 
 pub trait Character: Sized + Copy + Ord {
     fn max_char() -> Self;
 
     fn rank(&self) -> usize;
+
+    // the inverse of rank, for ranks up to the one of max_char. Only needed to enumerate the
+    // characters that do not occur in a text, e.g. for minimal absent words
+    fn from_rank(_rank: usize) -> Option<Self> {
+        None
+    }
 }
 
 impl<P: PrimInt> Character for P {
@@ -22,6 +58,10 @@ impl<P: PrimInt> Character for P {
     fn rank(&self) -> usize {
         self.to_usize().unwrap()
     }
+
+    fn from_rank(rank: usize) -> Option<Self> {
+        <P as NumCast>::from(rank)
+    }
 }
 
 pub trait IndexStorage:
@@ -35,8 +75,21 @@ impl IndexStorage for u32 {}
 impl IndexStorage for u64 {}
 impl IndexStorage for usize {}
 
+// below this text length, the suffixes are sorted by direct comparison instead of SAIS. In the
+// small-texts benchmark, the comparison sort is faster on random, DNA and repetitive texts of up to
+// 250 characters, and SAIS takes over between 300 and 400 characters
+const DEFAULT_SMALL_TEXT_THRESHOLD: usize = 300;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SaisBuilder<C = u8, I = usize> {
     max_char: Option<C>,
+    small_text_threshold: usize,
+    alphabet_compaction: bool,
+    bucket_granularity: Option<BucketGranularity>,
+    // describes a specific text rather than the configuration, so it is not serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    char_frequencies: Option<Vec<I>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _marker: PhantomData<I>,
 }
 
@@ -44,6 +97,10 @@ impl<C: Character, I: IndexStorage> SaisBuilder<C, I> {
     pub fn new() -> Self {
         Self {
             max_char: None,
+            small_text_threshold: DEFAULT_SMALL_TEXT_THRESHOLD,
+            alphabet_compaction: true,
+            bucket_granularity: None,
+            char_frequencies: None,
             _marker: PhantomData,
         }
     }
@@ -55,32 +112,211 @@ impl<C: Character, I: IndexStorage> SaisBuilder<C, I> {
         self
     }
 
+    // texts shorter than this threshold are handled by a simpler algorithm that avoids the setup
+    // overhead of SAIS. a threshold of 0 disables this special case
+    pub fn with_small_text_threshold(&mut self, small_text_threshold: usize) -> &mut Self {
+        self.small_text_threshold = small_text_threshold;
+        self
+    }
+
+    // if enabled, texts that only use a small part of a large alphabet are first transformed to
+    // a dense alphabet, which makes the bucket arrays of SAIS smaller. This is enabled by default,
+    // but only done when it is worthwhile. It needs an additional copy of the text.
+    pub fn with_alphabet_compaction(&mut self, alphabet_compaction: bool) -> &mut Self {
+        self.alphabet_compaction = alphabet_compaction;
+        self
+    }
+
+    // overrides the granularity of the bucket arrays, which is otherwise chosen from the alphabet
+    // size. It only applies to the text itself, the reduced texts of the recursion always choose
+    // it from their alphabet size.
+    pub fn with_bucket_granularity(&mut self, bucket_granularity: BucketGranularity) -> &mut Self {
+        self.bucket_granularity = Some(bucket_granularity);
+        self
+    }
+
+    // the number of occurrences of every character up to max_char in the texts that will be
+    // passed to this builder. SAIS then skips the counting in its scan of the text, and the
+    // alphabet compaction doesn't need a scan at all. Frequencies that don't belong to the text
+    // lead to panics or wrong suffix arrays.
+    pub fn with_char_frequencies(&mut self, char_frequencies: &[I]) -> &mut Self {
+        self.char_frequencies = Some(char_frequencies.to_vec());
+        self
+    }
+
     pub fn construct_suffix_array_inplace(&self, text: &[C], suffix_array_buffer: &mut [I]) {
         assert!(text.len() <= suffix_array_buffer.len());
         suffix_array_buffer[..text.len()].fill(I::max_value());
 
-        let mut extra_buffer = BufferStack::new();
+        self.construct_suffix_array_into_prepared_buffer(text, suffix_array_buffer, None);
+    }
+
+    pub fn construct_suffix_array(&self, text: &[C]) -> Vec<I> {
+        let mut suffix_array_buffer = vec![I::max_value(); text.len()];
+
+        self.construct_suffix_array_into_prepared_buffer(text, &mut suffix_array_buffer, None);
+
+        suffix_array_buffer
+    }
 
-        algorithm::suffix_array_induced_sort(
+    // like libsais' freq parameter: char_frequencies receives the number of occurrences of every
+    // character up to max_char. The counts are taken from the bucket computation of the algorithm,
+    // so the text is not scanned again.
+    pub fn construct_suffix_array_inplace_with_char_frequencies(
+        &self,
+        text: &[C],
+        suffix_array_buffer: &mut [I],
+        char_frequencies: &mut [I],
+    ) {
+        assert!(text.len() <= suffix_array_buffer.len());
+        assert_eq!(char_frequencies.len(), self.get_max_char().rank() + 1);
+        suffix_array_buffer[..text.len()].fill(I::max_value());
+
+        self.construct_suffix_array_into_prepared_buffer(
             text,
-            self.get_max_char(),
             suffix_array_buffer,
-            &mut extra_buffer,
+            Some(char_frequencies),
         );
     }
 
-    pub fn construct_suffix_array(&self, text: &[C]) -> Vec<I> {
+    // returns the suffix array and the character frequencies
+    pub fn construct_suffix_array_with_char_frequencies(&self, text: &[C]) -> (Vec<I>, Vec<I>) {
         let mut suffix_array_buffer = vec![I::max_value(); text.len()];
-        let mut extra_buffer = BufferStack::new();
+        let mut char_frequencies = vec![I::zero(); self.get_max_char().rank() + 1];
 
-        algorithm::suffix_array_induced_sort(
+        self.construct_suffix_array_into_prepared_buffer(
             text,
-            self.get_max_char(),
             &mut suffix_array_buffer,
-            &mut extra_buffer,
+            Some(&mut char_frequencies),
         );
 
-        suffix_array_buffer
+        (suffix_array_buffer, char_frequencies)
+    }
+
+    // expects the values at 0..text.len() of suffix_array_buffer to be I::max_value() and the
+    // character frequencies buffer to have one entry per character up to max_char
+    fn construct_suffix_array_into_prepared_buffer(
+        &self,
+        text: &[C],
+        suffix_array_buffer: &mut [I],
+        mut maybe_char_frequencies: Option<&mut [I]>,
+    ) {
+        let maybe_known_char_frequencies = self.checked_known_char_frequencies(text);
+
+        if let Some(char_frequencies) = maybe_char_frequencies.as_deref_mut() {
+            match maybe_known_char_frequencies {
+                Some(known_char_frequencies) => {
+                    char_frequencies.copy_from_slice(known_char_frequencies);
+                    maybe_char_frequencies = None;
+                }
+                // SAIS does not scan empty texts
+                None => char_frequencies.fill(I::zero()),
+            }
+        }
+
+        if text.len() < self.small_text_threshold {
+            algorithm::comparison_sort::construct_suffix_array(
+                text,
+                &mut suffix_array_buffer[..text.len()],
+            );
+
+            // small texts are not scanned by SAIS, so they are counted here
+            if let Some(char_frequencies) = maybe_char_frequencies {
+                for char in text {
+                    char_frequencies[char.rank()] = char_frequencies[char.rank()] + I::one();
+                }
+            }
+
+            return;
+        }
+
+        let max_char = self.get_max_char();
+
+        let maybe_compact_alphabet = self
+            .alphabet_compaction
+            .then(|| match maybe_known_char_frequencies {
+                Some(known_char_frequencies) => {
+                    CompactAlphabet::from_char_frequencies_if_worthwhile(known_char_frequencies)
+                }
+                None => CompactAlphabet::create_if_worthwhile(text, max_char),
+            })
+            .flatten();
+
+        let Some(compact_alphabet) = maybe_compact_alphabet else {
+            construct_suffix_array_with_sais(
+                text,
+                max_char,
+                self.bucket_granularity_for(max_char.rank() + 1),
+                suffix_array_buffer,
+                char_frequencies_mode(maybe_known_char_frequencies, maybe_char_frequencies),
+            );
+            return;
+        };
+
+        let compact_max_char = compact_alphabet.alphabet_size() - 1;
+        let maybe_known_compact_char_frequencies =
+            maybe_known_char_frequencies.map(|known_char_frequencies| {
+                compact_alphabet.compact_char_frequencies(known_char_frequencies)
+            });
+        let mut maybe_compact_char_frequencies = maybe_char_frequencies
+            .as_ref()
+            .map(|_| vec![I::zero(); compact_alphabet.alphabet_size()]);
+
+        let compact_char_frequencies_mode = char_frequencies_mode(
+            maybe_known_compact_char_frequencies.as_deref(),
+            maybe_compact_char_frequencies.as_deref_mut(),
+        );
+
+        if compact_max_char <= u8::MAX as usize {
+            let compact_text: Vec<u8> = compact_alphabet.compact_text(text);
+            construct_suffix_array_with_sais(
+                &compact_text,
+                compact_max_char as u8,
+                self.bucket_granularity_for(compact_max_char + 1),
+                suffix_array_buffer,
+                compact_char_frequencies_mode,
+            );
+        } else {
+            let compact_text: Vec<u16> = compact_alphabet.compact_text(text);
+            construct_suffix_array_with_sais(
+                &compact_text,
+                compact_max_char as u16,
+                self.bucket_granularity_for(compact_max_char + 1),
+                suffix_array_buffer,
+                compact_char_frequencies_mode,
+            );
+        }
+
+        if let (Some(char_frequencies), Some(compact_char_frequencies)) =
+            (maybe_char_frequencies, maybe_compact_char_frequencies)
+        {
+            compact_alphabet.expand_char_frequencies(&compact_char_frequencies, char_frequencies);
+        }
+    }
+
+    // only the cheap checks are done here, a full check would need the scan that is avoided
+    fn checked_known_char_frequencies(&self, text: &[C]) -> Option<&[I]> {
+        let known_char_frequencies = self.char_frequencies.as_deref()?;
+
+        assert_eq!(
+            known_char_frequencies.len(),
+            self.get_max_char().rank() + 1,
+            "there has to be one character frequency per character up to max_char"
+        );
+        assert_eq!(
+            known_char_frequencies
+                .iter()
+                .fold(0usize, |sum, frequency| sum + frequency.as_()),
+            text.len(),
+            "the character frequencies don't sum up to the text length"
+        );
+
+        Some(known_char_frequencies)
+    }
+
+    fn bucket_granularity_for(&self, alphabet_size: usize) -> BucketGranularity {
+        self.bucket_granularity
+            .unwrap_or_else(|| BucketGranularity::for_alphabet_size(alphabet_size))
     }
 
     fn get_max_char(&self) -> C {
@@ -101,3 +337,35 @@ impl<C: Character, I: IndexStorage> Default for SaisBuilder<C, I> {
         Self::new()
     }
 }
+
+fn construct_suffix_array_with_sais<C: Character, I: IndexStorage>(
+    text: &[C],
+    max_char: C,
+    bucket_granularity: BucketGranularity,
+    suffix_array_buffer: &mut [I],
+    char_frequencies: CharFrequencies<I>,
+) {
+    let mut extra_buffer = BufferStack::new();
+
+    algorithm::suffix_array_induced_sort(
+        text,
+        max_char,
+        bucket_granularity,
+        suffix_array_buffer,
+        &mut extra_buffer,
+        char_frequencies,
+    );
+}
+
+fn char_frequencies_mode<'a, I>(
+    maybe_known_char_frequencies: Option<&'a [I]>,
+    maybe_char_frequencies_output: Option<&'a mut [I]>,
+) -> CharFrequencies<'a, I> {
+    match (maybe_known_char_frequencies, maybe_char_frequencies_output) {
+        (Some(known_char_frequencies), _) => CharFrequencies::Known(known_char_frequencies),
+        (None, Some(char_frequencies_output)) => {
+            CharFrequencies::CountInto(char_frequencies_output)
+        }
+        (None, None) => CharFrequencies::Count,
+    }
+}
@@ -0,0 +1,326 @@
+// The longest previous factor (LPF) array and the greedy LZ77 factorization, computed from the
+// suffix array and the LCP array.
+//
+// LPF[i] is the length of the longest prefix of the suffix at i that also starts at an earlier
+// position of the text, which is the source of that prefix. Like in the classic LZ77, the source
+// may overlap the position itself (e.g. for runs of the same character). The non-overlapping
+// variant only allows sources that end before the position, such that phrases can be decoded by
+// copying whole slices of the already decoded text.
+//
+// The overlapping LPF array and its parse take linear time. The non-overlapping ones take
+// O(n log n) time and O(n) extra space, because every extension of a factor needs range minimum
+// and interval boundary queries on segment trees, not the linear time algorithms that are
+// known for this variant.
+
+use std::{iter, ops::Range};
+
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phrase<C> {
+    // a character that does not occur earlier in the text
+    Literal(C),
+    // a copy of text[source..source + len], where source is smaller than the start of the phrase
+    Copy { source: usize, len: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LongestPreviousFactors<I> {
+    pub lengths: Vec<I>,
+    // the start of an earlier occurrence of the longest previous factor, only meaningful where
+    // the length is not 0
+    pub sources: Vec<I>,
+}
+
+// the algorithm of Crochemore, Ilie and Smyth: the nearest entries with a smaller suffix index
+// before and after an entry in the suffix array are the candidates for its source, and one of
+// them has the longest common prefix with it. They are found with a stack of suffix array
+// entries with increasing suffix indices, where every entry knows the length of the common
+// prefix with the entry below it.
+pub fn longest_previous_factors<I: IndexStorage>(
+    suffix_array: &[I],
+    lcp: &[I],
+) -> LongestPreviousFactors<I> {
+    assert_eq!(suffix_array.len(), lcp.len());
+
+    let mut lengths = vec![I::zero(); suffix_array.len()];
+    let mut sources = vec![I::max_value(); suffix_array.len()];
+
+    // (suffix index, common prefix length with the entry below)
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    // the first entry has no predecessor, and the end of the suffix array closes all entries
+    let entries = suffix_array
+        .iter()
+        .map(|suffix_index| Some(suffix_index.as_()))
+        .chain(iter::once(None));
+    let lcp_values = iter::once(0)
+        .chain(lcp.iter().skip(1).map(|lcp_value| lcp_value.as_()))
+        .chain(iter::once(0));
+
+    // common_prefix_len is the one of the current entry with the top of the stack
+    for (suffix_index, mut common_prefix_len) in entries.zip(lcp_values) {
+        // the current entry is the next smaller one of the popped entries
+        while let Some(&(top_suffix_index, top_common_prefix_len)) = stack.last()
+            && suffix_index.is_none_or(|suffix_index| suffix_index < top_suffix_index)
+        {
+            stack.pop();
+
+            if top_common_prefix_len >= common_prefix_len {
+                if let Some(&(previous_suffix_index, _)) = stack.last() {
+                    lengths[top_suffix_index] = to_index(top_common_prefix_len);
+                    sources[top_suffix_index] = to_index(previous_suffix_index);
+                }
+            } else if let Some(suffix_index) = suffix_index {
+                lengths[top_suffix_index] = to_index(common_prefix_len);
+                sources[top_suffix_index] = to_index(suffix_index);
+            }
+
+            common_prefix_len = common_prefix_len.min(top_common_prefix_len);
+        }
+
+        if let Some(suffix_index) = suffix_index {
+            let common_prefix_len = if stack.is_empty() {
+                0
+            } else {
+                common_prefix_len
+            };
+
+            stack.push((suffix_index, common_prefix_len));
+        }
+    }
+
+    LongestPreviousFactors { lengths, sources }
+}
+
+// like longest_previous_factors, but source + length is at most the position. These lengths
+// decrease by at most 1 from one position to the next, because the factor without its first
+// character still ends before the next position. So the length at a position starts at the
+// previous one minus 1 and is extended one character at a time, which takes O(n) extensions.
+// An extension to length l is possible if the smallest suffix index in the suffix array
+// interval of the suffixes that share a prefix of length l with the position is at most the
+// position minus l. The interval and its smallest suffix index are found with segment trees
+// over the LCP and the suffix array in O(log n) time, so this takes O(n log n) time in total.
+pub fn longest_previous_non_overlapping_factors<I: IndexStorage>(
+    suffix_array: &[I],
+    lcp: &[I],
+) -> LongestPreviousFactors<I> {
+    assert_eq!(suffix_array.len(), lcp.len());
+
+    let len = suffix_array.len();
+    let mut lengths = vec![I::zero(); len];
+    let mut sources = vec![I::max_value(); len];
+
+    let mut inverse_suffix_array = vec![0; len];
+    for (index, suffix_index) in suffix_array.iter().enumerate() {
+        inverse_suffix_array[suffix_index.as_()] = index;
+    }
+
+    // the first LCP value is not defined, 0 makes the first entry the start of every interval
+    let lcp_tree = MinSegmentTree::new(
+        iter::once(0).chain(lcp.iter().skip(1).map(|lcp_value| lcp_value.as_())),
+        len,
+    );
+    let suffix_index_tree = MinSegmentTree::new(
+        suffix_array.iter().map(|suffix_index| suffix_index.as_()),
+        len,
+    );
+
+    // the smallest suffix index that shares a prefix of length factor_len with position
+    let smallest_source = |position: usize, factor_len: usize| {
+        let index = inverse_suffix_array[position];
+        let interval_start = lcp_tree.last_below(index + 1, factor_len).unwrap();
+        let interval_end = lcp_tree.first_below(index + 1, factor_len).unwrap_or(len);
+
+        suffix_index_tree.range_min(interval_start..interval_end)
+    };
+
+    let mut factor_len = 0;
+    let mut source = 0;
+
+    for position in 0..len {
+        if factor_len > 0 {
+            factor_len -= 1;
+            source += 1;
+        }
+
+        while factor_len < position {
+            let smallest_source = smallest_source(position, factor_len + 1);
+
+            if smallest_source + factor_len + 1 > position {
+                break;
+            }
+
+            factor_len += 1;
+            source = smallest_source;
+        }
+
+        if factor_len > 0 {
+            lengths[position] = to_index(factor_len);
+            sources[position] = to_index(source);
+        }
+    }
+
+    LongestPreviousFactors { lengths, sources }
+}
+
+// the greedy parse from left to right, where every phrase is the longest previous factor at its
+// start, or a literal if there is none. This is the parse with the smallest number of phrases.
+pub fn lz77_factorization<C: Character, I: IndexStorage>(
+    text: &[C],
+    suffix_array: &[I],
+    lcp: &[I],
+) -> Vec<Phrase<C>> {
+    assert_eq!(text.len(), suffix_array.len());
+
+    greedy_parse(text, &longest_previous_factors(suffix_array, lcp))
+}
+
+// the greedy parse with the longest previous non-overlapping factors, where every copy ends
+// before the start of its phrase. This is the parse with the smallest number of phrases among
+// the ones without overlaps.
+pub fn lz77_non_overlapping_factorization<C: Character, I: IndexStorage>(
+    text: &[C],
+    suffix_array: &[I],
+    lcp: &[I],
+) -> Vec<Phrase<C>> {
+    assert_eq!(text.len(), suffix_array.len());
+
+    greedy_parse(
+        text,
+        &longest_previous_non_overlapping_factors(suffix_array, lcp),
+    )
+}
+
+fn greedy_parse<C: Character, I: IndexStorage>(
+    text: &[C],
+    longest_previous_factors: &LongestPreviousFactors<I>,
+) -> Vec<Phrase<C>> {
+    let mut phrases = Vec::new();
+    let mut position = 0;
+
+    while position < text.len() {
+        let len: usize = longest_previous_factors.lengths[position].as_();
+
+        if len == 0 {
+            phrases.push(Phrase::Literal(text[position]));
+            position += 1;
+        } else {
+            phrases.push(Phrase::Copy {
+                source: longest_previous_factors.sources[position].as_(),
+                len,
+            });
+            position += len;
+        }
+    }
+
+    phrases
+}
+
+fn to_index<I: IndexStorage>(value: usize) -> I {
+    <I as NumCast>::from(value).unwrap()
+}
+
+// the minima of ranges of values in O(log n) time
+struct MinSegmentTree {
+    // the root is at 1, the children of node k at 2k and 2k + 1 and the values in the leaves
+    // from num_leaves on, padded with usize::MAX
+    nodes: Vec<usize>,
+    num_leaves: usize,
+}
+
+impl MinSegmentTree {
+    fn new(values: impl Iterator<Item = usize>, len: usize) -> Self {
+        let num_leaves = len.next_power_of_two();
+        let mut nodes = vec![usize::MAX; 2 * num_leaves];
+
+        for (node, value) in nodes[num_leaves..].iter_mut().zip(values) {
+            *node = value;
+        }
+
+        for node in (1..num_leaves).rev() {
+            nodes[node] = nodes[2 * node].min(nodes[2 * node + 1]);
+        }
+
+        Self { nodes, num_leaves }
+    }
+
+    fn range_min(&self, range: Range<usize>) -> usize {
+        let mut start = range.start + self.num_leaves;
+        let mut end = range.end + self.num_leaves;
+        let mut min = usize::MAX;
+
+        while start < end {
+            if start % 2 == 1 {
+                min = min.min(self.nodes[start]);
+                start += 1;
+            }
+
+            if end % 2 == 1 {
+                end -= 1;
+                min = min.min(self.nodes[end]);
+            }
+
+            start /= 2;
+            end /= 2;
+        }
+
+        min
+    }
+
+    // the largest index before end with a value below bound
+    fn last_below(&self, end: usize, bound: usize) -> Option<usize> {
+        self.last_below_in_node(1, 0..self.num_leaves, end, bound)
+    }
+
+    fn last_below_in_node(
+        &self,
+        node: usize,
+        node_range: Range<usize>,
+        end: usize,
+        bound: usize,
+    ) -> Option<usize> {
+        if node_range.start >= end || self.nodes[node] >= bound {
+            return None;
+        }
+
+        if node >= self.num_leaves {
+            return Some(node_range.start);
+        }
+
+        let middle = node_range.start + node_range.len() / 2;
+
+        self.last_below_in_node(2 * node + 1, middle..node_range.end, end, bound)
+            .or_else(|| self.last_below_in_node(2 * node, node_range.start..middle, end, bound))
+    }
+
+    // the smallest index from start on with a value below bound
+    fn first_below(&self, start: usize, bound: usize) -> Option<usize> {
+        self.first_below_in_node(1, 0..self.num_leaves, start, bound)
+    }
+
+    fn first_below_in_node(
+        &self,
+        node: usize,
+        node_range: Range<usize>,
+        start: usize,
+        bound: usize,
+    ) -> Option<usize> {
+        if node_range.end <= start || self.nodes[node] >= bound {
+            return None;
+        }
+
+        if node >= self.num_leaves {
+            return Some(node_range.start);
+        }
+
+        let middle = node_range.start + node_range.len() / 2;
+
+        self.first_below_in_node(2 * node, node_range.start..middle, start, bound)
+            .or_else(|| {
+                self.first_below_in_node(2 * node + 1, middle..node_range.end, start, bound)
+            })
+    }
+}
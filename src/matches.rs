@@ -0,0 +1,146 @@
+// Exact matches between two texts via their generalized suffix array, e.g. for the anchoring of
+// genome alignments like in MUMmer. The matches are (position in a, position in b, length)
+// triples. A maximal exact match (MEM) can neither be extended to the left nor to the right, a
+// maximal unique match (MUM) is a MEM that occurs exactly once in each text.
+
+use std::collections::BTreeSet;
+
+use crate::{Character, IndexStorage, SaisBuilder, enhanced_suffix_array::EnhancedSuffixArray};
+
+// the MUMs with a length of at least min_len, sorted by their positions
+pub fn maximal_unique_matches<C: Character, I: IndexStorage>(
+    text_a: &[C],
+    text_b: &[C],
+    min_len: usize,
+) -> Vec<(usize, usize, usize)> {
+    let generalized_text = GeneralizedText::new(text_a, text_b);
+    let enhanced_suffix_array = generalized_text.enhanced_suffix_array::<I>();
+    let suffix_array = enhanced_suffix_array.suffix_array();
+    let mut matches = Vec::new();
+
+    // the string of an interval with two suffixes only occurs there, and it is right-maximal
+    enhanced_suffix_array.bottom_up_traversal(|interval, _| {
+        if interval.len() != 2 || interval.lcp < min_len.max(1) {
+            return;
+        }
+
+        matches.extend(generalized_text.maximal_match(
+            suffix_array[interval.start].as_(),
+            suffix_array[interval.start + 1].as_(),
+            interval.lcp,
+        ));
+    });
+
+    matches.sort_unstable();
+    matches
+}
+
+// the MEMs with a length of at least min_len, sorted by their positions. Every pair of suffixes
+// of a and b with a common prefix of at least min_len is inspected once, so the running time
+// depends on how repetitive the texts are and can be much larger than the number of MEMs.
+pub fn maximal_exact_matches<C: Character, I: IndexStorage>(
+    text_a: &[C],
+    text_b: &[C],
+    min_len: usize,
+) -> Vec<(usize, usize, usize)> {
+    let generalized_text = GeneralizedText::new(text_a, text_b);
+    let enhanced_suffix_array = generalized_text.enhanced_suffix_array::<I>();
+    let suffix_array = enhanced_suffix_array.suffix_array();
+    let mut matches = Vec::new();
+
+    // suffixes from different child intervals have exactly the lcp value of the interval as
+    // their longest common prefix, so their match is right-maximal
+    enhanced_suffix_array.bottom_up_traversal(|interval, children| {
+        if interval.lcp < min_len.max(1) {
+            return;
+        }
+
+        for (child_index, first_child) in children.iter().enumerate() {
+            for second_child in &children[child_index + 1..] {
+                for first_suffix in &suffix_array[first_child.range()] {
+                    for second_suffix in &suffix_array[second_child.range()] {
+                        matches.extend(generalized_text.maximal_match(
+                            first_suffix.as_(),
+                            second_suffix.as_(),
+                            interval.lcp,
+                        ));
+                    }
+                }
+            }
+        }
+    });
+
+    matches.sort_unstable();
+    matches
+}
+
+// a, followed by the separator and then b, over the dense alphabet 1..=σ of the characters that
+// appear in the texts. The separator 0 is unique, so common prefixes of suffixes of a and b end
+// before it.
+struct GeneralizedText {
+    codes: Vec<u16>,
+    max_code: u16,
+    len_a: usize,
+}
+
+impl GeneralizedText {
+    fn new<C: Character>(text_a: &[C], text_b: &[C]) -> Self {
+        let alphabet: Vec<C> = text_a
+            .iter()
+            .chain(text_b)
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        assert!(
+            alphabet.len() <= u16::MAX as usize,
+            "the texts use too many different characters"
+        );
+
+        let encode = |char: &C| alphabet.binary_search(char).unwrap() as u16 + 1;
+
+        let mut codes = Vec::with_capacity(text_a.len() + 1 + text_b.len());
+        codes.extend(text_a.iter().map(encode));
+        codes.push(0);
+        codes.extend(text_b.iter().map(encode));
+
+        Self {
+            codes,
+            max_code: alphabet.len() as u16,
+            len_a: text_a.len(),
+        }
+    }
+
+    fn enhanced_suffix_array<I: IndexStorage>(&self) -> EnhancedSuffixArray<'_, u16, I> {
+        let mut builder = SaisBuilder::new();
+        builder.with_max_char(self.max_code);
+
+        EnhancedSuffixArray::construct(&self.codes, &builder)
+    }
+
+    // the match of two suffixes with a right-maximal common prefix, if one of them is from a, the
+    // other one from b and the match is also left-maximal
+    fn maximal_match(
+        &self,
+        first_suffix: usize,
+        second_suffix: usize,
+        len: usize,
+    ) -> Option<(usize, usize, usize)> {
+        let start_b = self.len_a + 1;
+
+        let (position_a, position_b) = if first_suffix < self.len_a && second_suffix >= start_b {
+            (first_suffix, second_suffix)
+        } else if second_suffix < self.len_a && first_suffix >= start_b {
+            (second_suffix, first_suffix)
+        } else {
+            return None;
+        };
+
+        let is_left_maximal = position_a == 0
+            || position_b == start_b
+            || self.codes[position_a - 1] != self.codes[position_b - 1];
+
+        is_left_maximal.then_some((position_a, position_b - start_b, len))
+    }
+}
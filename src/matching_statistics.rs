@@ -0,0 +1,139 @@
+// Matching statistics of a query against an indexed text: for every position i of the query, the
+// length of the longest prefix of query[i..] that occurs in the text, and the suffix array
+// interval of its occurrences. They are computed by walking the suffix tree of the text along
+// the query and following a suffix link after every position (Chang and Lawler, 1994). The
+// children are found by binary search, so this takes O(m log σ) time for a query of length m.
+
+use std::ops::Range;
+
+use crate::{Character, IndexStorage, suffix_tree::SuffixTree};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchingStatistics {
+    pub lengths: Vec<usize>,
+    // the occurrences of the longest match, as a range of the suffix array. The empty match
+    // occurs at every position.
+    pub intervals: Vec<Range<usize>>,
+}
+
+pub fn matching_statistics<C: Character, I: IndexStorage>(
+    suffix_tree: &SuffixTree<C, I>,
+    query: &[C],
+) -> MatchingStatistics {
+    let mut lengths = Vec::with_capacity(query.len());
+    let mut intervals = Vec::with_capacity(query.len());
+
+    // the match of the current query position ends on the edge from node to edge_child, or at
+    // node if there is no edge child
+    let mut locus = Locus {
+        node: suffix_tree.root(),
+        edge_child: None,
+        len: 0,
+    };
+
+    for position in 0..query.len() {
+        locus.extend(suffix_tree, &query[position..]);
+
+        lengths.push(locus.len);
+        intervals.push(suffix_tree.leaf_range(locus.edge_child.unwrap_or(locus.node)));
+
+        if locus.len == 0 {
+            continue;
+        }
+
+        // the match of the next position is at least the current one without its first character
+        let node = if suffix_tree.is_root(locus.node) {
+            locus.node
+        } else {
+            locus.suffix_linked_node(suffix_tree)
+        };
+
+        locus = Locus {
+            node,
+            edge_child: None,
+            len: locus.len - 1,
+        };
+        locus.skip_down(suffix_tree, &query[position + 1..]);
+    }
+
+    MatchingStatistics { lengths, intervals }
+}
+
+struct Locus {
+    node: usize,
+    edge_child: Option<usize>,
+    len: usize,
+}
+
+impl Locus {
+    // compares characters until the match can't be extended
+    fn extend<C: Character, I: IndexStorage>(
+        &mut self,
+        suffix_tree: &SuffixTree<C, I>,
+        query: &[C],
+    ) {
+        while let Some(&next_char) = query.get(self.len) {
+            let edge_child = match self.edge_child {
+                Some(edge_child) => edge_child,
+                None => match suffix_tree.child_with_char(self.node, next_char) {
+                    Some(child) => child,
+                    None => return,
+                },
+            };
+
+            let (label_start, _) = suffix_tree.edge_label(edge_child);
+            let edge_offset = self.len - suffix_tree.string_depth(self.node);
+
+            if suffix_tree.text()[label_start + edge_offset] != next_char {
+                self.edge_child = Some(edge_child);
+                return;
+            }
+
+            self.len += 1;
+
+            if self.len == suffix_tree.string_depth(edge_child) {
+                self.node = edge_child;
+                self.edge_child = None;
+            } else {
+                self.edge_child = Some(edge_child);
+            }
+        }
+    }
+
+    // the target of the suffix link of a leaf can be a leaf at an edge of length 0, then its
+    // parent has the same string and also the children to continue the match
+    fn suffix_linked_node<C: Character, I: IndexStorage>(
+        &self,
+        suffix_tree: &SuffixTree<C, I>,
+    ) -> usize {
+        let node = suffix_tree.suffix_link(self.node).unwrap();
+
+        match suffix_tree.parent(node) {
+            Some(parent) if suffix_tree.string_depth(parent) == suffix_tree.string_depth(node) => {
+                parent
+            }
+            _ => node,
+        }
+    }
+
+    // the match is known to occur, so only the first character of every edge is inspected
+    // (skip/count trick)
+    fn skip_down<C: Character, I: IndexStorage>(
+        &mut self,
+        suffix_tree: &SuffixTree<C, I>,
+        query: &[C],
+    ) {
+        while suffix_tree.string_depth(self.node) < self.len {
+            let child = suffix_tree
+                .child_with_char(self.node, query[suffix_tree.string_depth(self.node)])
+                .unwrap();
+
+            if suffix_tree.string_depth(child) <= self.len {
+                self.node = child;
+            } else {
+                self.edge_child = Some(child);
+                return;
+            }
+        }
+    }
+}
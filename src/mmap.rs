@@ -0,0 +1,101 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::algorithm::buffer_management::BufferConfig;
+use crate::{Character, IndexStorage, SaisBuilder};
+
+// a suffix array in a file, stored as I values in native endianness
+pub struct MappedSuffixArray<I> {
+    file: File,
+    mmap: Mmap,
+    len: usize,
+    _marker: PhantomData<I>,
+}
+
+impl<I: IndexStorage> MappedSuffixArray<I> {
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    pub fn into_file(self) -> File {
+        self.file
+    }
+}
+
+impl<I: IndexStorage> Deref for MappedSuffixArray<I> {
+    type Target = [I];
+
+    fn deref(&self) -> &[I] {
+        // SAFETY: the mapping is page aligned, has the size of len values and every bit
+        // pattern is a valid index value
+        unsafe { std::slice::from_raw_parts(self.mmap.as_ptr().cast(), self.len) }
+    }
+}
+
+impl<C: Character, I: IndexStorage> SaisBuilder<C, I> {
+    // creates or overwrites the output file and uses a memory mapping of it as the suffix array
+    // buffer. The file is temporarily enlarged, such that the helper buffers of the algorithm
+    // are also placed in the mapping. The output file must not be modified by other processes
+    // while the returned handle exists.
+    pub fn construct_suffix_array_into_file(
+        &self,
+        text: &[C],
+        output_path: impl AsRef<Path>,
+    ) -> io::Result<MappedSuffixArray<I>> {
+        let surplus_len = if text.len() < self.small_text_threshold {
+            0
+        } else {
+            let num_buckets = self.get_max_char().rank() + 1;
+
+            BufferConfig::surplus_len_for_main_buffer_only::<I>(
+                text.len(),
+                num_buckets,
+                self.bucket_granularity_for(num_buckets),
+            )
+        };
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(output_path)?;
+
+        file.set_len(byte_len::<I>(text.len() + surplus_len))?;
+
+        // SAFETY: see the documentation of this function
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        // SAFETY: the mapping is page aligned, has the size of the buffer and every bit
+        // pattern is a valid index value
+        let suffix_array_buffer = unsafe {
+            std::slice::from_raw_parts_mut(mmap.as_mut_ptr().cast::<I>(), text.len() + surplus_len)
+        };
+
+        self.construct_suffix_array_inplace(text, suffix_array_buffer);
+
+        mmap.flush()?;
+        drop(mmap);
+
+        file.set_len(byte_len::<I>(text.len()))?;
+
+        // SAFETY: see the documentation of this function
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        Ok(MappedSuffixArray {
+            file,
+            mmap,
+            len: text.len(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+fn byte_len<I>(len: usize) -> u64 {
+    (len * size_of::<I>()) as u64
+}
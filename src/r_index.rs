@@ -0,0 +1,405 @@
+// The run-length encoded BWT and the r-index of Gagie, Navarro and Prezza (2018), which count and
+// locate the occurrences of patterns in O(r) space, where r is the number of runs of equal
+// characters in the BWT. For repetitive texts like pangenome collections, r is much smaller than
+// the text length n. The construction goes through the suffix array, but only O(r) values are kept.
+//
+// Positions refer to the full BWT of the text with the sentinel, which has n + 1 entries, with the
+// sentinel at the primary index of bwt::construct_bwt. The full BWT position p belongs to the
+// suffix array entry p - 1, position 0 belongs to the sentinel suffix.
+
+use std::{cmp::Ordering, iter, ops::Range};
+
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage, SaisBuilder, bwt};
+
+pub struct RunLengthBwt<C, I> {
+    // the first position of every run in the full BWT
+    run_starts: Vec<I>,
+    // None for the run of the sentinel
+    run_chars: Vec<Option<C>>,
+    text_len: usize,
+    // the characters of the text in ascending order
+    alphabet: Vec<C>,
+    // per character of the alphabet: the number of smaller characters in the full BWT, including
+    // the sentinel, and its runs
+    smaller_char_counts: Vec<I>,
+    char_runs: Vec<CharRuns<I>>,
+}
+
+struct CharRuns<I> {
+    run_indices: Vec<I>,
+    // the number of occurrences of the character before each of its runs, and in total
+    preceding_counts: Vec<I>,
+}
+
+impl<C: Character, I: IndexStorage> RunLengthBwt<C, I> {
+    // from the output of bwt::construct_bwt
+    pub fn new(bwt: &[C], primary_index: usize) -> Self {
+        assert!(
+            bwt.len() < I::max_value().as_(),
+            "the BWT is too long for the index type"
+        );
+        assert!(primary_index <= bwt.len());
+
+        let full_bwt = (0..=bwt.len()).map(|position| match position.cmp(&primary_index) {
+            Ordering::Less => Some(bwt[position]),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(bwt[position - 1]),
+        });
+
+        let mut run_starts: Vec<I> = Vec::new();
+        let mut run_chars = Vec::new();
+
+        for (position, char) in full_bwt.enumerate() {
+            if run_chars.last() != Some(&char) {
+                run_starts.push(to_index(position));
+                run_chars.push(char);
+            }
+        }
+
+        let mut alphabet: Vec<C> = run_chars.iter().flatten().copied().collect();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        let mut char_runs: Vec<_> = alphabet
+            .iter()
+            .map(|_| CharRuns {
+                run_indices: Vec::new(),
+                preceding_counts: vec![I::zero()],
+            })
+            .collect();
+
+        for (run_index, char) in run_chars.iter().enumerate() {
+            let Some(char) = char else {
+                continue;
+            };
+
+            let run_end = run_starts
+                .get(run_index + 1)
+                .map_or(bwt.len() + 1, |run_start| run_start.as_());
+            let run_len = run_end - run_starts[run_index].as_();
+
+            let runs = &mut char_runs[alphabet.binary_search(char).unwrap()];
+            let preceding_count = *runs.preceding_counts.last().unwrap();
+
+            runs.run_indices.push(to_index(run_index));
+            runs.preceding_counts
+                .push(preceding_count + to_index(run_len));
+        }
+
+        // the sentinel is smaller than all characters
+        let mut smaller_char_counts = Vec::with_capacity(alphabet.len());
+        let mut smaller_char_count = I::one();
+
+        for runs in &char_runs {
+            smaller_char_counts.push(smaller_char_count);
+            smaller_char_count = smaller_char_count + *runs.preceding_counts.last().unwrap();
+        }
+
+        Self {
+            run_starts,
+            run_chars,
+            text_len: bwt.len(),
+            alphabet,
+            smaller_char_counts,
+            char_runs,
+        }
+    }
+
+    pub fn text_len(&self) -> usize {
+        self.text_len
+    }
+
+    pub fn num_runs(&self) -> usize {
+        self.run_starts.len()
+    }
+
+    // (character, length) of every run, None is the sentinel
+    pub fn runs(&self) -> impl Iterator<Item = (Option<C>, usize)> + '_ {
+        self.run_chars
+            .iter()
+            .enumerate()
+            .map(|(run_index, &char)| (char, self.run_range(run_index).len()))
+    }
+
+    // the character at a position of the full BWT, None for the sentinel
+    pub fn get(&self, position: usize) -> Option<C> {
+        assert!(position <= self.text_len);
+        self.run_chars[self.run_index(position)]
+    }
+
+    // the number of occurrences of the character in the full BWT before the position
+    pub fn rank(&self, char: C, position: usize) -> usize {
+        assert!(position <= self.text_len + 1);
+
+        self.alphabet
+            .binary_search(&char)
+            .map_or(0, |char_index| self.rank_by_index(char_index, position))
+    }
+
+    // the LF mapping: the position of the suffix that starts one character earlier than the one
+    // at the given position. None for the sentinel, which precedes the whole text.
+    pub fn lf(&self, position: usize) -> Option<usize> {
+        let char = self.get(position)?;
+        let char_index = self.alphabet.binary_search(&char).unwrap();
+
+        Some(self.smaller_char_counts[char_index].as_() + self.rank_by_index(char_index, position))
+    }
+
+    // back to the layout of bwt::construct_bwt
+    pub fn to_bwt(&self) -> (Vec<C>, usize) {
+        let mut bwt = Vec::with_capacity(self.text_len);
+        let mut primary_index = 0;
+
+        for (char, len) in self.runs() {
+            match char {
+                Some(char) => bwt.extend(iter::repeat_n(char, len)),
+                None => primary_index = bwt.len(),
+            }
+        }
+
+        (bwt, primary_index)
+    }
+
+    // the range of the full BWT for the pattern extended by the character to the left
+    fn backward_step(&self, range: Range<usize>, char_index: usize) -> Range<usize> {
+        let smaller_char_count: usize = self.smaller_char_counts[char_index].as_();
+
+        smaller_char_count + self.rank_by_index(char_index, range.start)
+            ..smaller_char_count + self.rank_by_index(char_index, range.end)
+    }
+
+    fn rank_by_index(&self, char_index: usize, position: usize) -> usize {
+        if position == 0 {
+            return 0;
+        }
+
+        let run_index = self.run_index(position - 1);
+        let runs = &self.char_runs[char_index];
+        let num_preceding_runs = runs
+            .run_indices
+            .partition_point(|&other_run_index| other_run_index.as_() < run_index);
+
+        let mut rank = runs.preceding_counts[num_preceding_runs].as_();
+
+        if self.run_chars[run_index] == Some(self.alphabet[char_index]) {
+            rank += position - self.run_starts[run_index].as_();
+        }
+
+        rank
+    }
+
+    // the last run of the character before the given run
+    fn previous_run_of_char(&self, char_index: usize, run_index: usize) -> Option<usize> {
+        let run_indices = &self.char_runs[char_index].run_indices;
+        let num_preceding_runs =
+            run_indices.partition_point(|&other_run_index| other_run_index.as_() < run_index);
+
+        num_preceding_runs
+            .checked_sub(1)
+            .map(|index| run_indices[index].as_())
+    }
+
+    fn run_index(&self, position: usize) -> usize {
+        self.run_starts
+            .partition_point(|&run_start| run_start.as_() <= position)
+            - 1
+    }
+
+    fn run_range(&self, run_index: usize) -> Range<usize> {
+        let run_end = self
+            .run_starts
+            .get(run_index + 1)
+            .map_or(self.text_len + 1, |run_start| run_start.as_());
+
+        self.run_starts[run_index].as_()..run_end
+    }
+}
+
+pub struct RIndex<C, I> {
+    bwt: RunLengthBwt<C, I>,
+    // the suffix at the last position of every run, used as the toehold of the backward search
+    run_end_suffixes: Vec<I>,
+    // phi(j) is the suffix before suffix j in the suffix array. It is stored for the suffixes
+    // before the ones at run starts (sorted), and derived for the others (see phi).
+    phi_sample_suffixes: Vec<I>,
+    phi_sample_values: Vec<I>,
+}
+
+impl<C: Character, I: IndexStorage> RIndex<C, I> {
+    pub fn construct(text: &[C], builder: &SaisBuilder<C, I>) -> Self {
+        let suffix_array = builder.construct_suffix_array(text);
+        Self::new(text, &suffix_array)
+    }
+
+    pub fn new(text: &[C], suffix_array: &[I]) -> Self {
+        let (bwt, primary_index) = bwt::construct_bwt(text, suffix_array);
+        let bwt = RunLengthBwt::new(&bwt, primary_index);
+
+        let text_len = text.len();
+        let full_suffix_array = |position: usize| {
+            position
+                .checked_sub(1)
+                .map_or(text_len, |index| suffix_array[index].as_())
+        };
+
+        let run_end_suffixes = (0..bwt.num_runs())
+            .map(|run_index| to_index(full_suffix_array(bwt.run_range(run_index).end - 1)))
+            .collect();
+
+        // phi(j) is sampled where the suffix j + 1 is at a run start p, then phi(j) is the suffix
+        // before LF(p). This includes j = n - 1, because the sentinel suffix is at position 0.
+        let mut phi_samples: Vec<(I, I)> = (0..bwt.num_runs())
+            .filter_map(|run_index| {
+                let run_start = bwt.run_range(run_index).start;
+                let next_position = bwt.lf(run_start)?;
+
+                Some((
+                    to_index(full_suffix_array(run_start) - 1),
+                    to_index(full_suffix_array(next_position - 1)),
+                ))
+            })
+            .collect();
+
+        phi_samples.sort_unstable();
+
+        let (phi_sample_suffixes, phi_sample_values) = phi_samples.into_iter().unzip();
+
+        Self {
+            bwt,
+            run_end_suffixes,
+            phi_sample_suffixes,
+            phi_sample_values,
+        }
+    }
+
+    pub fn bwt(&self) -> &RunLengthBwt<C, I> {
+        &self.bwt
+    }
+
+    // the range of suffix array entries whose suffixes start with the pattern, like
+    // search::pattern_range
+    pub fn pattern_range(&self, pattern: &[C]) -> Range<usize> {
+        let (range, _) = self.backward_search(pattern);
+        range.start - 1..range.end - 1
+    }
+
+    pub fn count(&self, pattern: &[C]) -> usize {
+        self.pattern_range(pattern).len()
+    }
+
+    // the text positions of all occurrences, in reverse suffix array order
+    pub fn locate(&self, pattern: &[C]) -> Locate<'_, C, I> {
+        let (range, last_suffix) = self.backward_search(pattern);
+
+        Locate {
+            r_index: self,
+            next_suffix: last_suffix,
+            remaining: range.len(),
+        }
+    }
+
+    // the range of the pattern in the full BWT, together with the suffix at its last position,
+    // the toehold. If the pattern doesn't occur, the range is empty and its position is the one
+    // where the pattern would be inserted. The empty pattern matches all suffixes except the
+    // sentinel.
+    fn backward_search(&self, pattern: &[C]) -> (Range<usize>, usize) {
+        let mut range = 0..self.bwt.text_len + 1;
+        let mut last_suffix = self.run_end_suffixes.last().unwrap().as_();
+
+        if pattern.is_empty() {
+            return (1..range.end, last_suffix);
+        }
+
+        for char in pattern.iter().rev() {
+            let char_index = match self.bwt.alphabet.binary_search(char) {
+                Ok(char_index) => char_index,
+                Err(insertion_index) => {
+                    let position = self
+                        .bwt
+                        .smaller_char_counts
+                        .get(insertion_index)
+                        .map_or(self.bwt.text_len + 1, |count| count.as_());
+
+                    range = position..position;
+                    continue;
+                }
+            };
+
+            let next_range = self.bwt.backward_step(range.clone(), char_index);
+
+            if next_range.is_empty() {
+                range = next_range;
+                continue;
+            }
+
+            // the last occurrence of the character in the range is mapped to the last position of
+            // the next range. If it is not at the end of the range, it is at the end of a run.
+            let last_run_index = self.bwt.run_index(range.end - 1);
+
+            last_suffix = if self.bwt.run_chars[last_run_index] == Some(*char) {
+                last_suffix - 1
+            } else {
+                let run_index = self
+                    .bwt
+                    .previous_run_of_char(char_index, last_run_index)
+                    .unwrap();
+                self.run_end_suffixes[run_index].as_() - 1
+            };
+
+            range = next_range;
+        }
+
+        (range, last_suffix)
+    }
+
+    // If the suffix j + 1 is not at a run start, it is preceded by the same character as the suffix
+    // phi(j + 1), so the suffixes j and phi(j + 1) - 1 are also neighbors in the suffix array and
+    // phi(j) = phi(j + 1) - 1. Therefore phi(j) is derived from the next sample at or after j.
+    fn phi(&self, suffix: usize) -> usize {
+        let sample_index = self
+            .phi_sample_suffixes
+            .partition_point(|&sample_suffix| sample_suffix.as_() < suffix);
+
+        let sample_suffix: usize = self.phi_sample_suffixes[sample_index].as_();
+        let sample_value: usize = self.phi_sample_values[sample_index].as_();
+
+        sample_value - (sample_suffix - suffix)
+    }
+}
+
+fn to_index<I: IndexStorage>(value: usize) -> I {
+    <I as NumCast>::from(value).unwrap()
+}
+
+pub struct Locate<'r, C, I> {
+    r_index: &'r RIndex<C, I>,
+    next_suffix: usize,
+    remaining: usize,
+}
+
+impl<C: Character, I: IndexStorage> Iterator for Locate<'_, C, I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let suffix = self.next_suffix;
+        self.remaining -= 1;
+
+        if self.remaining > 0 {
+            self.next_suffix = self.r_index.phi(suffix);
+        }
+
+        Some(suffix)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<C: Character, I: IndexStorage> ExactSizeIterator for Locate<'_, C, I> {}
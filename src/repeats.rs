@@ -0,0 +1,145 @@
+// Repeated substrings of a text, found via its suffix array and LCP array. A repeat occurs at
+// least twice, occurrences may overlap. It is maximal if its occurrences can neither all be
+// extended by the same character to the left nor to the right, and supermaximal if it is not
+// a substring of another repeat. The start and end of the text count as unique characters.
+
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage, enhanced_suffix_array::EnhancedSuffixArray};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Repeat {
+    pub len: usize,
+    // the start positions of the occurrences in the text, in ascending order
+    pub positions: Vec<usize>,
+}
+
+// if there are multiple longest repeated substrings, the lexicographically smallest is returned.
+// None if no character occurs twice.
+pub fn longest_repeated_substring<I: IndexStorage>(
+    suffix_array: &[I],
+    lcp: &[I],
+) -> Option<Repeat> {
+    assert_eq!(suffix_array.len(), lcp.len());
+
+    let (index, &max_lcp_value) = lcp
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, lcp_value)| *lcp_value)?;
+
+    if max_lcp_value == I::zero() {
+        return None;
+    }
+
+    // all suffixes with this prefix are next to each other in the suffix array
+    let start = index - 1;
+    let end = index
+        + lcp[index..]
+            .iter()
+            .take_while(|&&lcp_value| lcp_value >= max_lcp_value)
+            .count();
+
+    Some(repeat(max_lcp_value.as_(), &suffix_array[start..end]))
+}
+
+// the maximal repeats with a length of at least min_len, in the order of a bottom-up traversal
+// of the LCP-intervals
+pub fn maximal_repeats<C: Character, I: IndexStorage>(
+    enhanced_suffix_array: &EnhancedSuffixArray<C, I>,
+    min_len: usize,
+) -> Vec<Repeat> {
+    let suffix_array = enhanced_suffix_array.suffix_array();
+    let left_context_changes = left_context_changes(enhanced_suffix_array);
+    let mut repeats = Vec::new();
+
+    // the LCP-intervals are exactly the right-maximal repeats
+    enhanced_suffix_array.bottom_up_traversal(|interval, _| {
+        let is_left_maximal = left_context_changes[interval.start].as_() < interval.end;
+
+        if interval.lcp >= min_len.max(1) && is_left_maximal {
+            repeats.push(repeat(interval.lcp, &suffix_array[interval.range()]));
+        }
+    });
+
+    repeats
+}
+
+// the supermaximal repeats with a length of at least min_len, in the order of a bottom-up
+// traversal of the LCP-intervals
+pub fn supermaximal_repeats<C: Character, I: IndexStorage>(
+    enhanced_suffix_array: &EnhancedSuffixArray<C, I>,
+    min_len: usize,
+) -> Vec<Repeat> {
+    let text = enhanced_suffix_array.text();
+    let suffix_array = enhanced_suffix_array.suffix_array();
+    let mut left_contexts = Vec::new();
+    let mut repeats = Vec::new();
+
+    // the LCP-intervals without child intervals, where all left contexts are distinct. These
+    // intervals are disjoint, so the sorting of the contexts takes O(n log σ) in total.
+    enhanced_suffix_array.bottom_up_traversal(|interval, children| {
+        if interval.lcp < min_len.max(1) || !children.iter().all(|child| child.is_leaf()) {
+            return;
+        }
+
+        left_contexts.clear();
+        left_contexts.extend(suffix_array[interval.range()].iter().map(|suffix_index| {
+            let suffix_index: usize = suffix_index.as_();
+            suffix_index.checked_sub(1).map(|previous| text[previous])
+        }));
+        left_contexts.sort_unstable();
+
+        let has_distinct_left_contexts = left_contexts
+            .windows(2)
+            .all(|window| window[0].is_none() || window[0] != window[1]);
+
+        if has_distinct_left_contexts {
+            repeats.push(repeat(interval.lcp, &suffix_array[interval.range()]));
+        }
+    });
+
+    repeats
+}
+
+// for every suffix array entry, the next entry whose left context (the preceding character or
+// the start of the text) is different. An interval is left-maximal exactly if this entry of its
+// start lies inside of it.
+fn left_context_changes<C: Character, I: IndexStorage>(
+    enhanced_suffix_array: &EnhancedSuffixArray<C, I>,
+) -> Vec<I> {
+    let text = enhanced_suffix_array.text();
+    let suffix_array = enhanced_suffix_array.suffix_array();
+
+    let left_context = |index: usize| {
+        let suffix_index: usize = suffix_array[index].as_();
+        suffix_index.checked_sub(1).map(|previous| text[previous])
+    };
+
+    let mut changes = vec![I::zero(); suffix_array.len()];
+    let mut next_change = suffix_array.len();
+
+    for index in (0..suffix_array.len()).rev() {
+        let next_left_context = suffix_array.get(index + 1).map(|_| left_context(index + 1));
+
+        // the start of the text is unique, so it differs from everything
+        match (left_context(index), next_left_context) {
+            (Some(char), Some(Some(next_char))) if char == next_char => {}
+            _ => next_change = index + 1,
+        }
+
+        changes[index] = <I as NumCast>::from(next_change).unwrap();
+    }
+
+    changes
+}
+
+fn repeat<I: IndexStorage>(len: usize, suffix_array_entries: &[I]) -> Repeat {
+    let mut positions: Vec<_> = suffix_array_entries
+        .iter()
+        .map(|suffix_index| suffix_index.as_())
+        .collect();
+    positions.sort_unstable();
+
+    Repeat { len, positions }
+}
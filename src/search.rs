@@ -0,0 +1,42 @@
+use std::ops::Range;
+
+use crate::IndexStorage;
+
+// the range of suffix array entries whose suffixes start with the pattern. The empty pattern
+// matches every suffix.
+pub fn pattern_range<C: Ord, I: IndexStorage>(
+    text: &[C],
+    suffix_array: &[I],
+    pattern: &[C],
+) -> Range<usize> {
+    let suffix_prefix = |suffix_index: &I| {
+        let suffix = &text[suffix_index.as_()..];
+        &suffix[..pattern.len().min(suffix.len())]
+    };
+
+    let start = suffix_array.partition_point(|suffix_index| suffix_prefix(suffix_index) < pattern);
+    let end = start
+        + suffix_array[start..]
+            .partition_point(|suffix_index| suffix_prefix(suffix_index) <= pattern);
+
+    start..end
+}
+
+pub fn count_occurrences<C: Ord, I: IndexStorage>(
+    text: &[C],
+    suffix_array: &[I],
+    pattern: &[C],
+) -> usize {
+    pattern_range(text, suffix_array, pattern).len()
+}
+
+// the text positions of all occurrences, in suffix array order
+pub fn locate_occurrences<'a, C: Ord, I: IndexStorage>(
+    text: &[C],
+    suffix_array: &'a [I],
+    pattern: &[C],
+) -> impl Iterator<Item = usize> + 'a {
+    suffix_array[pattern_range(text, suffix_array, pattern)]
+        .iter()
+        .map(|suffix_index| suffix_index.as_())
+}
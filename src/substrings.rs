@@ -0,0 +1,185 @@
+// Statistics of the substrings of a text from its suffix array and LCP array. The k-mers are
+// groups of neighboring suffixes that share a prefix of length k, and every suffix contributes the
+// distinct substrings that are longer than its LCP value, so everything is a single LCP scan.
+
+use std::ops::RangeInclusive;
+
+use crate::IndexStorage;
+
+// the k-mers of the text with their number of occurrences for every k in the range, from a single
+// LCP scan. The groups of a larger k nest inside the groups of a smaller k, and a k-mer is reported
+// when its group ends, with the shorter k-mers first for groups that end at the same suffix. So the
+// k-mers of every single k appear in the lexicographical order, and a range with a single k gives
+// the k-mers in suffix array order.
+pub fn kmer_counts<'a, C, I: IndexStorage>(
+    text: &'a [C],
+    suffix_array: &'a [I],
+    lcp: &'a [I],
+    ks: RangeInclusive<usize>,
+) -> KmerCounts<'a, C, I> {
+    assert_eq!(text.len(), suffix_array.len());
+    assert_eq!(text.len(), lcp.len());
+    assert!(*ks.start() > 0, "k has to be at least 1");
+
+    KmerCounts {
+        text,
+        suffix_array,
+        lcp,
+        min_k: *ks.start(),
+        max_k: *ks.end(),
+        index: 0,
+        open_groups: Vec::new(),
+        ended_groups: Vec::new(),
+    }
+}
+
+pub struct KmerCounts<'a, C, I> {
+    text: &'a [C],
+    suffix_array: &'a [I],
+    lcp: &'a [I],
+    min_k: usize,
+    max_k: usize,
+    index: usize,
+    // (start, k) of a stack entry stands for the groups that start at this suffix for all k above
+    // the k of the entry below up to k
+    open_groups: Vec<(usize, usize)>,
+    // (start, k, count) of the groups that ended at the current suffix, reported from the back
+    ended_groups: Vec<(usize, usize, usize)>,
+}
+
+impl<'a, C, I: IndexStorage> KmerCounts<'a, C, I> {
+    // ends the groups of all k above max_open_k before the suffix at self.index
+    fn end_groups(&mut self, max_open_k: usize) {
+        while let Some(&(start, k)) = self.open_groups.last() {
+            if k <= max_open_k {
+                break;
+            }
+
+            let below_k = self
+                .open_groups
+                .len()
+                .checked_sub(2)
+                .map_or(0, |below| self.open_groups[below].1);
+
+            if below_k >= max_open_k {
+                self.open_groups.pop();
+            } else {
+                self.open_groups.last_mut().unwrap().1 = max_open_k;
+            }
+
+            // the entries are ended from the largest k to the smallest, so pushing every entry in
+            // descending order reports the shorter k-mers first
+            let count = self.index - start;
+            let ended_ks = below_k.max(max_open_k).max(self.min_k - 1) + 1..=k;
+            self.ended_groups
+                .extend(ended_ks.rev().map(|ended_k| (start, ended_k, count)));
+        }
+    }
+}
+
+impl<'a, C, I: IndexStorage> Iterator for KmerCounts<'a, C, I> {
+    type Item = (&'a [C], usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((start, k, count)) = self.ended_groups.pop() {
+                let suffix_index: usize = self.suffix_array[start].as_();
+                return Some((&self.text[suffix_index..suffix_index + k], count));
+            }
+
+            if self.index > self.suffix_array.len() || self.min_k > self.max_k {
+                return None;
+            }
+
+            // behind the last suffix, all groups end. suffixes shorter than k have an LCP value
+            // smaller than k with both neighbors, so they never start a group for k
+            let (lcp_value, max_k) = match self.suffix_array.get(self.index) {
+                Some(suffix_index) if self.index > 0 => (
+                    self.lcp[self.index].as_(),
+                    self.text.len() - suffix_index.as_(),
+                ),
+                Some(suffix_index) => (0, self.text.len() - suffix_index.as_()),
+                None => (0, 0),
+            };
+            let max_open_k = lcp_value.min(self.max_k);
+
+            self.end_groups(max_open_k);
+
+            let max_k = max_k.min(self.max_k);
+            if max_k > max_open_k {
+                self.open_groups.push((self.index, max_k));
+            }
+
+            self.index += 1;
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubstringProfile {
+    // the number of distinct substrings of every length, index 0 counts the empty string
+    distinct_per_length: Vec<usize>,
+    total_distinct: u128,
+}
+
+impl SubstringProfile {
+    // the suffix with LCP value l to its predecessor adds the distinct substrings of the lengths
+    // l + 1 up to its own length, which is recorded in a difference array
+    pub fn new<I: IndexStorage>(suffix_array: &[I], lcp: &[I]) -> Self {
+        assert_eq!(suffix_array.len(), lcp.len());
+
+        let len = suffix_array.len();
+        let mut differences = vec![0isize; len + 2];
+        let mut total_distinct = 0;
+
+        for (index, suffix_index) in suffix_array.iter().enumerate() {
+            let suffix_len = len - suffix_index.as_();
+            let lcp_value = if index == 0 { 0 } else { lcp[index].as_() };
+
+            differences[lcp_value + 1] += 1;
+            differences[suffix_len + 1] -= 1;
+            total_distinct += (suffix_len - lcp_value) as u128;
+        }
+
+        let mut distinct_per_length = Vec::with_capacity(len + 1);
+        distinct_per_length.push(1);
+
+        let mut distinct = 0;
+        for difference in &differences[1..=len] {
+            distinct += difference;
+            distinct_per_length.push(distinct as usize);
+        }
+
+        Self {
+            distinct_per_length,
+            total_distinct,
+        }
+    }
+
+    // the number of distinct non-empty substrings. It can exceed the range of usize for long
+    // texts, because it grows quadratically with the text length.
+    pub fn total_distinct_substrings(&self) -> u128 {
+        self.total_distinct
+    }
+
+    pub fn distinct_substrings_of_len(&self, len: usize) -> usize {
+        self.distinct_per_length.get(len).copied().unwrap_or(0)
+    }
+
+    // the substring complexity function, the number of distinct substrings of every length up to
+    // the text length
+    pub fn distinct_per_length(&self) -> &[usize] {
+        &self.distinct_per_length
+    }
+
+    // the repetitiveness measure δ = max d(l) / l of Kociumaka, Navarro and Prezza, where d(l) is
+    // the number of distinct substrings of length l. It is 0 for the empty text.
+    pub fn substring_complexity(&self) -> f64 {
+        self.distinct_per_length
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(len, &distinct)| distinct as f64 / len as f64)
+            .fold(0.0, f64::max)
+    }
+}
@@ -0,0 +1,399 @@
+// A compact suffix tree built from the suffix array and the LCP array in linear time.
+//
+// The texts of this crate have no sentinel, so the tree is the one of the text with a unique
+// smallest terminator, where the terminator is removed from the edge labels. Every suffix has its
+// own leaf, and the leaves of suffixes that are prefixes of other suffixes hang at an edge of
+// length 0. The empty suffix has no leaf, so the root can have a single child.
+//
+// Nodes are identified by their index into the flat node arrays. The leaves come first, the leaf
+// of suffix array entry i has the id i, followed by the root and the internal nodes.
+
+use std::{iter, ops::Range};
+
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage, SaisBuilder, lcp};
+
+pub struct SuffixTree<'a, C, I> {
+    text: &'a [C],
+    suffix_array: Vec<I>,
+    string_depths: Vec<I>,
+    parents: Vec<I>,
+    next_siblings: Vec<I>,
+    // the children of the internal nodes in the order of the first characters of their edge
+    // labels. The ones of node u are at children_starts[u - root]..children_starts[u - root + 1],
+    // such that a child can be found by binary search
+    children: Vec<I>,
+    children_starts: Vec<I>,
+    suffix_links: Vec<I>,
+    // the leaves below a node are a range of suffix array entries
+    leaf_range_starts: Vec<I>,
+    leaf_range_ends: Vec<I>,
+}
+
+impl<'a, C: Character, I: IndexStorage> SuffixTree<'a, C, I> {
+    pub fn construct(text: &'a [C], builder: &SaisBuilder<C, I>) -> Self {
+        let suffix_array = builder.construct_suffix_array(text);
+        let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+        Self::new(text, suffix_array, &lcp)
+    }
+
+    // the node ids have to fit into I, so the text can have at most I::max_value() / 2 characters
+    pub fn new(text: &'a [C], suffix_array: Vec<I>, lcp: &[I]) -> Self {
+        assert_eq!(text.len(), suffix_array.len());
+        assert_eq!(text.len(), lcp.len());
+        assert!(
+            text.len()
+                .checked_mul(2)
+                .is_some_and(|max_num_nodes| max_num_nodes <= I::max_value().as_()),
+            "the text is too long for the node ids of the index type"
+        );
+
+        let len = text.len();
+
+        let mut tree = Self {
+            text,
+            suffix_array,
+            string_depths: Vec::with_capacity(2 * len),
+            parents: Vec::with_capacity(2 * len),
+            next_siblings: Vec::with_capacity(2 * len),
+            children: Vec::new(),
+            children_starts: Vec::new(),
+            suffix_links: Vec::with_capacity(2 * len),
+            leaf_range_starts: Vec::with_capacity(2 * len),
+            leaf_range_ends: Vec::with_capacity(2 * len),
+        };
+
+        for leaf in 0..len {
+            let suffix_len = len - tree.suffix_array[leaf].as_();
+            tree.push_node(suffix_len, leaf, leaf + 1);
+        }
+
+        tree.push_node(0, 0, len);
+
+        tree.construct_topology(lcp);
+        tree.construct_suffix_links();
+
+        tree
+    }
+
+    pub fn text(&self) -> &'a [C] {
+        self.text
+    }
+
+    pub fn suffix_array(&self) -> &[I] {
+        &self.suffix_array
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.string_depths.len()
+    }
+
+    pub fn num_leaves(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn root(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_leaf(&self, node: usize) -> bool {
+        node < self.text.len()
+    }
+
+    pub fn is_root(&self, node: usize) -> bool {
+        node == self.root()
+    }
+
+    // the children in the order of the first characters of their edge labels
+    pub fn children(&self, node: usize) -> Children<'_, 'a, C, I> {
+        Children {
+            tree: self,
+            next_child: self.first_child(node),
+        }
+    }
+
+    // the child whose edge label starts with the given character, found by binary search in
+    // O(log σ) time
+    pub fn child_with_char(&self, node: usize, char: C) -> Option<usize> {
+        let children = self.children_slice(node);
+
+        // a leaf with an edge label of length 0 comes first and has no first character
+        let first_char = |child: &I| {
+            let (label_start, label_len) = self.edge_label(child.as_());
+            (label_len > 0).then(|| self.text[label_start])
+        };
+
+        let num_smaller_children = children.partition_point(|child| first_char(child) < Some(char));
+
+        children
+            .get(num_smaller_children)
+            .filter(|child| first_char(child) == Some(char))
+            .map(|child| child.as_())
+    }
+
+    pub fn parent(&self, node: usize) -> Option<usize> {
+        option_node(self.parents[node])
+    }
+
+    // the node whose path label is the one of this node without its first character. The
+    // suffix link of the leaf of the last suffix is the root, the root has no suffix link.
+    pub fn suffix_link(&self, node: usize) -> Option<usize> {
+        option_node(self.suffix_links[node])
+    }
+
+    // the length of the path label
+    pub fn string_depth(&self, node: usize) -> usize {
+        self.string_depths[node].as_()
+    }
+
+    // (start, len) of the label of the edge from the parent to this node in the text
+    pub fn edge_label(&self, node: usize) -> (usize, usize) {
+        let parent_depth = self
+            .parent(node)
+            .map_or(0, |parent| self.string_depth(parent));
+
+        (
+            self.first_suffix(node) + parent_depth,
+            self.string_depth(node) - parent_depth,
+        )
+    }
+
+    // the concatenated edge labels from the root to this node
+    pub fn path_label(&self, node: usize) -> &'a [C] {
+        let start = self.first_suffix(node);
+        &self.text[start..start + self.string_depth(node)]
+    }
+
+    // the leaves below this node, which are the suffix array entries with this path label as a
+    // prefix of their suffixes
+    pub fn leaf_range(&self, node: usize) -> Range<usize> {
+        self.leaf_range_starts[node].as_()..self.leaf_range_ends[node].as_()
+    }
+
+    // the text position of the suffix of a leaf
+    pub fn suffix_index(&self, leaf: usize) -> usize {
+        assert!(self.is_leaf(leaf));
+        self.suffix_array[leaf].as_()
+    }
+
+    // the nodes of the subtree below the given node in pre-order, children in the order of
+    // their edge labels. The traversal needs no extra memory.
+    pub fn dfs(&self, node: usize) -> Dfs<'_, 'a, C, I> {
+        Dfs {
+            tree: self,
+            start_node: node,
+            next_node: Some(node),
+        }
+    }
+
+    fn children_slice(&self, node: usize) -> &[I] {
+        if self.is_leaf(node) {
+            return &[];
+        }
+
+        let internal_node = node - self.root();
+
+        &self.children[self.children_starts[internal_node].as_()
+            ..self.children_starts[internal_node + 1].as_()]
+    }
+
+    fn first_child(&self, node: usize) -> Option<usize> {
+        self.children_slice(node).first().map(|child| child.as_())
+    }
+
+    fn first_suffix(&self, node: usize) -> usize {
+        let first_leaf: usize = self.leaf_range_starts[node].as_();
+
+        self.suffix_array
+            .get(first_leaf)
+            .map_or(0, |suffix_index| suffix_index.as_())
+    }
+
+    fn push_node(&mut self, string_depth: usize, leaf_range_start: usize, leaf_range_end: usize) {
+        self.string_depths.push(to_index(string_depth));
+        self.parents.push(I::max_value());
+        self.next_siblings.push(I::max_value());
+        self.suffix_links.push(I::max_value());
+        self.leaf_range_starts.push(to_index(leaf_range_start));
+        self.leaf_range_ends.push(to_index(leaf_range_end));
+    }
+
+    // the leaves are added in suffix array order to the rightmost path of the tree, which is kept
+    // on a stack. The LCP value of a leaf and its predecessor determines where the path branches.
+    // Nodes are attached to their parents when they leave the path, so the children of every
+    // node are attached in order. They are linked as siblings and then stored contiguously.
+    fn construct_topology(&mut self, lcp: &[I]) {
+        let len = self.text.len();
+        let root = self.root();
+
+        let mut first_children = vec![I::max_value(); len + 1];
+        let mut last_children = vec![I::max_value(); len + 1];
+        let mut stack = vec![root];
+
+        // the first leaf and the final closing of the path branch at the root
+        let lcp_values = iter::once(0)
+            .chain(lcp.iter().skip(1).map(|lcp_value| lcp_value.as_()))
+            .chain(iter::once(0));
+
+        for (leaf, lcp_value) in (0..=len).zip(lcp_values) {
+            loop {
+                let top = *stack.last().unwrap();
+
+                // a leaf whose suffix ends at the branching depth is still closed
+                if !self.is_leaf(top) && self.string_depth(top) <= lcp_value {
+                    break;
+                }
+
+                stack.pop();
+                let below = *stack.last().unwrap();
+
+                if self.string_depth(below) >= lcp_value {
+                    self.attach(below, top, &mut first_children, &mut last_children);
+                } else {
+                    let node = self.num_nodes();
+                    self.push_node(lcp_value, self.leaf_range_starts[top].as_(), 0);
+                    first_children.push(I::max_value());
+                    last_children.push(I::max_value());
+
+                    self.attach(node, top, &mut first_children, &mut last_children);
+                    stack.push(node);
+                }
+            }
+
+            if leaf < len {
+                stack.push(leaf);
+            }
+        }
+
+        let num_internal_nodes = self.num_nodes() - root;
+        self.children = Vec::with_capacity(self.num_nodes() - 1);
+        self.children_starts = Vec::with_capacity(num_internal_nodes + 1);
+
+        for &first_child in &first_children[root..] {
+            self.children_starts.push(to_index(self.children.len()));
+
+            let mut maybe_child = option_node(first_child);
+            while let Some(child) = maybe_child {
+                self.children.push(to_index(child));
+                maybe_child = option_node(self.next_siblings[child]);
+            }
+        }
+
+        self.children_starts.push(to_index(self.children.len()));
+    }
+
+    fn attach(
+        &mut self,
+        parent: usize,
+        child: usize,
+        first_children: &mut [I],
+        last_children: &mut [I],
+    ) {
+        self.parents[child] = to_index(parent);
+
+        match option_node(last_children[parent]) {
+            Some(last_child) => self.next_siblings[last_child] = to_index(child),
+            None => first_children[parent] = to_index(child),
+        }
+
+        last_children[parent] = to_index(child);
+        self.leaf_range_ends[parent] = self.leaf_range_ends[child];
+    }
+
+    // The suffix link of an internal node u is the ancestor with string depth depth(u) - 1 of the
+    // leaf that follows the first leaf of u in text order. The nodes with the same first leaf form
+    // a path upwards from that leaf, and their targets are found by a single walk up from the
+    // following leaf. Like in McCreight's algorithm, the node depth of the leaves in text order
+    // decreases by at most one per step, so all walks together take linear time.
+    fn construct_suffix_links(&mut self) {
+        let len = self.text.len();
+        let root = self.root();
+
+        let mut inverse_suffix_array = vec![0; len];
+        for (leaf, suffix_index) in self.suffix_array.iter().enumerate() {
+            inverse_suffix_array[suffix_index.as_()] = leaf;
+        }
+
+        for suffix_index in 0..len {
+            let leaf = inverse_suffix_array[suffix_index];
+            let mut target = inverse_suffix_array
+                .get(suffix_index + 1)
+                .copied()
+                .unwrap_or(root);
+
+            self.suffix_links[leaf] = to_index(target);
+
+            let mut node = leaf;
+
+            while let Some(parent) = self.parent(node)
+                && !self.is_root(parent)
+                && self.first_child(parent) == Some(node)
+            {
+                node = parent;
+
+                // a leaf at the target depth hangs at an edge of length 0 below the target
+                while self.is_leaf(target) || self.string_depth(target) >= self.string_depth(node) {
+                    target = self.parent(target).unwrap();
+                }
+
+                self.suffix_links[node] = to_index(target);
+            }
+        }
+    }
+}
+
+fn option_node<I: IndexStorage>(value: I) -> Option<usize> {
+    (value != I::max_value()).then(|| value.as_())
+}
+
+fn to_index<I: IndexStorage>(value: usize) -> I {
+    <I as NumCast>::from(value).unwrap()
+}
+
+pub struct Children<'t, 'a, C, I> {
+    tree: &'t SuffixTree<'a, C, I>,
+    next_child: Option<usize>,
+}
+
+impl<C: Character, I: IndexStorage> Iterator for Children<'_, '_, C, I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let child = self.next_child?;
+        self.next_child = option_node(self.tree.next_siblings[child]);
+
+        Some(child)
+    }
+}
+
+pub struct Dfs<'t, 'a, C, I> {
+    tree: &'t SuffixTree<'a, C, I>,
+    start_node: usize,
+    next_node: Option<usize>,
+}
+
+impl<C: Character, I: IndexStorage> Iterator for Dfs<'_, '_, C, I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let node = self.next_node?;
+
+        self.next_node = self.tree.first_child(node).or_else(|| {
+            let mut ancestor = node;
+
+            while ancestor != self.start_node {
+                if let Some(sibling) = option_node(self.tree.next_siblings[ancestor]) {
+                    return Some(sibling);
+                }
+
+                ancestor = self.tree.parent(ancestor).unwrap();
+            }
+
+            None
+        });
+
+        Some(node)
+    }
+}
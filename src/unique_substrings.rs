@@ -0,0 +1,93 @@
+// Shortest unique substrings, e.g. for the design of probes that hybridize at a single position.
+// A substring is unique if it occurs exactly once in the text. The minimal unique substring
+// starting at i is one character longer than the longest common prefix of the suffix at i with
+// any other suffix, which is the larger LCP value with its neighbors in the suffix array.
+
+use std::{collections::VecDeque, ops::Range};
+
+use crate::IndexStorage;
+
+// for every start position, the length of the shortest unique substring that starts there. It is
+// None if already the whole suffix occurs more than once.
+pub fn minimal_unique_lengths<I: IndexStorage>(
+    suffix_array: &[I],
+    lcp: &[I],
+) -> Vec<Option<usize>> {
+    assert_eq!(suffix_array.len(), lcp.len());
+
+    let len = suffix_array.len();
+    let mut lengths = vec![None; len];
+
+    for (index, suffix_index) in suffix_array.iter().enumerate() {
+        let suffix_index: usize = suffix_index.as_();
+        let lcp_before = if index == 0 { 0 } else { lcp[index].as_() };
+        let lcp_after = lcp.get(index + 1).map_or(0, |lcp_value| lcp_value.as_());
+        let unique_len = lcp_before.max(lcp_after) + 1;
+
+        if suffix_index + unique_len <= len {
+            lengths[suffix_index] = Some(unique_len);
+        }
+    }
+
+    lengths
+}
+
+// for every position, the shortest unique substring that contains it, the leftmost one if there
+// are several. The whole text is unique, so every position of a non-empty text has one.
+//
+// The ends of the minimal unique substrings never decrease from one start position to the next,
+// and the positions without one are at the end of the text. For a position p, this gives two
+// candidates: the shortest minimal unique substring that contains p, from a sliding window over
+// the starts whose minimal unique substring ends after p, and the extension up to p of the one
+// that starts right before this window.
+pub fn shortest_unique_substrings<I: IndexStorage>(
+    suffix_array: &[I],
+    lcp: &[I],
+) -> Vec<Range<usize>> {
+    let unique_lengths = minimal_unique_lengths(suffix_array, lcp);
+    let num_starts = unique_lengths.partition_point(Option::is_some);
+    let end = |start: usize| start + unique_lengths[start].unwrap();
+
+    let mut shortest = Vec::with_capacity(unique_lengths.len());
+    // starts of the window, with increasing lengths of their minimal unique substrings
+    let mut window = VecDeque::new();
+    let mut window_start = 0;
+
+    for position in 0..unique_lengths.len() {
+        if position < num_starts {
+            let unique_len = unique_lengths[position];
+
+            while window
+                .back()
+                .is_some_and(|&start| unique_lengths[start] > unique_len)
+            {
+                window.pop_back();
+            }
+
+            window.push_back(position);
+        }
+
+        while window_start < num_starts && end(window_start) <= position {
+            window_start += 1;
+        }
+
+        while window.front().is_some_and(|&start| start < window_start) {
+            window.pop_front();
+        }
+
+        // the extension starts further left, so it wins ties
+        let extension = window_start.checked_sub(1).map(|start| start..position + 1);
+        let window_minimum = window.front().map(|&start| start..end(start));
+
+        shortest.push(match (extension, window_minimum) {
+            (Some(extension), Some(window_minimum)) if window_minimum.len() < extension.len() => {
+                window_minimum
+            }
+            (Some(extension), _) => extension,
+            (None, Some(window_minimum)) => window_minimum,
+            (None, None) => unreachable!("the whole text is unique"),
+        });
+    }
+
+    shortest
+}
@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use bitvec::vec::BitVec;
+use num_traits::NumCast;
+
+use crate::{Character, IndexStorage};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationError {
+    LengthMismatch {
+        text_len: usize,
+        suffix_array_len: usize,
+    },
+    IndexOutOfBounds {
+        position: usize,
+        suffix_index: usize,
+    },
+    DuplicateIndex {
+        position: usize,
+        suffix_index: usize,
+    },
+    // the suffix at this position is smaller than the one at the previous position
+    WrongOrder {
+        position: usize,
+    },
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::LengthMismatch {
+                text_len,
+                suffix_array_len,
+            } => write!(
+                f,
+                "the suffix array has {suffix_array_len} entries, but the text has length {text_len}"
+            ),
+            VerificationError::IndexOutOfBounds {
+                position,
+                suffix_index,
+            } => write!(
+                f,
+                "the suffix array contains the out of bounds index {suffix_index} at position {position}"
+            ),
+            VerificationError::DuplicateIndex {
+                position,
+                suffix_index,
+            } => write!(
+                f,
+                "the suffix array contains the index {suffix_index} a second time at position {position}"
+            ),
+            VerificationError::WrongOrder { position } => write!(
+                f,
+                "the suffixes at positions {} and {position} of the suffix array are in the wrong order",
+                position - 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+// linear time check of Burkhardt and Kärkkäinen: a permutation of the suffixes is sorted if and
+// only if every pair of neighbors is ordered by their first character, and neighbors with the
+// same first character are ordered like the suffixes that follow them. The error refers to the
+// first position at which a violation is found.
+pub fn verify_suffix_array<C: Character, I: IndexStorage>(
+    text: &[C],
+    suffix_array: &[I],
+) -> Result<(), VerificationError> {
+    if text.len() != suffix_array.len() {
+        return Err(VerificationError::LengthMismatch {
+            text_len: text.len(),
+            suffix_array_len: suffix_array.len(),
+        });
+    }
+
+    let mut inverse_suffix_array = vec![I::zero(); text.len()];
+    let mut seen: BitVec = BitVec::repeat(false, text.len());
+
+    for (position, &suffix_index) in suffix_array.iter().enumerate() {
+        let suffix_index: usize = suffix_index.as_();
+
+        if suffix_index >= text.len() {
+            return Err(VerificationError::IndexOutOfBounds {
+                position,
+                suffix_index,
+            });
+        }
+
+        if seen[suffix_index] {
+            return Err(VerificationError::DuplicateIndex {
+                position,
+                suffix_index,
+            });
+        }
+
+        seen.set(suffix_index, true);
+        inverse_suffix_array[suffix_index] = <I as NumCast>::from(position).unwrap();
+    }
+
+    for (position, suffix_indices) in suffix_array.windows(2).enumerate() {
+        let previous_suffix_index: usize = suffix_indices[0].as_();
+        let suffix_index: usize = suffix_indices[1].as_();
+
+        let is_ordered = match text[previous_suffix_index].cmp(&text[suffix_index]) {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            // the empty suffix after the last character is the smallest one
+            Ordering::Equal if previous_suffix_index + 1 == text.len() => true,
+            Ordering::Equal if suffix_index + 1 == text.len() => false,
+            Ordering::Equal => {
+                inverse_suffix_array[previous_suffix_index + 1]
+                    < inverse_suffix_array[suffix_index + 1]
+            }
+        };
+
+        if !is_ordered {
+            return Err(VerificationError::WrongOrder {
+                position: position + 1,
+            });
+        }
+    }
+
+    Ok(())
+}
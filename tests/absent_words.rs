@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+
+use sais_drum::absent_words::{self, MinimalAbsentWord};
+use sais_drum::enhanced_suffix_array::EnhancedSuffixArray;
+use sais_drum::{Character, SaisBuilder, lcp};
+
+fn occurs(text: &[u8], word: &[u8]) -> bool {
+    word.is_empty() || text.windows(word.len()).any(|window| window == word)
+}
+
+fn computed_absent_words(text: &[u8]) -> Vec<Vec<u8>> {
+    let builder = SaisBuilder::<u8, u32>::new();
+    let enhanced_suffix_array = EnhancedSuffixArray::construct(text, &builder);
+
+    let absent_words = absent_words::minimal_absent_words(&enhanced_suffix_array);
+    let words: Vec<_> = absent_words
+        .iter()
+        .map(|absent_word| {
+            let word = absent_word.to_word(text);
+            assert_eq!(word.len(), absent_word.word_len());
+            if absent_word.last.is_some() {
+                assert_eq!(&word[1..word.len() - 1], &text[absent_word.middle.clone()]);
+            }
+            word
+        })
+        .collect();
+
+    assert_eq!(words.iter().collect::<BTreeSet<_>>().len(), words.len());
+    words
+}
+
+fn test_absent_words(text: &[u8]) {
+    let mut words = computed_absent_words(text);
+    words.sort();
+
+    let chars: BTreeSet<u8> = text.iter().copied().collect();
+    let mut middles: BTreeSet<&[u8]> = BTreeSet::new();
+    middles.insert(&[]);
+    for start in 0..text.len() {
+        for end in start + 1..=text.len() {
+            middles.insert(&text[start..end]);
+        }
+    }
+
+    let mut expected: Vec<Vec<u8>> = (0..=u8::MAX)
+        .filter(|char| !chars.contains(char))
+        .map(|char| vec![char])
+        .collect();
+
+    for middle in middles {
+        for &first in &chars {
+            for &last in &chars {
+                let mut word = vec![first];
+                word.extend_from_slice(middle);
+                word.push(last);
+
+                let is_minimal_absent = !occurs(text, &word)
+                    && occurs(text, &word[..word.len() - 1])
+                    && occurs(text, &word[1..]);
+
+                if is_minimal_absent {
+                    expected.push(word);
+                }
+            }
+        }
+    }
+
+    expected.sort();
+    assert_eq!(words, expected);
+}
+
+#[test]
+fn example_absent_words() {
+    let text = b"abaab";
+    let builder = SaisBuilder::<u8, u32>::new();
+    let enhanced_suffix_array = EnhancedSuffixArray::construct(text, &builder);
+    let absent_words = absent_words::minimal_absent_words(&enhanced_suffix_array);
+
+    assert_eq!(absent_words.len(), 256 - 2 + 4);
+    assert!(absent_words.contains(&MinimalAbsentWord {
+        first: b'c',
+        middle: 0..0,
+        last: None,
+    }));
+
+    let mut longer_words: Vec<_> = absent_words
+        .iter()
+        .filter(|absent_word| absent_word.last.is_some())
+        .map(|absent_word| absent_word.to_word(text))
+        .collect();
+    longer_words.sort();
+
+    assert_eq!(longer_words, [&b"aaa"[..], b"aaba", b"bab", b"bb"]);
+
+    test_absent_words(text);
+}
+
+#[test]
+fn tiny_texts() {
+    assert_eq!(computed_absent_words(b"").len(), 256);
+
+    test_absent_words(b"");
+    test_absent_words(b"a");
+    test_absent_words(b"aaaaaa");
+    test_absent_words(b"ab");
+}
+
+// a character type that does not implement from_rank
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Nucleotide(u8);
+
+impl Character for Nucleotide {
+    fn max_char() -> Self {
+        Nucleotide(3)
+    }
+
+    fn rank(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[test]
+fn length_1_words_are_skipped_without_from_rank() {
+    let u8_text = b"\x00\x01\x00\x00\x01";
+    let text: Vec<_> = u8_text.iter().map(|&char| Nucleotide(char)).collect();
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(u8_text);
+    let lcp = lcp::construct_lcp_array(u8_text, &suffix_array);
+
+    let enhanced_suffix_array = EnhancedSuffixArray::new(&text, suffix_array, lcp);
+    let mut words: Vec<_> = absent_words::minimal_absent_words(&enhanced_suffix_array)
+        .iter()
+        .map(|absent_word| absent_word.to_word(&text))
+        .collect();
+    words.sort();
+
+    let mut expected: Vec<_> = computed_absent_words(u8_text)
+        .into_iter()
+        .filter(|word| word.len() > 1)
+        .map(|word| word.into_iter().map(Nucleotide).collect::<Vec<_>>())
+        .collect();
+    expected.sort();
+
+    assert_eq!(words, expected);
+}
+
+#[test]
+#[should_panic(expected = "the alphabet of the character type is too large")]
+fn large_alphabet() {
+    let text = [1u32, 2];
+    let enhanced_suffix_array = EnhancedSuffixArray::new(&text, vec![0u32, 1], vec![0u32, 0]);
+    absent_words::minimal_absent_words(&enhanced_suffix_array);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..40)) {
+        test_absent_words(&text);
+    }
+
+    #[test]
+    fn random_binary_texts(text in prop::collection::vec(0u8..2, 0..40)) {
+        test_absent_words(&text);
+    }
+}
@@ -0,0 +1,238 @@
+#![cfg(feature = "capi")]
+
+use proptest::prelude::*;
+
+// makes sure that the exported functions are linked
+use sais_drum as _;
+use sais_drum::{SaisBuilder, bwt};
+
+// the declarations of include/sais_drum.h
+unsafe extern "C" {
+    fn libsais(T: *const u8, SA: *mut i32, n: i32, fs: i32, freq: *mut i32) -> i32;
+    fn libsais16(T: *const u16, SA: *mut i32, n: i32, fs: i32, freq: *mut i32) -> i32;
+    fn libsais64(T: *const u8, SA: *mut i64, n: i64, fs: i64, freq: *mut i64) -> i64;
+    fn libsais_bwt(T: *const u8, U: *mut u8, A: *mut i32, n: i32, fs: i32, freq: *mut i32) -> i32;
+    fn libsais16_bwt(
+        T: *const u16,
+        U: *mut u16,
+        A: *mut i32,
+        n: i32,
+        fs: i32,
+        freq: *mut i32,
+    ) -> i32;
+    fn libsais64_bwt(T: *const u8, U: *mut u8, A: *mut i64, n: i64, fs: i64, freq: *mut i64)
+    -> i64;
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/sais_drum.h"));
+    let committed = include_str!("../include/sais_drum.h");
+
+    assert_eq!(
+        committed.replace("\r\n", "\n"),
+        generated.replace("\r\n", "\n"),
+        "include/sais_drum.h is outdated, copy it from {}",
+        env!("OUT_DIR")
+    );
+}
+
+#[test]
+fn invalid_arguments() {
+    let text = b"banana";
+    let mut suffix_array = [0i32; 6];
+    let mut bwt = [0u8; 6];
+
+    unsafe {
+        assert_eq!(
+            libsais(
+                text.as_ptr(),
+                suffix_array.as_mut_ptr(),
+                -1,
+                0,
+                std::ptr::null_mut()
+            ),
+            -1
+        );
+        assert_eq!(
+            libsais(
+                text.as_ptr(),
+                suffix_array.as_mut_ptr(),
+                6,
+                -1,
+                std::ptr::null_mut()
+            ),
+            -1
+        );
+        assert_eq!(
+            libsais(
+                std::ptr::null(),
+                suffix_array.as_mut_ptr(),
+                6,
+                0,
+                std::ptr::null_mut()
+            ),
+            -1
+        );
+        assert_eq!(
+            libsais_bwt(
+                text.as_ptr(),
+                bwt.as_mut_ptr(),
+                std::ptr::null_mut(),
+                6,
+                0,
+                std::ptr::null_mut()
+            ),
+            -1
+        );
+    }
+}
+
+#[test]
+fn frequencies() {
+    let text = b"banana";
+    let mut suffix_array = [0i32; 6];
+    let mut freq = [42i32; 256];
+
+    let result = unsafe {
+        libsais(
+            text.as_ptr(),
+            suffix_array.as_mut_ptr(),
+            6,
+            0,
+            freq.as_mut_ptr(),
+        )
+    };
+
+    assert_eq!(result, 0);
+    assert_eq!(suffix_array, [5, 3, 1, 0, 4, 2]);
+    assert_eq!(freq[b'a' as usize], 3);
+    assert_eq!(freq[b'b' as usize], 1);
+    assert_eq!(freq[b'n' as usize], 2);
+    assert_eq!(freq.iter().sum::<i32>(), 6);
+}
+
+#[test]
+fn bwt_in_place() {
+    let mut text = *b"mississippi";
+    let mut temp = [0i32; 11];
+
+    let text_ptr = text.as_mut_ptr();
+    let primary_index = unsafe {
+        libsais_bwt(
+            text_ptr,
+            text_ptr,
+            temp.as_mut_ptr(),
+            11,
+            0,
+            std::ptr::null_mut(),
+        )
+    };
+
+    assert_eq!(primary_index, 5);
+    assert_eq!(&text, b"ipssmpissii");
+}
+
+#[test]
+fn tiny_texts() {
+    let mut bwt = [0u8; 1];
+    let mut temp = [0i32; 1];
+
+    unsafe {
+        assert_eq!(
+            libsais_bwt(
+                b"".as_ptr(),
+                bwt.as_mut_ptr(),
+                temp.as_mut_ptr(),
+                0,
+                0,
+                std::ptr::null_mut()
+            ),
+            0
+        );
+        assert_eq!(
+            libsais_bwt(
+                b"x".as_ptr(),
+                bwt.as_mut_ptr(),
+                temp.as_mut_ptr(),
+                1,
+                0,
+                std::ptr::null_mut()
+            ),
+            1
+        );
+    }
+
+    assert_eq!(bwt, *b"x");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn suffix_arrays(text in prop::collection::vec(any::<u8>(), 0..2000), fs in 0usize..2000) {
+        let expected = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+        let n = text.len();
+
+        let mut suffix_array = vec![0i32; n + fs];
+        let result = unsafe {
+            libsais(text.as_ptr(), suffix_array.as_mut_ptr(), n as i32, fs as i32, std::ptr::null_mut())
+        };
+        prop_assert_eq!(result, 0);
+        prop_assert!(suffix_array[..n].iter().map(|&value| value as u32).eq(expected.iter().copied()));
+
+        let mut suffix_array = vec![0i64; n + fs];
+        let result = unsafe {
+            libsais64(text.as_ptr(), suffix_array.as_mut_ptr(), n as i64, fs as i64, std::ptr::null_mut())
+        };
+        prop_assert_eq!(result, 0);
+        prop_assert!(suffix_array[..n].iter().map(|&value| value as u32).eq(expected.iter().copied()));
+    }
+
+    #[test]
+    fn u16_suffix_arrays(text in prop::collection::vec(0u16..1000, 0..2000)) {
+        let expected = SaisBuilder::<u16, u32>::new().construct_suffix_array(&text);
+        let n = text.len();
+
+        let mut suffix_array = vec![0i32; n];
+        let mut freq = vec![0i32; 1 << 16];
+        let result = unsafe {
+            libsais16(text.as_ptr(), suffix_array.as_mut_ptr(), n as i32, 0, freq.as_mut_ptr())
+        };
+        prop_assert_eq!(result, 0);
+        prop_assert!(suffix_array.iter().map(|&value| value as u32).eq(expected.iter().copied()));
+        prop_assert_eq!(freq.iter().sum::<i32>(), n as i32);
+    }
+
+    #[test]
+    fn bwts(text in prop::collection::vec(0u8..4, 0..2000), fs in 0usize..100) {
+        let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+        let (expected_bwt, expected_primary_index) = bwt::construct_bwt(&text, &suffix_array);
+        let n = text.len();
+
+        let mut bwt = vec![0u8; n];
+        let mut temp = vec![0i32; n + fs];
+        let primary_index = unsafe {
+            libsais_bwt(text.as_ptr(), bwt.as_mut_ptr(), temp.as_mut_ptr(), n as i32, fs as i32, std::ptr::null_mut())
+        };
+        prop_assert_eq!(primary_index as usize, expected_primary_index);
+        prop_assert_eq!(&bwt, &expected_bwt);
+
+        let mut bwt = vec![0u8; n];
+        let mut temp = vec![0i64; n + fs];
+        let primary_index = unsafe {
+            libsais64_bwt(text.as_ptr(), bwt.as_mut_ptr(), temp.as_mut_ptr(), n as i64, fs as i64, std::ptr::null_mut())
+        };
+        prop_assert_eq!(primary_index as usize, expected_primary_index);
+        prop_assert_eq!(&bwt, &expected_bwt);
+
+        let wide_text: Vec<u16> = text.iter().map(|&char| char as u16 * 1000).collect();
+        let mut bwt = vec![0u16; n];
+        let mut temp = vec![0i32; n + fs];
+        let primary_index = unsafe {
+            libsais16_bwt(wide_text.as_ptr(), bwt.as_mut_ptr(), temp.as_mut_ptr(), n as i32, fs as i32, std::ptr::null_mut())
+        };
+        prop_assert_eq!(primary_index as usize, expected_primary_index);
+        prop_assert!(bwt.iter().map(|&char| (char / 1000) as u8).eq(expected_bwt.iter().copied()));
+    }
+}
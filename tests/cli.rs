@@ -0,0 +1,134 @@
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use sais_drum::format::SuffixArrayFile;
+
+fn run_cli(arguments: &[&str], working_dir: &Path) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_sais-drum"))
+        .args(arguments)
+        .current_dir(working_dir)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("peak heap memory"), "{stderr}");
+
+    output
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn build_verify_and_search() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let text = b"mississippi";
+    std::fs::write(temp_dir.path().join("text"), text).unwrap();
+
+    let output = run_cli(&["build", "text", "sa", "--lcp", "--bwt"], temp_dir.path());
+    stdout(&output);
+
+    // small texts get 16 bit indices
+    let file = SuffixArrayFile::<u8, u16>::read_from_file(temp_dir.path().join("sa")).unwrap();
+    assert_eq!(file.suffix_array(), [10, 7, 4, 1, 0, 9, 8, 6, 3, 5, 2]);
+    assert_eq!(file.lcp().unwrap(), [0, 1, 1, 4, 0, 0, 1, 0, 2, 1, 3]);
+    assert_eq!(file.bwt().unwrap(), b"ipssmpissii");
+    assert_eq!(file.header().bwt_primary_index, 5);
+
+    let output = run_cli(&["verify", "text", "sa"], temp_dir.path());
+    assert_eq!(stdout(&output), "the suffix array is valid\n");
+
+    let output = run_cli(
+        &["search", "text", "sa", "ssi", "x", "--locate"],
+        temp_dir.path(),
+    );
+    assert_eq!(stdout(&output), "ssi\t2\t2\t5\nx\t0\n");
+
+    let output = run_cli(&["search", "text", "sa", "i"], temp_dir.path());
+    assert_eq!(stdout(&output), "i\t4\n");
+}
+
+#[test]
+fn explicit_index_width() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("text"), b"abracadabra").unwrap();
+
+    let output = run_cli(
+        &["build", "text", "sa", "--index-width", "64"],
+        temp_dir.path(),
+    );
+    stdout(&output);
+
+    let file = SuffixArrayFile::<u8, u64>::read_from_file(temp_dir.path().join("sa")).unwrap();
+    assert_eq!(file.header().index_width, 8);
+
+    let output = run_cli(&["verify", "text", "sa"], temp_dir.path());
+    stdout(&output);
+}
+
+#[test]
+fn index_width_too_small_for_text() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("text"), vec![b'a'; u16::MAX as usize]).unwrap();
+
+    let output = run_cli(
+        &["build", "text", "sa", "--index-width", "16"],
+        temp_dir.path(),
+    );
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("text of 65535 characters does not fit 16-bit indices")
+    );
+    assert!(!temp_dir.path().join("sa").exists());
+
+    let output = run_cli(
+        &["build", "text", "sa", "--index-width", "32"],
+        temp_dir.path(),
+    );
+    stdout(&output);
+}
+
+#[test]
+fn bwt_and_lcp_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("text"), b"banana").unwrap();
+
+    let output = run_cli(&["bwt", "text", "bwt"], temp_dir.path());
+    assert_eq!(stdout(&output), "primary index: 4\n");
+    assert_eq!(
+        std::fs::read(temp_dir.path().join("bwt")).unwrap(),
+        b"annbaa"
+    );
+
+    stdout(&run_cli(&["build", "text", "sa"], temp_dir.path()));
+    let output = run_cli(
+        &["lcp", "text", "lcp", "--suffix-array", "sa"],
+        temp_dir.path(),
+    );
+    stdout(&output);
+
+    let lcp: Vec<_> = std::fs::read(temp_dir.path().join("lcp"))
+        .unwrap()
+        .chunks_exact(2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect();
+    assert_eq!(lcp, [0, 1, 3, 0, 0, 2]);
+}
+
+#[test]
+fn verify_rejects_other_text() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("text"), b"banana").unwrap();
+    std::fs::write(temp_dir.path().join("other_text"), b"bandana").unwrap();
+
+    stdout(&run_cli(&["build", "text", "sa"], temp_dir.path()));
+
+    let output = run_cli(&["verify", "other_text", "sa"], temp_dir.path());
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("different text"));
+}
@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use sais_drum::enhanced_suffix_array::{EnhancedSuffixArray, LcpInterval};
+use sais_drum::{SaisBuilder, search};
+
+// all intervals [i..j] with i < j whose minimum inner LCP value is larger than the LCP values
+// at their boundaries, in O(n²)
+fn naive_lcp_intervals(lcp: &[u32]) -> HashSet<LcpInterval> {
+    let signed_lcp = |index: usize| {
+        if index == 0 || index >= lcp.len() {
+            -1
+        } else {
+            lcp[index] as i64
+        }
+    };
+
+    let mut intervals = HashSet::new();
+
+    for start in 0..lcp.len() {
+        let mut min_lcp = i64::MAX;
+
+        for last in start + 1..lcp.len() {
+            min_lcp = min_lcp.min(signed_lcp(last));
+
+            if signed_lcp(start) < min_lcp && signed_lcp(last + 1) < min_lcp {
+                intervals.insert(LcpInterval {
+                    lcp: min_lcp as usize,
+                    start,
+                    end: last + 1,
+                });
+            }
+        }
+    }
+
+    intervals
+}
+
+fn test_enhanced_suffix_array(text: &[u8]) {
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+    let expected_intervals = naive_lcp_intervals(esa.lcp());
+
+    let mut bottom_up_intervals = HashSet::new();
+    esa.bottom_up_traversal(|interval, children| {
+        assert!(bottom_up_intervals.insert(*interval));
+
+        // children are visited before their parents
+        for child in children.iter().filter(|child| !child.is_leaf()) {
+            assert!(bottom_up_intervals.contains(child));
+        }
+
+        assert_eq!(esa.child_intervals(interval).collect::<Vec<_>>(), children);
+    });
+    assert_eq!(bottom_up_intervals, expected_intervals);
+
+    let mut top_down_intervals = HashSet::new();
+    esa.top_down_traversal(|interval| {
+        assert!(top_down_intervals.insert(*interval));
+
+        let children: Vec<_> = esa.child_intervals(interval).collect();
+        assert!(children.len() >= 2);
+        assert_eq!(children.first().unwrap().start, interval.start);
+        assert_eq!(children.last().unwrap().end, interval.end);
+
+        for (child, next_child) in children.iter().zip(&children[1..]) {
+            assert_eq!(child.end, next_child.start);
+        }
+
+        // every child is found by the character after the common prefix
+        let mut child_chars = HashSet::new();
+        for child in &children {
+            let suffix = &text[esa.suffix_array()[child.start] as usize..];
+            if let Some(&char) = suffix.get(interval.lcp) {
+                assert_eq!(esa.child_interval_with_char(interval, char), Some(*child));
+                child_chars.insert(char);
+            }
+        }
+        for char in (0..=u8::MAX).filter(|char| !child_chars.contains(char)) {
+            assert_eq!(esa.child_interval_with_char(interval, char), None);
+        }
+
+        for child in children {
+            if child.is_leaf() {
+                // without a sentinel, a suffix can end at the depth of its parent
+                let suffix_len = text.len() - esa.suffix_array()[child.start] as usize;
+                assert_eq!(child.lcp, suffix_len);
+                assert!(child.lcp >= interval.lcp);
+            } else {
+                assert!(child.lcp > interval.lcp);
+                assert!(expected_intervals.contains(&child));
+            }
+        }
+
+        true
+    });
+    assert_eq!(top_down_intervals, expected_intervals);
+
+    if let Some(root) = esa.root() {
+        assert_eq!(root.range(), 0..text.len());
+    }
+}
+
+fn test_find_pattern(text: &[u8], pattern: &[u8]) {
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+    let expected_range = search::pattern_range(text, esa.suffix_array(), pattern);
+
+    match esa.find_pattern(pattern) {
+        Some(interval) => {
+            assert_eq!(interval.range(), expected_range);
+            assert!(interval.lcp >= pattern.len());
+        }
+        None => assert!(expected_range.is_empty()),
+    }
+}
+
+#[test]
+fn example_intervals() {
+    // example from Abouelhoda et al.
+    let text = b"acaaacatat";
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+
+    let root = esa.root().unwrap();
+    assert_eq!(root.lcp, 0);
+
+    let children: Vec<_> = esa.child_intervals(&root).collect();
+    assert_eq!(
+        children,
+        [
+            LcpInterval {
+                lcp: 1,
+                start: 0,
+                end: 6
+            },
+            LcpInterval {
+                lcp: 2,
+                start: 6,
+                end: 8
+            },
+            LcpInterval {
+                lcp: 1,
+                start: 8,
+                end: 10
+            },
+        ]
+    );
+
+    assert_eq!(esa.child_interval_with_char(&root, b'c'), Some(children[1]));
+    assert_eq!(esa.child_interval_with_char(&root, b'g'), None);
+    assert_eq!(esa.find_pattern(b"aca").unwrap().range(), 2..4);
+    assert_eq!(esa.find_pattern(b"acg"), None);
+}
+
+#[test]
+fn unary_root() {
+    // all suffixes share the first character, the root is a 1-interval
+    let text = b"aaaa";
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+
+    assert_eq!(
+        esa.root(),
+        Some(LcpInterval {
+            lcp: 1,
+            start: 0,
+            end: 4
+        })
+    );
+    test_enhanced_suffix_array(text);
+}
+
+#[test]
+fn tiny_texts() {
+    let esa = EnhancedSuffixArray::construct(b"", &SaisBuilder::<u8, u32>::new());
+    assert_eq!(esa.root(), None);
+    assert_eq!(esa.find_pattern(b""), None);
+    esa.bottom_up_traversal(|_, _| panic!());
+    esa.top_down_traversal(|_| panic!());
+
+    let esa = EnhancedSuffixArray::construct(b"x", &SaisBuilder::<u8, u32>::new());
+    let root = esa.root().unwrap();
+    assert!(root.is_leaf());
+    assert_eq!(esa.child_intervals(&root).count(), 0);
+    assert_eq!(esa.find_pattern(b"x"), Some(root));
+    assert_eq!(esa.find_pattern(b"xx"), None);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn intervals_random_texts(text in prop::collection::vec(0u8..4, 0..200)) {
+        test_enhanced_suffix_array(&text);
+    }
+
+    #[test]
+    fn intervals_random_binary_texts(text in prop::collection::vec(0u8..2, 0..200)) {
+        test_enhanced_suffix_array(&text);
+    }
+
+    #[test]
+    fn intervals_random_large_alphabet_texts(text in prop::collection::vec(any::<u8>(), 0..300)) {
+        test_enhanced_suffix_array(&text);
+    }
+
+    #[test]
+    fn find_pattern_random_texts(
+        text in prop::collection::vec(0u8..3, 0..300),
+        pattern in prop::collection::vec(0u8..3, 0..8)
+    ) {
+        test_find_pattern(&text, &pattern);
+    }
+}
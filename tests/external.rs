@@ -0,0 +1,92 @@
+#![cfg(feature = "external")]
+
+use std::fs;
+use std::path::Path;
+
+use proptest::prelude::*;
+
+use sais_drum::SaisBuilder;
+use sais_drum::external::ExternalSaisBuilder;
+
+fn construct_external_and_compare(text: &[u8], memory_budget: usize) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let text_path = temp_dir.path().join("text");
+    let output_path = temp_dir.path().join("suffix_array");
+
+    fs::write(&text_path, text).unwrap();
+
+    ExternalSaisBuilder::new()
+        .with_temp_dir(temp_dir.path())
+        .with_memory_budget(memory_budget)
+        .construct_suffix_array_file(&text_path, &output_path)
+        .unwrap();
+
+    let expected = SaisBuilder::<u8, u64>::new().construct_suffix_array(text);
+
+    assert_eq!(read_u64s(&output_path), expected);
+}
+
+fn read_u64s(path: &Path) -> Vec<u64> {
+    fs::read(path)
+        .unwrap()
+        .chunks_exact(8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
+
+#[test]
+fn empty_and_tiny_texts() {
+    construct_external_and_compare(b"", 0);
+    construct_external_and_compare(b"a", 0);
+    construct_external_and_compare(b"ba", 0);
+    construct_external_and_compare(b"ababcabcabba", 0);
+}
+
+#[test]
+fn repetitive_text() {
+    // many equal LMS substrings lead to several recursion levels
+    let text: Vec<_> = b"abcab".iter().copied().cycle().take(5000).collect();
+    construct_external_and_compare(&text, 0);
+}
+
+#[test]
+fn single_char_text() {
+    // a single bucket that is larger than the memory budget
+    construct_external_and_compare(&[b'x'; 3000], 0);
+}
+
+#[test]
+fn long_chains() {
+    // long runs of distinct characters make long chains of L- and S-type suffixes
+    let text: Vec<_> = (0..=255u8)
+        .chain((0..=255u8).rev())
+        .cycle()
+        .take(5000)
+        .collect();
+    construct_external_and_compare(&text, 0);
+}
+
+#[test]
+fn in_memory_fallback() {
+    construct_external_and_compare(b"mississippi", 1 << 20);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn correctness_random_texts(
+        text in prop::collection::vec(any::<u8>(), 0..3000),
+        memory_budget in 0usize..20_000,
+    ) {
+        construct_external_and_compare(&text, memory_budget);
+    }
+
+    #[test]
+    fn correctness_random_small_alphabet_texts(
+        text in prop::collection::vec(0u8..4, 0..3000),
+        memory_budget in 0usize..20_000,
+    ) {
+        construct_external_and_compare(&text, memory_budget);
+    }
+}
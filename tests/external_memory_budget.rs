@@ -0,0 +1,117 @@
+#![cfg(feature = "external")]
+
+// a separate test binary, such that no other test allocates while the peak is measured
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rand::{Rng, SeedableRng};
+
+use sais_drum::SaisBuilder;
+use sais_drum::external::ExternalSaisBuilder;
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+fn add_allocated(size: usize) {
+    let allocated = ALLOCATED.fetch_add(size, Ordering::SeqCst) + size;
+    PEAK_ALLOCATED.fetch_max(allocated, Ordering::SeqCst);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            add_allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+            add_allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn peak_memory_of_construction(text: &[u8], memory_budget: usize) -> usize {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let text_path = temp_dir.path().join("text");
+    let output_path = temp_dir.path().join("suffix_array");
+
+    fs::write(&text_path, text).unwrap();
+
+    let mut builder = ExternalSaisBuilder::new();
+    builder
+        .with_temp_dir(temp_dir.path())
+        .with_memory_budget(memory_budget);
+
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK_ALLOCATED.store(baseline, Ordering::SeqCst);
+
+    builder
+        .construct_suffix_array_file(&text_path, &output_path)
+        .unwrap();
+
+    let peak = PEAK_ALLOCATED.load(Ordering::SeqCst) - baseline;
+
+    let suffix_array: Vec<_> = fs::read(&output_path)
+        .unwrap()
+        .chunks_exact(8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    assert_eq!(
+        suffix_array,
+        SaisBuilder::<u8, u64>::new().construct_suffix_array(text)
+    );
+
+    peak
+}
+
+// only one test in this binary, because tests run in parallel
+#[test]
+fn memory_budget_is_respected() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0x0DDB1A5E5BAD5EEDu64);
+    let memory_budget = 1 << 20;
+
+    // the suffix arrays alone are several times larger than the memory budget
+    let random_text: Vec<u8> = (0..1 << 19).map(|_| rng.random()).collect();
+    let dna_text: Vec<u8> = (0..1 << 19)
+        .map(|_| b"ACGT"[rng.random_range(0..4)])
+        .collect();
+    let repetitive_text: Vec<u8> = b"abcab".iter().copied().cycle().take(1 << 19).collect();
+
+    // decreasing numbers that are separated by a smaller character, twice. The reduced text of
+    // the reduced text is mostly a run of distinct descending names, a single chain of L-type
+    // suffixes that is much larger than the memory budget
+    let descending_numbers: Vec<u8> = (56_000..100_000u32)
+        .rev()
+        .flat_map(|number| {
+            let digits = number.to_string().into_bytes();
+            std::iter::once(b'a').chain(digits.into_iter().map(|digit| digit - b'0' + b'b'))
+        })
+        .collect();
+    let long_chain_text = [descending_numbers.clone(), descending_numbers].concat();
+
+    for text in [random_text, dna_text, repetitive_text, long_chain_text] {
+        let peak = peak_memory_of_construction(&text, memory_budget);
+        assert!(
+            peak <= memory_budget,
+            "peak memory of {peak} bytes exceeds the budget of {memory_budget} bytes"
+        );
+    }
+}
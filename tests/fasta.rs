@@ -0,0 +1,140 @@
+#![cfg(feature = "fasta")]
+
+use sais_drum::fasta::{Alphabet, FastaError, Record, SequenceCollection, read_records};
+
+static FASTA: &str = ">chr1 first chromosome
+ACGTac
+gtNR
+; a comment
+>chr2
+tta
+
+>empty
+>chr3
+GGA
+";
+
+static FASTQ: &str = "@read1 some description
+ACGTN
++
+IIIII
+@read2
+gga
++read2
+!!!
+";
+
+#[test]
+fn read_fasta_records() {
+    let records = read_records(FASTA.as_bytes()).unwrap();
+    let names: Vec<_> = records.iter().map(|record| record.name.as_str()).collect();
+
+    assert_eq!(names, ["chr1", "chr2", "empty", "chr3"]);
+    assert_eq!(records[0].sequence, b"ACGTacgtNR");
+    assert_eq!(records[1].sequence, b"tta");
+    assert_eq!(records[2].sequence, b"");
+}
+
+#[test]
+fn read_fastq_records() {
+    let records = read_records(FASTQ.as_bytes()).unwrap();
+
+    assert_eq!(
+        records,
+        [
+            Record {
+                name: String::from("read1"),
+                sequence: b"ACGTN".to_vec()
+            },
+            Record {
+                name: String::from("read2"),
+                sequence: b"gga".to_vec()
+            }
+        ]
+    );
+}
+
+#[test]
+fn invalid_inputs() {
+    assert!(matches!(
+        read_records("ACGT".as_bytes()),
+        Err(FastaError::InvalidFormat { line: 1, .. })
+    ));
+
+    assert!(matches!(
+        read_records("@read\nACGT\n+\nIII\n".as_bytes()),
+        Err(FastaError::InvalidFormat { line: 4, .. })
+    ));
+
+    assert!(matches!(
+        SequenceCollection::read(">seq\nACGU\n>protein\nEQL\n".as_bytes(), Alphabet::Dna),
+        Err(FastaError::InvalidResidue {
+            offset: 0,
+            residue: b'E',
+            ..
+        })
+    ));
+
+    assert!(read_records("".as_bytes()).unwrap().is_empty());
+}
+
+#[test]
+fn dense_encodings() {
+    let collection = SequenceCollection::read(FASTA.as_bytes(), Alphabet::Dna).unwrap();
+    assert_eq!(&collection.text()[..11], [1, 2, 3, 4, 1, 2, 3, 4, 5, 5, 0]);
+
+    let collection = SequenceCollection::read(FASTA.as_bytes(), Alphabet::DnaIupac).unwrap();
+    assert_eq!(&collection.text()[..11], [1, 2, 3, 4, 1, 2, 3, 4, 15, 5, 0]);
+
+    for alphabet in [Alphabet::Dna, Alphabet::DnaIupac, Alphabet::Protein] {
+        for code in 1..=alphabet.max_code() {
+            let residue = alphabet.decode(code).unwrap();
+            assert_eq!(alphabet.encode(residue), Some(code));
+            assert_eq!(alphabet.encode(residue.to_ascii_lowercase()), Some(code));
+        }
+
+        assert_eq!(alphabet.decode(0), None);
+        assert_eq!(alphabet.decode(alphabet.max_code() + 1), None);
+    }
+}
+
+#[test]
+fn suffix_arrays_and_coordinates() {
+    let collection = SequenceCollection::read(FASTA.as_bytes(), Alphabet::Dna).unwrap();
+    let text = collection.text();
+
+    assert_eq!(collection.num_records(), 4);
+    assert_eq!(collection.record_range(1), 11..14);
+    assert_eq!(collection.record_range(2), 15..15);
+    assert_eq!(collection.locate(12), Some(("chr2", 1)));
+    assert_eq!(collection.locate(16), Some(("chr3", 0)));
+    assert_eq!(collection.locate(10), None);
+    assert_eq!(collection.locate(text.len()), None);
+
+    let concatenated: Vec<u32> = collection.concatenated_suffix_array();
+    assert_eq!(concatenated.len(), text.len());
+    assert!(
+        concatenated
+            .windows(2)
+            .all(|pair| text[pair[0] as usize..] < text[pair[1] as usize..])
+    );
+
+    let generalized: Vec<u32> = collection.generalized_suffix_array();
+    assert_eq!(generalized.len(), text.len() - collection.num_records());
+    assert!(
+        generalized
+            .iter()
+            .all(|&position| collection.locate(position as usize).is_some())
+    );
+
+    // all occurrences of GT, reported in record coordinates
+    let pattern = collection.encode_pattern(b"gt").unwrap();
+    let mut hits: Vec<_> = generalized
+        .iter()
+        .filter(|&&position| text[position as usize..].starts_with(&pattern))
+        .map(|&position| collection.locate(position as usize).unwrap())
+        .collect();
+    hits.sort();
+
+    assert_eq!(hits, [("chr1", 2), ("chr1", 6)]);
+}
@@ -0,0 +1,149 @@
+use proptest::prelude::*;
+
+use sais_drum::SaisBuilder;
+use sais_drum::format::{FormatError, SuffixArrayFile, SuffixArrayFileWriter};
+
+static ABC_TEXT: &[u8] = b"ababcabcabba";
+
+fn write_abc_file() -> Vec<u8> {
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(ABC_TEXT);
+    let lcp = [0u32, 2, 1, 4, 3, 0, 1, 3, 2, 0, 2, 1];
+    let samples = [11u32, 8, 2];
+
+    let mut bytes = Vec::new();
+    SuffixArrayFileWriter::new(ABC_TEXT, &suffix_array)
+        .with_lcp(&lcp)
+        .with_samples(4, &samples)
+        .write_to(&mut bytes)
+        .unwrap();
+
+    bytes
+}
+
+#[test]
+fn roundtrip_with_companion_arrays() {
+    let bytes = write_abc_file();
+    let file = SuffixArrayFile::<u8, u32>::read_from(bytes.as_slice()).unwrap();
+    let header = file.header();
+
+    assert_eq!(header.text_len, 12);
+    assert_eq!(header.alphabet_size, b'c' as u64 + 1);
+    assert!(header.matches_text(ABC_TEXT));
+    assert!(!header.matches_text(b"ababcabcabbb"));
+    assert_eq!(file.suffix_array(), [11, 0, 8, 5, 2, 10, 1, 9, 6, 3, 7, 4]);
+    assert_eq!(
+        file.lcp(),
+        Some([0, 2, 1, 4, 3, 0, 1, 3, 2, 0, 2, 1].as_slice())
+    );
+    assert_eq!(file.bwt(), None);
+    assert_eq!(file.samples(), Some([11, 8, 2].as_slice()));
+    assert_eq!(header.sample_rate, 4);
+}
+
+#[test]
+fn corrupted_files_are_rejected() {
+    let bytes = write_abc_file();
+
+    let mut wrong_magic = bytes.clone();
+    wrong_magic[0] = b'X';
+    assert!(matches!(
+        SuffixArrayFile::<u8, u32>::read_from(wrong_magic.as_slice()),
+        Err(FormatError::InvalidMagicNumber)
+    ));
+
+    let mut corrupted_header = bytes.clone();
+    corrupted_header[20] ^= 1;
+    assert!(matches!(
+        SuffixArrayFile::<u8, u32>::read_from(corrupted_header.as_slice()),
+        Err(FormatError::HeaderChecksumMismatch)
+    ));
+
+    // the first byte after the header belongs to the suffix array
+    let mut corrupted_array = bytes.clone();
+    corrupted_array[80] ^= 1;
+    assert!(matches!(
+        SuffixArrayFile::<u8, u32>::read_from(corrupted_array.as_slice()),
+        Err(FormatError::ChecksumMismatch)
+    ));
+
+    assert!(matches!(
+        SuffixArrayFile::<u8, u32>::read_from(&bytes[..bytes.len() - 1]),
+        Err(FormatError::Io(_))
+    ));
+
+    assert!(matches!(
+        SuffixArrayFile::<u8, u64>::read_from(bytes.as_slice()),
+        Err(FormatError::IndexWidthMismatch {
+            expected: 8,
+            found: 4
+        })
+    ));
+}
+
+#[test]
+fn truncated_files_are_rejected_before_reading_the_arrays() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("abc.sa");
+    let bytes = write_abc_file();
+
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(SuffixArrayFile::<u8, u32>::read_from_file(&path).is_ok());
+
+    std::fs::write(&path, &bytes[..100]).unwrap();
+    assert!(matches!(
+        SuffixArrayFile::<u8, u32>::read_from_file(&path),
+        Err(FormatError::UnexpectedFileLength {
+            expected,
+            found: 100
+        }) if expected == bytes.len()
+    ));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn zero_copy_reader() {
+    use sais_drum::format::MappedSuffixArrayFile;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("abc.sa");
+    std::fs::write(&path, write_abc_file()).unwrap();
+
+    let file = MappedSuffixArrayFile::<u8, u32>::open(&path).unwrap();
+
+    assert!(file.verify_checksum());
+    assert_eq!(file.suffix_array(), [11, 0, 8, 5, 2, 10, 1, 9, 6, 3, 7, 4]);
+    assert_eq!(
+        file.lcp(),
+        Some([0, 2, 1, 4, 3, 0, 1, 3, 2, 0, 2, 1].as_slice())
+    );
+    assert_eq!(file.bwt(), None);
+    assert_eq!(file.samples(), Some([11, 8, 2].as_slice()));
+
+    std::fs::write(&path, &write_abc_file()[..100]).unwrap();
+    assert!(matches!(
+        MappedSuffixArrayFile::<u8, u32>::open(&path),
+        Err(FormatError::UnexpectedFileLength { .. })
+    ));
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_random_texts(text in prop::collection::vec(0u16..300, 0..1000)) {
+        let suffix_array = SaisBuilder::<u16, u64>::new().construct_suffix_array(&text);
+        let bwt: Vec<_> = text.iter().rev().copied().collect();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("random.sa");
+
+        SuffixArrayFileWriter::new(&text, &suffix_array)
+            .with_bwt(&bwt, 0)
+            .write_to_file(&path)
+            .unwrap();
+
+        let file = SuffixArrayFile::<u16, u64>::read_from_file(&path).unwrap();
+
+        prop_assert!(file.header().matches_text(&text));
+        prop_assert_eq!(file.suffix_array(), suffix_array.as_slice());
+        prop_assert_eq!(file.bwt(), Some(bwt.as_slice()));
+    }
+}
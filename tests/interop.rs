@@ -0,0 +1,74 @@
+use proptest::prelude::*;
+
+use sais_drum::SaisBuilder;
+use sais_drum::interop::divsufsort::{RawIndexWidth, read_suffix_array, write_suffix_array};
+use sais_drum::interop::sdsl;
+
+#[test]
+fn divsufsort_suffix_array_can_be_read() {
+    let text = b"abracadabra and abracadabra again";
+
+    // the same layout that mksary writes for a 32 bit build of libdivsufsort
+    let divsufsort_bytes: Vec<u8> = divsufsort::sort(text)
+        .into_parts()
+        .1
+        .iter()
+        .flat_map(|index| index.to_le_bytes())
+        .collect();
+
+    let suffix_array: Vec<u32> =
+        read_suffix_array(divsufsort_bytes.as_slice(), RawIndexWidth::Int32).unwrap();
+    assert_eq!(
+        suffix_array,
+        SaisBuilder::<u8, u32>::new().construct_suffix_array(text)
+    );
+
+    let mut written_bytes = Vec::new();
+    write_suffix_array(&mut written_bytes, &suffix_array, RawIndexWidth::Int32).unwrap();
+    assert_eq!(written_bytes, divsufsort_bytes);
+}
+
+#[test]
+fn invalid_raw_suffix_arrays_are_rejected() {
+    let negative_index = (-1i32).to_le_bytes();
+    assert!(read_suffix_array::<u32>(negative_index.as_slice(), RawIndexWidth::Int32).is_err());
+
+    let incomplete_index = [0u8; 7];
+    assert!(read_suffix_array::<u64>(incomplete_index.as_slice(), RawIndexWidth::Int64).is_err());
+
+    let too_large_index = 300i32.to_le_bytes();
+    assert!(read_suffix_array::<u8>(too_large_index.as_slice(), RawIndexWidth::Int32).is_err());
+
+    let mut bytes = Vec::new();
+    assert!(write_suffix_array(&mut bytes, &[u32::MAX], RawIndexWidth::Int32).is_err());
+}
+
+proptest! {
+    #[test]
+    fn raw_suffix_array_roundtrip(text in prop::collection::vec(any::<u8>(), 0..1000)) {
+        let suffix_array = SaisBuilder::<u8, u64>::new().construct_suffix_array(&text);
+
+        for index_width in [RawIndexWidth::Int32, RawIndexWidth::Int64] {
+            let mut bytes = Vec::new();
+            write_suffix_array(&mut bytes, &suffix_array, index_width).unwrap();
+
+            let suffix_array_read: Vec<u64> = read_suffix_array(bytes.as_slice(), index_width).unwrap();
+            prop_assert_eq!(&suffix_array_read, &suffix_array);
+        }
+    }
+
+    #[test]
+    fn sdsl_int_vector_roundtrip(text in prop::collection::vec(any::<u8>(), 0..1000)) {
+        let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+
+        let mut bytes = Vec::new();
+        sdsl::write_int_vector(&mut bytes, &suffix_array).unwrap();
+        let suffix_array_read: Vec<u32> = sdsl::read_int_vector(bytes.as_slice()).unwrap();
+        prop_assert_eq!(suffix_array_read, suffix_array);
+
+        let mut bytes = Vec::new();
+        sdsl::write_fixed_width_int_vector(&mut bytes, &text, 8).unwrap();
+        let text_read: Vec<u8> = sdsl::read_fixed_width_int_vector(bytes.as_slice(), 8).unwrap();
+        prop_assert_eq!(text_read, text);
+    }
+}
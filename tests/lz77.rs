@@ -0,0 +1,235 @@
+use proptest::prelude::*;
+
+use sais_drum::lz77::{self, Phrase};
+use sais_drum::{SaisBuilder, lcp};
+
+fn naive_longest_previous_factor(text: &[u8], position: usize) -> usize {
+    (0..position)
+        .map(|source| {
+            text[source..]
+                .iter()
+                .zip(&text[position..])
+                .take_while(|(source_char, char)| source_char == char)
+                .count()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn naive_longest_previous_non_overlapping_factor(text: &[u8], position: usize) -> usize {
+    (0..position)
+        .map(|source| {
+            text[source..position]
+                .iter()
+                .zip(&text[position..])
+                .take_while(|(source_char, char)| source_char == char)
+                .count()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+// copies character by character, such that sources that overlap their phrase work
+fn decode(phrases: &[Phrase<u8>]) -> Vec<u8> {
+    let mut text = Vec::new();
+
+    for phrase in phrases {
+        match *phrase {
+            Phrase::Literal(char) => text.push(char),
+            Phrase::Copy { source, len } => {
+                for offset in 0..len {
+                    text.push(text[source + offset]);
+                }
+            }
+        }
+    }
+
+    text
+}
+
+// copies whole slices, which fails for sources that overlap their phrase
+fn decode_non_overlapping(phrases: &[Phrase<u8>]) -> Vec<u8> {
+    let mut text = Vec::new();
+
+    for phrase in phrases {
+        match *phrase {
+            Phrase::Literal(char) => text.push(char),
+            Phrase::Copy { source, len } => {
+                assert!(source + len <= text.len());
+                text.extend_from_within(source..source + len);
+            }
+        }
+    }
+
+    text
+}
+
+fn test_lz77(text: &[u8]) {
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    let longest_previous_factors = lz77::longest_previous_factors(&suffix_array, &lcp);
+
+    for position in 0..text.len() {
+        let len = longest_previous_factors.lengths[position] as usize;
+        assert_eq!(len, naive_longest_previous_factor(text, position));
+
+        if len > 0 {
+            let source = longest_previous_factors.sources[position] as usize;
+            assert!(source < position);
+            assert_eq!(text[source..source + len], text[position..position + len]);
+        }
+    }
+
+    let phrases = lz77::lz77_factorization(text, &suffix_array, &lcp);
+    assert_eq!(decode(&phrases), text);
+    check_greedy_parse(text, &phrases, naive_longest_previous_factor);
+
+    test_lz77_non_overlapping(text, &suffix_array, &lcp);
+}
+
+fn test_lz77_non_overlapping(text: &[u8], suffix_array: &[u32], lcp: &[u32]) {
+    let longest_previous_factors =
+        lz77::longest_previous_non_overlapping_factors(suffix_array, lcp);
+
+    for position in 0..text.len() {
+        let len = longest_previous_factors.lengths[position] as usize;
+        assert_eq!(
+            len,
+            naive_longest_previous_non_overlapping_factor(text, position)
+        );
+
+        if len > 0 {
+            let source = longest_previous_factors.sources[position] as usize;
+            assert!(source + len <= position);
+            assert_eq!(text[source..source + len], text[position..position + len]);
+        }
+    }
+
+    let phrases = lz77::lz77_non_overlapping_factorization(text, suffix_array, lcp);
+    assert_eq!(decode_non_overlapping(&phrases), text);
+    check_greedy_parse(
+        text,
+        &phrases,
+        naive_longest_previous_non_overlapping_factor,
+    );
+}
+
+fn check_greedy_parse(
+    text: &[u8],
+    phrases: &[Phrase<u8>],
+    naive_longest_previous_factor: fn(&[u8], usize) -> usize,
+) {
+    let mut position = 0;
+    for &phrase in phrases {
+        match phrase {
+            Phrase::Literal(char) => {
+                assert!(!text[..position].contains(&char));
+                position += 1;
+            }
+            Phrase::Copy { source, len } => {
+                assert!(source < position);
+                assert_eq!(len, naive_longest_previous_factor(text, position));
+                position += len;
+            }
+        }
+    }
+}
+
+#[test]
+fn example_factorization() {
+    let text = b"abababbbbc";
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    assert_eq!(
+        lz77::longest_previous_factors(&suffix_array, &lcp).lengths,
+        [0, 0, 4, 3, 2, 1, 3, 2, 1, 0]
+    );
+    assert_eq!(
+        lz77::lz77_factorization(text, &suffix_array, &lcp),
+        [
+            Phrase::Literal(b'a'),
+            Phrase::Literal(b'b'),
+            Phrase::Copy { source: 0, len: 4 },
+            Phrase::Copy { source: 5, len: 3 },
+            Phrase::Literal(b'c'),
+        ]
+    );
+
+    test_lz77(text);
+}
+
+#[test]
+fn example_non_overlapping_factorization() {
+    let text = b"abababbbbc";
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    assert_eq!(
+        lz77::longest_previous_non_overlapping_factors(&suffix_array, &lcp).lengths,
+        [0, 0, 2, 2, 2, 1, 1, 2, 1, 0]
+    );
+    assert_eq!(
+        lz77::lz77_non_overlapping_factorization(text, &suffix_array, &lcp),
+        [
+            Phrase::Literal(b'a'),
+            Phrase::Literal(b'b'),
+            Phrase::Copy { source: 0, len: 2 },
+            Phrase::Copy { source: 0, len: 2 },
+            Phrase::Copy { source: 1, len: 1 },
+            Phrase::Copy { source: 5, len: 2 },
+            Phrase::Literal(b'c'),
+        ]
+    );
+}
+
+#[test]
+fn tiny_texts() {
+    test_lz77(b"");
+    test_lz77(b"a");
+    test_lz77(b"aaaaaaaa");
+}
+
+#[test]
+fn larger_characters() {
+    let text = [300u16, 7, 300, 7, 300, 65535];
+    let suffix_array = SaisBuilder::<u16, u64>::new().construct_suffix_array(&text);
+    let lcp = lcp::construct_lcp_array(&text, &suffix_array);
+
+    assert_eq!(
+        lz77::lz77_factorization(&text, &suffix_array, &lcp),
+        [
+            Phrase::Literal(300),
+            Phrase::Literal(7),
+            Phrase::Copy { source: 0, len: 3 },
+            Phrase::Literal(65535),
+        ]
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..150)) {
+        test_lz77(&text);
+    }
+
+    #[test]
+    fn random_binary_texts(text in prop::collection::vec(0u8..2, 0..150)) {
+        test_lz77(&text);
+    }
+
+    #[test]
+    fn random_tandem_repeat_texts(
+        unit in prop::collection::vec(0u8..3, 1..6),
+        num_repeats in 1usize..40,
+        suffix in prop::collection::vec(0u8..3, 0..6),
+    ) {
+        let mut text = unit.repeat(num_repeats);
+        text.extend(suffix);
+
+        test_lz77(&text);
+    }
+}
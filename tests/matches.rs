@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use sais_drum::matches;
+
+fn common_prefix_len<C: Eq>(first: &[C], second: &[C]) -> usize {
+    first
+        .iter()
+        .zip(second)
+        .take_while(|(first_char, second_char)| first_char == second_char)
+        .count()
+}
+
+fn count_occurrences<C: Eq>(text: &[C], pattern: &[C]) -> usize {
+    text.windows(pattern.len())
+        .filter(|window| *window == pattern)
+        .count()
+}
+
+fn naive_maximal_exact_matches<C: Eq>(
+    text_a: &[C],
+    text_b: &[C],
+    min_len: usize,
+) -> Vec<(usize, usize, usize)> {
+    let mut matches = Vec::new();
+
+    for position_a in 0..text_a.len() {
+        for position_b in 0..text_b.len() {
+            let len = common_prefix_len(&text_a[position_a..], &text_b[position_b..]);
+            let is_left_maximal = position_a == 0
+                || position_b == 0
+                || text_a[position_a - 1] != text_b[position_b - 1];
+
+            if len >= min_len.max(1) && is_left_maximal {
+                matches.push((position_a, position_b, len));
+            }
+        }
+    }
+
+    matches
+}
+
+fn naive_maximal_unique_matches<C: Eq>(
+    text_a: &[C],
+    text_b: &[C],
+    min_len: usize,
+) -> Vec<(usize, usize, usize)> {
+    naive_maximal_exact_matches(text_a, text_b, min_len)
+        .into_iter()
+        .filter(|&(position_a, _, len)| {
+            let pattern = &text_a[position_a..position_a + len];
+            count_occurrences(text_a, pattern) == 1 && count_occurrences(text_b, pattern) == 1
+        })
+        .collect()
+}
+
+fn test_matches<C: sais_drum::Character>(text_a: &[C], text_b: &[C], min_len: usize) {
+    let maximal_exact_matches = matches::maximal_exact_matches::<_, u32>(text_a, text_b, min_len);
+    assert_eq!(
+        maximal_exact_matches,
+        naive_maximal_exact_matches(text_a, text_b, min_len)
+    );
+
+    let maximal_unique_matches = matches::maximal_unique_matches::<_, u32>(text_a, text_b, min_len);
+    assert_eq!(
+        maximal_unique_matches,
+        naive_maximal_unique_matches(text_a, text_b, min_len)
+    );
+
+    let maximal_exact_matches: HashSet<_> = maximal_exact_matches.into_iter().collect();
+    assert!(
+        maximal_unique_matches
+            .iter()
+            .all(|mum| maximal_exact_matches.contains(mum))
+    );
+}
+
+#[test]
+fn example_matches() {
+    let text_a = b"ACGTTACGAT";
+    let text_b = b"TACGTTCGA";
+
+    assert_eq!(
+        matches::maximal_unique_matches::<_, u32>(text_a, text_b, 3),
+        [(0, 1, 5), (4, 0, 4), (6, 6, 3)]
+    );
+    assert_eq!(
+        matches::maximal_exact_matches::<_, u32>(text_a, text_b, 3),
+        [(0, 1, 5), (4, 0, 4), (6, 6, 3)]
+    );
+
+    test_matches(text_a, text_b, 1);
+
+    // repeated matches are maximal, but not unique
+    assert_eq!(
+        matches::maximal_exact_matches::<_, u32>(b"ACAC", b"AC", 2),
+        [(0, 0, 2), (2, 0, 2)]
+    );
+    assert!(matches::maximal_unique_matches::<_, u32>(b"ACAC", b"AC", 2).is_empty());
+}
+
+#[test]
+fn empty_texts() {
+    assert!(matches::maximal_exact_matches::<u8, u32>(b"", b"ACGT", 0).is_empty());
+    assert!(matches::maximal_unique_matches::<u8, u32>(b"ACGT", b"", 0).is_empty());
+    assert!(matches::maximal_unique_matches::<u8, u32>(b"", b"", 0).is_empty());
+}
+
+#[test]
+fn large_characters() {
+    let text_a = [7u32, 1_000_000, 42, u32::MAX, 7, 42];
+    let text_b = [1_000_000, 42, u32::MAX, 7, 1_000_000];
+
+    assert_eq!(
+        matches::maximal_unique_matches::<_, u32>(&text_a, &text_b, 2),
+        [(0, 3, 2), (1, 0, 4)]
+    );
+    test_matches(&text_a, &text_b, 1);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(
+        text_a in prop::collection::vec(0u8..4, 0..60),
+        text_b in prop::collection::vec(0u8..4, 0..60),
+        min_len in 0usize..5
+    ) {
+        test_matches(&text_a, &text_b, min_len);
+    }
+
+    #[test]
+    fn random_binary_texts(
+        text_a in prop::collection::vec(0u8..2, 0..60),
+        text_b in prop::collection::vec(0u8..2, 0..60),
+        min_len in 0usize..5
+    ) {
+        test_matches(&text_a, &text_b, min_len);
+    }
+
+    #[test]
+    fn random_large_char_texts(
+        text_a in prop::collection::vec(prop::sample::select(vec![0u64, 1 << 40, u64::MAX]), 0..60),
+        text_b in prop::collection::vec(prop::sample::select(vec![0u64, 1 << 40, u64::MAX]), 0..60),
+    ) {
+        test_matches(&text_a, &text_b, 1);
+    }
+}
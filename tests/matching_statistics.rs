@@ -0,0 +1,79 @@
+use proptest::prelude::*;
+
+use sais_drum::matching_statistics::matching_statistics;
+use sais_drum::suffix_tree::SuffixTree;
+use sais_drum::{SaisBuilder, search};
+
+fn occurs(text: &[u8], pattern: &[u8]) -> bool {
+    pattern.is_empty() || text.windows(pattern.len()).any(|window| window == pattern)
+}
+
+fn test_matching_statistics(text: &[u8], query: &[u8]) {
+    let suffix_tree = SuffixTree::construct(text, &SaisBuilder::<u8, u32>::new());
+    let statistics = matching_statistics(&suffix_tree, query);
+
+    assert_eq!(statistics.lengths.len(), query.len());
+    assert_eq!(statistics.intervals.len(), query.len());
+
+    for position in 0..query.len() {
+        let expected_len = (0..=query.len() - position)
+            .rev()
+            .find(|&len| occurs(text, &query[position..position + len]))
+            .unwrap();
+        let longest_match = &query[position..position + expected_len];
+
+        assert_eq!(statistics.lengths[position], expected_len);
+        assert_eq!(
+            statistics.intervals[position],
+            search::pattern_range(text, suffix_tree.suffix_array(), longest_match)
+        );
+    }
+}
+
+#[test]
+fn example_statistics() {
+    let text = b"banana";
+    let suffix_tree = SuffixTree::construct(text, &SaisBuilder::<u8, u32>::new());
+    let statistics = matching_statistics(&suffix_tree, b"anabanx");
+
+    assert_eq!(statistics.lengths, [3, 2, 1, 3, 2, 1, 0]);
+    assert_eq!(statistics.intervals[0], 1..3);
+    assert_eq!(statistics.intervals[6], 0..6);
+
+    test_matching_statistics(text, b"anabanx");
+}
+
+#[test]
+fn empty_inputs() {
+    test_matching_statistics(b"", b"abc");
+    test_matching_statistics(b"abc", b"");
+    test_matching_statistics(b"aaaa", b"aaaaaaa");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(
+        text in prop::collection::vec(0u8..4, 0..100),
+        query in prop::collection::vec(0u8..4, 0..50)
+    ) {
+        test_matching_statistics(&text, &query);
+    }
+
+    #[test]
+    fn random_binary_texts(
+        text in prop::collection::vec(0u8..2, 0..100),
+        query in prop::collection::vec(0u8..2, 0..50)
+    ) {
+        test_matching_statistics(&text, &query);
+    }
+
+    #[test]
+    fn random_large_alphabet_texts(
+        text in prop::collection::vec(0u8..64, 0..300),
+        query in prop::collection::vec(0u8..64, 0..50)
+    ) {
+        test_matching_statistics(&text, &query);
+    }
+}
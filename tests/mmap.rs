@@ -0,0 +1,67 @@
+#![cfg(feature = "mmap")]
+
+use proptest::prelude::*;
+
+use sais_drum::{Character, IndexStorage, SaisBuilder};
+
+fn construct_into_file_and_compare<C: Character, I: IndexStorage>(text: &[C]) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("suffix_array");
+
+    for small_text_threshold in [0, usize::MAX] {
+        let mut builder = SaisBuilder::<C, I>::new();
+        builder.with_small_text_threshold(small_text_threshold);
+
+        let mapped_suffix_array = builder
+            .construct_suffix_array_into_file(text, &output_path)
+            .unwrap();
+
+        assert_eq!(*mapped_suffix_array, builder.construct_suffix_array(text));
+
+        let file_len = mapped_suffix_array.file().metadata().unwrap().len();
+        assert_eq!(file_len as usize, text.len() * size_of::<I>());
+    }
+}
+
+#[test]
+fn empty_text() {
+    construct_into_file_and_compare::<u8, u32>(&[]);
+}
+
+#[test]
+fn existing_file_is_overwritten() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("suffix_array");
+    std::fs::write(&output_path, [42u8; 1000]).unwrap();
+
+    let mapped_suffix_array = SaisBuilder::<u8, u16>::new()
+        .construct_suffix_array_into_file(b"ababcabcabba", &output_path)
+        .unwrap();
+
+    assert_eq!(*mapped_suffix_array, [11, 0, 8, 5, 2, 10, 1, 9, 6, 3, 7, 4]);
+    drop(mapped_suffix_array);
+
+    assert_eq!(
+        std::fs::read(&output_path).unwrap().len(),
+        12 * size_of::<u16>()
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn correctness_random_texts(text in prop::collection::vec(any::<u8>(), 0..2000), type_index in 0..3) {
+        match type_index {
+            0 => construct_into_file_and_compare::<u8, u16>(&text),
+            1 => construct_into_file_and_compare::<u8, u32>(&text),
+            2 => construct_into_file_and_compare::<u8, u64>(&text),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn correctness_random_u16_texts(text in prop::collection::vec(0u16..1000, 0..2000)) {
+        construct_into_file_and_compare::<u16, u32>(&text);
+    }
+}
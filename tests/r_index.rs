@@ -0,0 +1,154 @@
+use proptest::prelude::*;
+
+use sais_drum::r_index::{RIndex, RunLengthBwt};
+use sais_drum::{SaisBuilder, bwt, search};
+
+fn test_run_length_bwt(text: &[u8]) {
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let (bwt, primary_index) = bwt::construct_bwt(text, &suffix_array);
+    let run_length_bwt = RunLengthBwt::<u8, u32>::new(&bwt, primary_index);
+
+    let mut full_bwt: Vec<_> = bwt.iter().copied().map(Some).collect();
+    full_bwt.insert(primary_index, None);
+
+    assert_eq!(run_length_bwt.to_bwt(), (bwt.clone(), primary_index));
+    assert_eq!(run_length_bwt.text_len(), text.len());
+    assert_eq!(
+        run_length_bwt.num_runs(),
+        1 + full_bwt
+            .windows(2)
+            .filter(|window| window[0] != window[1])
+            .count()
+    );
+    assert_eq!(
+        run_length_bwt.runs().map(|(_, len)| len).sum::<usize>(),
+        full_bwt.len()
+    );
+
+    for (position, &char) in full_bwt.iter().enumerate() {
+        assert_eq!(run_length_bwt.get(position), char);
+    }
+
+    for char in 0..=4 {
+        for position in 0..=full_bwt.len() {
+            let expected_rank = full_bwt[..position]
+                .iter()
+                .filter(|&&other_char| other_char == Some(char))
+                .count();
+            assert_eq!(run_length_bwt.rank(char, position), expected_rank);
+        }
+    }
+
+    // the LF mapping leads from the suffix at a position to the one that starts one earlier
+    for position in 0..full_bwt.len() {
+        let suffix = position
+            .checked_sub(1)
+            .map_or(text.len(), |index| suffix_array[index] as usize);
+
+        match run_length_bwt.lf(position) {
+            Some(next_position) => {
+                let next_suffix = suffix_array[next_position - 1] as usize;
+                assert_eq!(next_suffix + 1, suffix);
+            }
+            None => assert_eq!(suffix, 0),
+        }
+    }
+}
+
+fn test_r_index(text: &[u8], patterns: &[Vec<u8>]) {
+    let builder = SaisBuilder::<u8, u32>::new();
+    let suffix_array = builder.construct_suffix_array(text);
+    let r_index = RIndex::construct(text, &builder);
+
+    for pattern in patterns
+        .iter()
+        .map(Vec::as_slice)
+        .chain([&b""[..], &text[text.len() / 3..text.len() / 2]])
+    {
+        let expected_range = search::pattern_range(text, &suffix_array, pattern);
+        assert_eq!(r_index.pattern_range(pattern), expected_range);
+        assert_eq!(r_index.count(pattern), expected_range.len());
+
+        let expected_occurrences: Vec<_> = suffix_array[expected_range]
+            .iter()
+            .rev()
+            .map(|&suffix_index| suffix_index as usize)
+            .collect();
+        let locate = r_index.locate(pattern);
+        assert_eq!(locate.len(), expected_occurrences.len());
+        assert_eq!(locate.collect::<Vec<_>>(), expected_occurrences);
+    }
+}
+
+// copies of a random base sequence with a few point mutations, like a pangenome collection
+fn repetitive_text() -> impl Strategy<Value = Vec<u8>> {
+    (
+        prop::collection::vec(0u8..4, 1..40),
+        1usize..6,
+        prop::collection::vec((any::<prop::sample::Index>(), 0u8..4), 0..5),
+    )
+        .prop_map(|(base, num_copies, mutations)| {
+            let mut text = base.repeat(num_copies);
+            for (index, char) in mutations {
+                let position = index.index(text.len());
+                text[position] = char;
+            }
+            text
+        })
+}
+
+#[test]
+fn repetitive_text_has_few_runs() {
+    let base = b"ACGTTGCAAGCTAGCTTACG";
+    let text = base.repeat(50);
+    let r_index = RIndex::construct(&text, &SaisBuilder::<u8, u32>::new());
+
+    assert!(r_index.bwt().num_runs() < 30);
+    assert_eq!(r_index.count(b"GCTAGC"), 50);
+    assert_eq!(r_index.count(b"GCTAGG"), 0);
+    assert_eq!(r_index.locate(b"CGACG").count(), 49);
+
+    test_r_index(&text, &[b"TTACGA".to_vec(), b"AAGC".to_vec()]);
+}
+
+#[test]
+fn tiny_texts() {
+    test_run_length_bwt(b"");
+    test_run_length_bwt(b"\x01");
+
+    let r_index = RIndex::construct(b"", &SaisBuilder::<u8, u32>::new());
+    assert_eq!(r_index.count(b""), 0);
+    assert_eq!(r_index.count(b"a"), 0);
+    assert_eq!(r_index.locate(b"").count(), 0);
+
+    test_r_index(b"a", &[b"a".to_vec(), b"aa".to_vec(), b"b".to_vec()]);
+    test_r_index(
+        b"aaaaa",
+        &[b"a".to_vec(), b"aaa".to_vec(), b"aaaaaa".to_vec()],
+    );
+}
+
+#[test]
+#[should_panic(expected = "too long for the index type")]
+fn too_long_for_index_type() {
+    RunLengthBwt::<u8, u8>::new(&[0; 255], 1);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..100)) {
+        test_run_length_bwt(&text);
+        test_r_index(&text, &[vec![0], vec![1, 2], vec![3, 3, 0]]);
+    }
+
+    #[test]
+    fn repetitive_texts(
+        text in repetitive_text(),
+        patterns in prop::collection::vec(prop::collection::vec(0u8..4, 1..6), 1..6)
+    ) {
+        test_run_length_bwt(&text);
+        test_r_index(&text, &patterns);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, HashSet};
+
+use proptest::prelude::*;
+
+use sais_drum::enhanced_suffix_array::EnhancedSuffixArray;
+use sais_drum::repeats::{self, Repeat};
+use sais_drum::{SaisBuilder, lcp};
+
+// every substring that occurs at least twice, with its occurrences
+fn naive_repeats(text: &[u8]) -> BTreeMap<&[u8], Vec<usize>> {
+    let mut occurrences: BTreeMap<&[u8], Vec<usize>> = BTreeMap::new();
+
+    for start in 0..text.len() {
+        for end in start + 1..=text.len() {
+            occurrences
+                .entry(&text[start..end])
+                .or_default()
+                .push(start);
+        }
+    }
+
+    occurrences.retain(|_, positions| positions.len() >= 2);
+    occurrences
+}
+
+fn naive_maximal_repeats(text: &[u8], min_len: usize) -> HashSet<Repeat> {
+    let naive_repeats = naive_repeats(text);
+
+    naive_repeats
+        .iter()
+        .filter(|(substring, _)| substring.len() >= min_len)
+        .filter(|(substring, positions)| {
+            let left_contexts: HashSet<_> = positions
+                .iter()
+                .map(|&position| position.checked_sub(1).map(|previous| text[previous]))
+                .collect();
+            let right_contexts: HashSet<_> = positions
+                .iter()
+                .map(|&position| text.get(position + substring.len()))
+                .collect();
+
+            (left_contexts.len() > 1 || left_contexts.contains(&None))
+                && (right_contexts.len() > 1 || right_contexts.contains(&None))
+        })
+        .map(|(substring, positions)| Repeat {
+            len: substring.len(),
+            positions: positions.clone(),
+        })
+        .collect()
+}
+
+fn naive_supermaximal_repeats(text: &[u8], min_len: usize) -> HashSet<Repeat> {
+    let naive_repeats = naive_repeats(text);
+
+    naive_repeats
+        .iter()
+        .filter(|(substring, _)| substring.len() >= min_len)
+        .filter(|(substring, _)| {
+            !naive_repeats.keys().any(|other| {
+                other.len() > substring.len()
+                    && other
+                        .windows(substring.len())
+                        .any(|window| window == **substring)
+            })
+        })
+        .map(|(substring, positions)| Repeat {
+            len: substring.len(),
+            positions: positions.clone(),
+        })
+        .collect()
+}
+
+fn test_repeats(text: &[u8], min_len: usize) {
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+
+    let maximal_repeats = repeats::maximal_repeats(&esa, min_len);
+    assert_eq!(
+        maximal_repeats
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>()
+            .len(),
+        maximal_repeats.len()
+    );
+    assert_eq!(
+        maximal_repeats.into_iter().collect::<HashSet<_>>(),
+        naive_maximal_repeats(text, min_len)
+    );
+
+    let supermaximal_repeats = repeats::supermaximal_repeats(&esa, min_len);
+    assert_eq!(
+        supermaximal_repeats
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>()
+            .len(),
+        supermaximal_repeats.len()
+    );
+    assert_eq!(
+        supermaximal_repeats.into_iter().collect::<HashSet<_>>(),
+        naive_supermaximal_repeats(text, min_len)
+    );
+
+    let expected_longest_repeated_substring = naive_repeats(text)
+        .into_iter()
+        .rev()
+        .max_by_key(|(substring, _)| substring.len())
+        .map(|(substring, positions)| Repeat {
+            len: substring.len(),
+            positions,
+        });
+    assert_eq!(
+        repeats::longest_repeated_substring(esa.suffix_array(), esa.lcp()),
+        expected_longest_repeated_substring
+    );
+}
+
+#[test]
+fn example_repeats() {
+    let text = b"xabcyabcwabcyz";
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    assert_eq!(
+        repeats::longest_repeated_substring(&suffix_array, &lcp),
+        Some(Repeat {
+            len: 4,
+            positions: vec![1, 9]
+        })
+    );
+
+    let esa = EnhancedSuffixArray::new(text, suffix_array, lcp);
+    let maximal_repeats: HashSet<_> = repeats::maximal_repeats(&esa, 3).into_iter().collect();
+    assert_eq!(
+        maximal_repeats,
+        HashSet::from([
+            Repeat {
+                len: 3,
+                positions: vec![1, 5, 9]
+            },
+            Repeat {
+                len: 4,
+                positions: vec![1, 9]
+            },
+        ])
+    );
+    assert_eq!(
+        repeats::supermaximal_repeats(&esa, 0),
+        [Repeat {
+            len: 4,
+            positions: vec![1, 9]
+        }]
+    );
+
+    test_repeats(text, 0);
+}
+
+#[test]
+fn without_repeats() {
+    assert_eq!(repeats::longest_repeated_substring::<u32>(&[], &[]), None);
+
+    let text = b"abc";
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+    assert_eq!(
+        repeats::longest_repeated_substring(esa.suffix_array(), esa.lcp()),
+        None
+    );
+    assert!(repeats::maximal_repeats(&esa, 0).is_empty());
+    assert!(repeats::supermaximal_repeats(&esa, 0).is_empty());
+}
+
+#[test]
+fn unary_text() {
+    // every prefix except the text itself is a maximal repeat
+    let text = b"aaaa";
+    let esa = EnhancedSuffixArray::construct(text, &SaisBuilder::<u8, u32>::new());
+    assert_eq!(repeats::maximal_repeats(&esa, 0).len(), 3);
+    assert_eq!(
+        repeats::supermaximal_repeats(&esa, 0),
+        [Repeat {
+            len: 3,
+            positions: vec![0, 1]
+        }]
+    );
+
+    test_repeats(text, 0);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..40), min_len in 0usize..4) {
+        test_repeats(&text, min_len);
+    }
+
+    #[test]
+    fn random_binary_texts(text in prop::collection::vec(0u8..2, 0..40), min_len in 0usize..4) {
+        test_repeats(&text, min_len);
+    }
+}
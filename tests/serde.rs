@@ -0,0 +1,136 @@
+#![cfg(feature = "serde")]
+
+use sais_drum::SaisBuilder;
+use sais_drum::format::{SuffixArrayFile, SuffixArrayFileWriter};
+
+static ABC_TEXT: &[u8] = b"ababcabcabba";
+
+fn abc_suffix_array_file<I: sais_drum::IndexStorage>() -> SuffixArrayFile<u8, I> {
+    let suffix_array = SaisBuilder::<u8, I>::new().construct_suffix_array(ABC_TEXT);
+
+    let mut bytes = Vec::new();
+    SuffixArrayFileWriter::new(ABC_TEXT, &suffix_array)
+        .with_bwt(b"bcbbaaacaab!", 11)
+        .write_to(&mut bytes)
+        .unwrap();
+
+    SuffixArrayFile::read_from(bytes.as_slice()).unwrap()
+}
+
+fn assert_same_content<I: sais_drum::IndexStorage>(
+    file: &SuffixArrayFile<u8, I>,
+    other: &SuffixArrayFile<u8, I>,
+) {
+    assert_eq!(file.header(), other.header());
+    assert_eq!(file.suffix_array(), other.suffix_array());
+    assert_eq!(file.lcp(), other.lcp());
+    assert_eq!(file.bwt(), other.bwt());
+    assert_eq!(file.samples(), other.samples());
+}
+
+#[test]
+fn suffix_array_file_postcard_roundtrip() {
+    let file = abc_suffix_array_file::<u16>();
+
+    let bytes = postcard::to_allocvec(&file).unwrap();
+    let deserialized: SuffixArrayFile<u8, u16> = postcard::from_bytes(&bytes).unwrap();
+
+    assert_same_content(&file, &deserialized);
+}
+
+#[test]
+fn index_arrays_are_encoded_compactly() {
+    for (file_bytes, header_bytes, index_width) in [
+        {
+            let file = abc_suffix_array_file::<u8>();
+            (
+                postcard::to_allocvec(&file).unwrap(),
+                postcard::to_allocvec(file.header()).unwrap(),
+                1,
+            )
+        },
+        {
+            let file = abc_suffix_array_file::<u16>();
+            (
+                postcard::to_allocvec(&file).unwrap(),
+                postcard::to_allocvec(file.header()).unwrap(),
+                2,
+            )
+        },
+    ] {
+        // length prefixed suffix array, no LCP, length prefixed BWT, no samples
+        let expected_len =
+            header_bytes.len() + (1 + ABC_TEXT.len() * index_width) + 1 + (2 + ABC_TEXT.len()) + 1;
+
+        assert_eq!(file_bytes.len(), expected_len);
+    }
+}
+
+#[test]
+fn usize_index_arrays_are_encoded_with_8_bytes() {
+    let file = abc_suffix_array_file::<usize>();
+    let file_bytes = postcard::to_allocvec(&file).unwrap();
+    let header_bytes = postcard::to_allocvec(file.header()).unwrap();
+
+    let expected_len = header_bytes.len() + (1 + ABC_TEXT.len() * 8) + 1 + (2 + ABC_TEXT.len()) + 1;
+    assert_eq!(file_bytes.len(), expected_len);
+
+    let deserialized: SuffixArrayFile<u8, usize> = postcard::from_bytes(&file_bytes).unwrap();
+    assert_same_content(&file, &deserialized);
+}
+
+#[test]
+fn inconsistent_deserialized_files_are_rejected() {
+    let json = serde_json::to_string(&abc_suffix_array_file::<u32>()).unwrap();
+
+    let missing_bwt = json.replace("\"has_bwt\":true", "\"has_bwt\":false");
+    let unexpected_lcp = json.replace("\"lcp\":null", "\"lcp\":[0,0,0,0,0,0,0,0,0,0,0,0]");
+    let too_short = json.replace("[11,0,8,", "[11,8,");
+    let corrupted = json.replace("[11,0,8,", "[11,8,0,");
+    let other_version = json.replace("\"version\":", "\"version\":1");
+
+    for manipulated in [
+        missing_bwt,
+        unexpected_lcp,
+        too_short,
+        corrupted,
+        other_version,
+    ] {
+        assert_ne!(manipulated, json);
+        assert!(serde_json::from_str::<SuffixArrayFile<u8, u32>>(&manipulated).is_err());
+    }
+}
+
+#[test]
+fn suffix_array_file_json_roundtrip() {
+    let file = abc_suffix_array_file::<u32>();
+
+    let json = serde_json::to_string(&file).unwrap();
+    assert!(json.contains("\"suffix_array\":[11,0,8,5,2,10,1,9,6,3,7,4]"));
+
+    let deserialized: SuffixArrayFile<u8, u32> = serde_json::from_str(&json).unwrap();
+    assert_same_content(&file, &deserialized);
+
+    let too_large_index = json.replace("[11,0,8", "[300,0,8");
+    assert!(serde_json::from_str::<SuffixArrayFile<u8, u8>>(&too_large_index).is_err());
+}
+
+#[test]
+fn builder_configuration_roundtrip() {
+    let mut builder = SaisBuilder::<u16, u32>::new();
+    builder
+        .with_max_char(300)
+        .with_small_text_threshold(0)
+        .with_alphabet_compaction(false);
+
+    let bytes = postcard::to_allocvec(&builder).unwrap();
+    let deserialized: SaisBuilder<u16, u32> = postcard::from_bytes(&bytes).unwrap();
+
+    assert_eq!(postcard::to_allocvec(&deserialized).unwrap(), bytes);
+
+    let text = [300u16, 2, 300, 2, 1];
+    assert_eq!(
+        deserialized.construct_suffix_array(&text),
+        builder.construct_suffix_array(&text)
+    );
+}
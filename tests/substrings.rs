@@ -0,0 +1,112 @@
+use std::collections::{BTreeMap, HashSet};
+
+use proptest::prelude::*;
+
+use sais_drum::substrings::{self, SubstringProfile};
+use sais_drum::{SaisBuilder, lcp};
+
+fn test_substrings(text: &[u8]) {
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    for min_k in 1..=text.len() + 1 {
+        for ks in [min_k..=min_k, min_k..=text.len() + 1, min_k..=min_k - 1] {
+            let kmer_counts: Vec<_> =
+                substrings::kmer_counts(text, &suffix_array, &lcp, ks.clone()).collect();
+
+            assert!(kmer_counts.iter().all(|(kmer, _)| ks.contains(&kmer.len())));
+
+            for k in ks {
+                let mut expected_counts = BTreeMap::new();
+                for kmer in text.windows(k) {
+                    *expected_counts.entry(kmer).or_insert(0) += 1;
+                }
+
+                let counts_of_k: Vec<_> = kmer_counts
+                    .iter()
+                    .copied()
+                    .filter(|(kmer, _)| kmer.len() == k)
+                    .collect();
+                assert_eq!(counts_of_k, expected_counts.into_iter().collect::<Vec<_>>());
+            }
+        }
+    }
+
+    let profile = SubstringProfile::new(&suffix_array, &lcp);
+    let mut total_distinct = 0;
+
+    assert_eq!(profile.distinct_per_length().len(), text.len() + 1);
+    assert_eq!(profile.distinct_substrings_of_len(0), 1);
+    assert_eq!(profile.distinct_substrings_of_len(text.len() + 1), 0);
+
+    for len in 1..=text.len() {
+        let distinct = text.windows(len).collect::<HashSet<_>>().len();
+        assert_eq!(profile.distinct_substrings_of_len(len), distinct);
+        total_distinct += distinct as u128;
+    }
+
+    assert_eq!(profile.total_distinct_substrings(), total_distinct);
+}
+
+#[test]
+fn example_profile() {
+    let text = b"abab";
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    assert_eq!(
+        substrings::kmer_counts(text, &suffix_array, &lcp, 2..=2).collect::<Vec<_>>(),
+        [(&b"ab"[..], 2), (b"ba", 1)]
+    );
+    // the suffixes in suffix array order are ab, abab, b and bab
+    assert_eq!(
+        substrings::kmer_counts(text, &suffix_array, &lcp, 1..=3).collect::<Vec<_>>(),
+        [
+            (&b"a"[..], 2),
+            (b"ab", 2),
+            (b"aba", 1),
+            (b"b", 2),
+            (b"ba", 1),
+            (b"bab", 1),
+        ]
+    );
+
+    let profile = SubstringProfile::new(&suffix_array, &lcp);
+    assert_eq!(profile.distinct_per_length(), [1, 2, 2, 2, 1]);
+    assert_eq!(profile.total_distinct_substrings(), 7);
+    assert_eq!(profile.substring_complexity(), 2.0);
+
+    test_substrings(text);
+}
+
+#[test]
+fn tiny_texts() {
+    let profile = SubstringProfile::new::<u32>(&[], &[]);
+    assert_eq!(profile.distinct_per_length(), [1]);
+    assert_eq!(profile.total_distinct_substrings(), 0);
+    assert_eq!(profile.substring_complexity(), 0.0);
+
+    test_substrings(b"");
+    test_substrings(b"a");
+    test_substrings(b"aaaaaa");
+}
+
+#[test]
+#[should_panic(expected = "k has to be at least 1")]
+fn zero_k() {
+    substrings::kmer_counts::<u8, u32>(&[], &[], &[], 0..=2);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..60)) {
+        test_substrings(&text);
+    }
+
+    #[test]
+    fn random_binary_texts(text in prop::collection::vec(0u8..2, 0..60)) {
+        test_substrings(&text);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use sais_drum::SaisBuilder;
+use sais_drum::enhanced_suffix_array::EnhancedSuffixArray;
+use sais_drum::suffix_tree::SuffixTree;
+
+fn test_suffix_tree(text: &[u8]) {
+    let builder = SaisBuilder::<u8, u32>::new();
+    let tree = SuffixTree::construct(text, &builder);
+    let root = tree.root();
+
+    assert_eq!(tree.num_leaves(), text.len());
+    assert_eq!(tree.string_depth(root), 0);
+    assert_eq!(tree.parent(root), None);
+    assert_eq!(tree.suffix_link(root), None);
+    assert_eq!(tree.leaf_range(root), 0..text.len());
+
+    // pre-order visits every node once, and the leaves in suffix array order
+    let dfs_nodes: Vec<_> = tree.dfs(root).collect();
+    assert_eq!(dfs_nodes.len(), tree.num_nodes());
+    assert_eq!(
+        dfs_nodes.iter().copied().collect::<HashSet<_>>().len(),
+        tree.num_nodes()
+    );
+    let dfs_leaves: Vec<_> = dfs_nodes
+        .iter()
+        .copied()
+        .filter(|&node| tree.is_leaf(node))
+        .collect();
+    assert_eq!(dfs_leaves, (0..text.len()).collect::<Vec<_>>());
+
+    for node in 0..tree.num_nodes() {
+        let path_label = tree.path_label(node);
+        assert_eq!(path_label.len(), tree.string_depth(node));
+
+        if tree.is_leaf(node) {
+            let suffix_index = tree.suffix_index(node);
+            assert_eq!(path_label, &text[suffix_index..]);
+            assert_eq!(tree.leaf_range(node), node..node + 1);
+            assert_eq!(tree.children(node).count(), 0);
+
+            let expected_suffix_link = if suffix_index + 1 == text.len() {
+                root
+            } else {
+                tree.suffix_array()
+                    .iter()
+                    .position(|&index| index as usize == suffix_index + 1)
+                    .unwrap()
+            };
+            assert_eq!(tree.suffix_link(node), Some(expected_suffix_link));
+        } else if !tree.is_root(node) {
+            assert!(tree.children(node).count() >= 2);
+
+            let suffix_link = tree.suffix_link(node).unwrap();
+            assert!(!tree.is_leaf(suffix_link));
+            assert_eq!(tree.path_label(suffix_link), &path_label[1..]);
+        }
+
+        if let Some(parent) = tree.parent(node) {
+            assert!(tree.children(parent).any(|child| child == node));
+        }
+
+        let children: Vec<_> = tree.children(node).collect();
+        let mut previous_first_char = None;
+        let mut next_leaf = tree.leaf_range(node).start;
+
+        for &child in &children {
+            assert_eq!(tree.parent(child), Some(node));
+
+            // the children partition the leaves of their parent
+            assert_eq!(tree.leaf_range(child).start, next_leaf);
+            next_leaf = tree.leaf_range(child).end;
+
+            let (label_start, label_len) = tree.edge_label(child);
+            assert_eq!(
+                &tree.path_label(child)[..tree.string_depth(node)],
+                path_label
+            );
+            assert_eq!(
+                &text[label_start..label_start + label_len],
+                &tree.path_label(child)[tree.string_depth(node)..]
+            );
+
+            if label_len == 0 {
+                // only a leaf can end at its parent, and it is the first child
+                assert!(tree.is_leaf(child));
+                assert_eq!(child, children[0]);
+                continue;
+            }
+
+            let first_char = text[label_start];
+            assert!(previous_first_char < Some(first_char));
+            previous_first_char = Some(first_char);
+
+            assert_eq!(tree.child_with_char(node, first_char), Some(child));
+        }
+
+        for char in 0..=u8::MAX {
+            if !children.iter().any(|&child| {
+                let (label_start, label_len) = tree.edge_label(child);
+                label_len > 0 && text[label_start] == char
+            }) {
+                assert_eq!(tree.child_with_char(node, char), None);
+            }
+        }
+
+        if !tree.is_leaf(node) {
+            assert_eq!(next_leaf, tree.leaf_range(node).end);
+        }
+    }
+
+    // the internal nodes are the LCP-intervals
+    let esa = EnhancedSuffixArray::construct(text, &builder);
+    let mut expected_intervals = HashSet::new();
+    esa.bottom_up_traversal(|interval, _| {
+        expected_intervals.insert((interval.lcp, interval.range()));
+    });
+
+    let intervals: HashSet<_> = (0..tree.num_nodes())
+        .filter(|&node| !tree.is_leaf(node) && tree.children(node).count() >= 2)
+        .map(|node| (tree.string_depth(node), tree.leaf_range(node)))
+        .collect();
+
+    assert_eq!(intervals, expected_intervals);
+}
+
+#[test]
+fn example_tree() {
+    let text = b"banana";
+    let tree = SuffixTree::construct(text, &SaisBuilder::<u8, u32>::new());
+    let root = tree.root();
+
+    let child_labels: Vec<_> = tree
+        .children(root)
+        .map(|child| tree.path_label(child))
+        .collect();
+    assert_eq!(child_labels, [&b"a"[..], b"banana", b"na"]);
+
+    let ana = tree
+        .child_with_char(tree.child_with_char(root, b'a').unwrap(), b'n')
+        .unwrap();
+    assert_eq!(tree.path_label(ana), b"ana");
+    assert_eq!(tree.edge_label(ana), (4, 2));
+    assert_eq!(tree.path_label(tree.suffix_link(ana).unwrap()), b"na");
+    assert_eq!(tree.child_with_char(root, b'c'), None);
+
+    let subtree_leaves: Vec<_> = tree
+        .dfs(ana)
+        .filter(|&node| tree.is_leaf(node))
+        .map(|leaf| tree.suffix_index(leaf))
+        .collect();
+    assert_eq!(subtree_leaves, [3, 1]);
+
+    test_suffix_tree(text);
+}
+
+#[test]
+fn tiny_texts() {
+    let tree = SuffixTree::construct(b"", &SaisBuilder::<u8, u32>::new());
+    assert_eq!(tree.num_nodes(), 1);
+    assert_eq!(tree.dfs(tree.root()).collect::<Vec<_>>(), [tree.root()]);
+
+    test_suffix_tree(b"x");
+    test_suffix_tree(b"aaaa");
+}
+
+#[test]
+#[should_panic(expected = "too long for the node ids")]
+fn too_long_for_index_type() {
+    let text = vec![0u8; 200];
+    SuffixTree::construct(&text, &SaisBuilder::<u8, u8>::new());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..200)) {
+        test_suffix_tree(&text);
+    }
+
+    #[test]
+    fn random_binary_texts(text in prop::collection::vec(0u8..2, 0..200)) {
+        test_suffix_tree(&text);
+    }
+
+    #[test]
+    fn random_large_alphabet_texts(text in prop::collection::vec(any::<u8>(), 0..300)) {
+        test_suffix_tree(&text);
+    }
+}
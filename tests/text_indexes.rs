@@ -0,0 +1,97 @@
+use proptest::prelude::*;
+
+use sais_drum::{SaisBuilder, bwt, lcp, search};
+
+fn naive_lcp_array(text: &[u8], suffix_array: &[u32]) -> Vec<u32> {
+    let mut lcp = vec![0; text.len()];
+
+    for i in 1..text.len() {
+        let previous_suffix = &text[suffix_array[i - 1] as usize..];
+        let suffix = &text[suffix_array[i] as usize..];
+
+        lcp[i] = previous_suffix
+            .iter()
+            .zip(suffix)
+            .take_while(|(a, b)| a == b)
+            .count() as u32;
+    }
+
+    lcp
+}
+
+fn naive_occurrences(text: &[u8], pattern: &[u8]) -> Vec<usize> {
+    (0..text.len())
+        .filter(|&position| text[position..].starts_with(pattern))
+        .collect()
+}
+
+#[test]
+fn bwt_of_example_text() {
+    let text = b"mississippi";
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+
+    // full BWT with sentinel: ipssm$pissii
+    assert_eq!(
+        bwt::construct_bwt(text, &suffix_array),
+        (b"ipssmpissii".to_vec(), 5)
+    );
+}
+
+#[test]
+fn empty_text() {
+    assert_eq!(bwt::construct_bwt::<u8, u32>(&[], &[]), (Vec::new(), 0));
+    assert!(bwt::invert_bwt::<u8>(&[], 0).is_empty());
+    assert!(lcp::construct_lcp_array::<u8, u32>(&[], &[]).is_empty());
+    assert_eq!(search::pattern_range::<u8, u32>(&[], &[], b"a"), 0..0);
+}
+
+#[test]
+fn lcp_of_example_text() {
+    let text = b"banana";
+    let suffix_array = SaisBuilder::<u8, u16>::new().construct_suffix_array(text);
+
+    assert_eq!(
+        lcp::construct_lcp_array(text, &suffix_array),
+        [0, 1, 3, 0, 0, 2]
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn bwt_roundtrip(text in prop::collection::vec(0u8..4, 0..1000)) {
+        let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+        let (bwt, primary_index) = bwt::construct_bwt(&text, &suffix_array);
+
+        prop_assert_eq!(bwt::invert_bwt(&bwt, primary_index), text);
+    }
+
+    #[test]
+    fn lcp_correctness(text in prop::collection::vec(0u8..4, 0..1000)) {
+        let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+
+        prop_assert_eq!(
+            lcp::construct_lcp_array(&text, &suffix_array),
+            naive_lcp_array(&text, &suffix_array)
+        );
+    }
+
+    #[test]
+    fn search_correctness(
+        text in prop::collection::vec(0u8..3, 0..1000),
+        pattern in prop::collection::vec(0u8..3, 0..6)
+    ) {
+        let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+
+        let mut positions: Vec<_> =
+            search::locate_occurrences(&text, &suffix_array, &pattern).collect();
+        positions.sort_unstable();
+
+        prop_assert_eq!(
+            search::count_occurrences(&text, &suffix_array, &pattern),
+            positions.len()
+        );
+        prop_assert_eq!(positions, naive_occurrences(&text, &pattern));
+    }
+}
@@ -0,0 +1,87 @@
+use std::ops::Range;
+
+use proptest::prelude::*;
+
+use sais_drum::unique_substrings;
+use sais_drum::{SaisBuilder, lcp};
+
+fn occurrences(text: &[u8], word: &[u8]) -> usize {
+    text.windows(word.len())
+        .filter(|window| *window == word)
+        .count()
+}
+
+fn test_unique_substrings(text: &[u8]) {
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    let expected_lengths: Vec<_> = (0..text.len())
+        .map(|start| {
+            (1..=text.len() - start).find(|&len| occurrences(text, &text[start..start + len]) == 1)
+        })
+        .collect();
+
+    assert_eq!(
+        unique_substrings::minimal_unique_lengths(&suffix_array, &lcp),
+        expected_lengths
+    );
+
+    // shortest first, then leftmost
+    let expected_shortest: Vec<Range<usize>> = (0..text.len())
+        .map(|position| {
+            (1..=text.len())
+                .flat_map(|len| (0..=text.len() - len).map(move |start| start..start + len))
+                .find(|range| {
+                    range.contains(&position) && occurrences(text, &text[range.clone()]) == 1
+                })
+                .unwrap()
+        })
+        .collect();
+
+    assert_eq!(
+        unique_substrings::shortest_unique_substrings(&suffix_array, &lcp),
+        expected_shortest
+    );
+}
+
+#[test]
+fn example_unique_substrings() {
+    let text = b"abaab";
+    let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(text);
+    let lcp = lcp::construct_lcp_array(text, &suffix_array);
+
+    assert_eq!(
+        unique_substrings::minimal_unique_lengths(&suffix_array, &lcp),
+        [Some(3), Some(2), Some(2), None, None]
+    );
+    assert_eq!(
+        unique_substrings::shortest_unique_substrings(&suffix_array, &lcp),
+        [0..3, 1..3, 1..3, 2..4, 2..5]
+    );
+
+    test_unique_substrings(text);
+}
+
+#[test]
+fn tiny_texts() {
+    assert!(unique_substrings::shortest_unique_substrings::<u32>(&[], &[]).is_empty());
+
+    test_unique_substrings(b"");
+    test_unique_substrings(b"a");
+    test_unique_substrings(b"aaaaaa");
+    test_unique_substrings(b"aba");
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn random_texts(text in prop::collection::vec(0u8..4, 0..50)) {
+        test_unique_substrings(&text);
+    }
+
+    #[test]
+    fn random_binary_texts(text in prop::collection::vec(0u8..2, 0..50)) {
+        test_unique_substrings(&text);
+    }
+}
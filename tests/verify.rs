@@ -0,0 +1,100 @@
+use proptest::prelude::*;
+
+use sais_drum::SaisBuilder;
+use sais_drum::verify::{VerificationError, verify_suffix_array};
+
+fn is_sorted_permutation(text: &[u8], suffix_array: &[u32]) -> bool {
+    let mut sorted_indices = suffix_array.to_vec();
+    sorted_indices.sort_unstable();
+
+    sorted_indices.iter().copied().eq(0..text.len() as u32)
+        && suffix_array
+            .windows(2)
+            .all(|indices| text[indices[0] as usize..] < text[indices[1] as usize..])
+}
+
+#[test]
+fn valid_suffix_arrays() {
+    assert_eq!(verify_suffix_array::<u8, u32>(&[], &[]), Ok(()));
+    assert_eq!(verify_suffix_array(b"a", &[0u8]), Ok(()));
+    assert_eq!(
+        verify_suffix_array(b"ababcabcabba", &[11u16, 0, 8, 5, 2, 10, 1, 9, 6, 3, 7, 4]),
+        Ok(())
+    );
+}
+
+#[test]
+fn structured_errors() {
+    assert_eq!(
+        verify_suffix_array(b"abc", &[0u32, 1]),
+        Err(VerificationError::LengthMismatch {
+            text_len: 3,
+            suffix_array_len: 2
+        })
+    );
+    assert_eq!(
+        verify_suffix_array(b"abc", &[0u32, 3, 2]),
+        Err(VerificationError::IndexOutOfBounds {
+            position: 1,
+            suffix_index: 3
+        })
+    );
+    assert_eq!(
+        verify_suffix_array(b"abc", &[0u32, 1, 0]),
+        Err(VerificationError::DuplicateIndex {
+            position: 2,
+            suffix_index: 0
+        })
+    );
+    assert_eq!(
+        verify_suffix_array(b"abc", &[0u32, 2, 1]),
+        Err(VerificationError::WrongOrder { position: 2 })
+    );
+    // equal first characters, the shorter suffix must come first
+    assert_eq!(
+        verify_suffix_array(b"aa", &[0u32, 1]),
+        Err(VerificationError::WrongOrder { position: 1 })
+    );
+}
+
+#[test]
+fn large_index_values() {
+    // the largest u8 value is a valid index for a text of length 256
+    let text: Vec<u8> = (0..=255).collect();
+    let suffix_array: Vec<u8> = (0..=255).collect();
+
+    assert_eq!(verify_suffix_array(&text, &suffix_array), Ok(()));
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1024))]
+
+    #[test]
+    fn constructed_suffix_arrays_are_valid(text in prop::collection::vec(0u8..4, 0..1000)) {
+        let suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+        prop_assert_eq!(verify_suffix_array(&text, &suffix_array), Ok(()));
+    }
+
+    #[test]
+    fn swapped_entries_are_detected(
+        text in prop::collection::vec(0u8..4, 2..1000),
+        first in any::<prop::sample::Index>(),
+        second in any::<prop::sample::Index>()
+    ) {
+        let mut suffix_array = SaisBuilder::<u8, u32>::new().construct_suffix_array(&text);
+        let first = first.index(text.len());
+        let second = second.index(text.len());
+        suffix_array.swap(first, second);
+
+        prop_assert_eq!(verify_suffix_array(&text, &suffix_array).is_ok(), first == second);
+    }
+
+    #[test]
+    fn agrees_with_naive_check(
+        text in prop::collection::vec(0u8..3, 0..8),
+        suffix_array in prop::collection::vec(0u32..8, 0..8)
+    ) {
+        let result = verify_suffix_array(&text, &suffix_array);
+        prop_assert_eq!(result.is_ok(), is_sorted_permutation(&text, &suffix_array));
+    }
+}
@@ -3,7 +3,9 @@ use std::iter;
 use proptest::prelude::*;
 use rand::seq::SliceRandom;
 
-use sais_drum::{Character, IndexStorage, SaisBuilder};
+use sais_drum::{
+    BucketGranularity, Character, IndexStorage, SaisBuilder, verify::verify_suffix_array,
+};
 
 // example from
 // https://ae.iti.kit.edu/download/kurpicz/2022_text_indexing/02_suffix_tree_and_array_handout_ws2223.pdf
@@ -56,6 +58,35 @@ fn two_lms_mini_text() {
     assert_eq!(suffix_array, [3, 1, 4, 2, 0]);
 }
 
+#[test]
+fn small_text_threshold() {
+    let text = b"ababcabcabba";
+    let expected_suffix_array = [11, 0, 8, 5, 2, 10, 1, 9, 6, 3, 7, 4];
+
+    for small_text_threshold in [0, text.len(), text.len() + 1] {
+        let suffix_array = SaisBuilder::<_>::new()
+            .with_small_text_threshold(small_text_threshold)
+            .construct_suffix_array(text);
+
+        assert_eq!(suffix_array, expected_suffix_array);
+    }
+}
+
+#[test]
+fn sparse_alphabet_text() {
+    let text: Vec<u16> = ABC_TEXT.iter().map(|&char| char as u16 * 200).collect();
+    let expected_suffix_array = [11, 0, 8, 5, 2, 10, 1, 9, 6, 3, 7, 4];
+
+    for alphabet_compaction in [false, true] {
+        let suffix_array = SaisBuilder::<_>::new()
+            .with_small_text_threshold(0)
+            .with_alphabet_compaction(alphabet_compaction)
+            .construct_suffix_array(&text);
+
+        assert_eq!(suffix_array, expected_suffix_array);
+    }
+}
+
 #[test]
 fn single_char_text() {
     let text = vec![0u8; 10_000];
@@ -125,9 +156,34 @@ fn failing_proptest_example1() {
 }
 
 fn construct_and_test_suffix_array<C: Character, I: IndexStorage>(text: &[C]) {
-    let suffix_array = SaisBuilder::<C, I>::new().construct_suffix_array(text);
+    // test SAIS and the special case for small texts independent of the default threshold
+    for small_text_threshold in [0, usize::MAX] {
+        let suffix_array = SaisBuilder::<C, I>::new()
+            .with_small_text_threshold(small_text_threshold)
+            .construct_suffix_array(text);
+
+        assert!(is_suffix_array(&suffix_array, text));
+        assert_eq!(verify_suffix_array(text, &suffix_array), Ok(()));
+    }
+}
+
+// the granularity is otherwise fixed by the alphabet size, so u8 texts would never use the plain
+// character buckets
+fn construct_and_test_bucket_granularities(text: &[u8]) {
+    let suffix_arrays = [
+        BucketGranularity::Characters,
+        BucketGranularity::CharactersAndTypes,
+    ]
+    .map(|bucket_granularity| {
+        SaisBuilder::<u8, u32>::new()
+            .with_small_text_threshold(0)
+            .with_alphabet_compaction(false)
+            .with_bucket_granularity(bucket_granularity)
+            .construct_suffix_array(text)
+    });
 
-    assert!(is_suffix_array(&suffix_array, text));
+    assert!(is_suffix_array(&suffix_arrays[0], text));
+    assert_eq!(suffix_arrays[0], suffix_arrays[1]);
 }
 
 fn is_suffix_array<C: Character, I: IndexStorage>(maybe_suffix_array: &[I], text: &[C]) -> bool {
@@ -144,6 +200,94 @@ fn is_suffix_array<C: Character, I: IndexStorage>(maybe_suffix_array: &[I], text
     true
 }
 
+fn construct_and_test_char_frequencies<C: Character, I: IndexStorage>(text: &[C]) {
+    let mut expected_char_frequencies = vec![I::zero(); C::max_char().rank() + 1];
+    for char in text {
+        let entry = &mut expected_char_frequencies[char.rank()];
+        *entry = *entry + I::one();
+    }
+
+    for small_text_threshold in [0, usize::MAX] {
+        let mut builder = SaisBuilder::<C, I>::new();
+        builder.with_small_text_threshold(small_text_threshold);
+
+        let (suffix_array, char_frequencies) =
+            builder.construct_suffix_array_with_char_frequencies(text);
+
+        assert_eq!(suffix_array, builder.construct_suffix_array(text));
+        assert!(char_frequencies == expected_char_frequencies);
+
+        // the frequency buffer is overwritten, not added to
+        let mut suffix_array_buffer = vec![I::zero(); text.len() + 100];
+        let mut char_frequencies = vec![I::one(); C::max_char().rank() + 1];
+        builder.construct_suffix_array_inplace_with_char_frequencies(
+            text,
+            &mut suffix_array_buffer,
+            &mut char_frequencies,
+        );
+
+        assert!(suffix_array_buffer[..text.len()] == suffix_array);
+        assert!(char_frequencies == expected_char_frequencies);
+    }
+}
+
+fn construct_and_test_with_known_char_frequencies<C: Character, I: IndexStorage>(text: &[C]) {
+    let (expected_suffix_array, char_frequencies) =
+        SaisBuilder::<C, I>::new().construct_suffix_array_with_char_frequencies(text);
+
+    for small_text_threshold in [0, usize::MAX] {
+        let mut builder = SaisBuilder::<C, I>::new();
+        builder
+            .with_small_text_threshold(small_text_threshold)
+            .with_char_frequencies(&char_frequencies);
+
+        assert_eq!(builder.construct_suffix_array(text), expected_suffix_array);
+
+        let (suffix_array, returned_char_frequencies) =
+            builder.construct_suffix_array_with_char_frequencies(text);
+
+        assert_eq!(suffix_array, expected_suffix_array);
+        assert!(returned_char_frequencies == char_frequencies);
+    }
+}
+
+#[test]
+#[should_panic(expected = "don't sum up to the text length")]
+fn known_char_frequencies_of_other_text() {
+    let mut char_frequencies = [0u32; 256];
+    char_frequencies[b'a' as usize] = 3;
+
+    SaisBuilder::<u8, u32>::new()
+        .with_char_frequencies(&char_frequencies)
+        .construct_suffix_array(b"aa");
+}
+
+#[test]
+#[should_panic(expected = "one character frequency per character")]
+fn known_char_frequencies_of_wrong_alphabet() {
+    SaisBuilder::<u8, u32>::new()
+        .with_char_frequencies(&[0, 2])
+        .construct_suffix_array(b"aa");
+}
+
+#[test]
+fn char_frequencies_of_empty_text() {
+    let (suffix_array, char_frequencies) =
+        SaisBuilder::<u8, u32>::new().construct_suffix_array_with_char_frequencies(&[]);
+
+    assert!(suffix_array.is_empty());
+    assert_eq!(char_frequencies, vec![0; 256]);
+}
+
+#[test]
+fn char_frequencies_with_max_char() {
+    let (_, char_frequencies) = SaisBuilder::<u8, u32>::new()
+        .with_max_char(3)
+        .construct_suffix_array_with_char_frequencies(&[0, 3, 3, 1, 3]);
+
+    assert_eq!(char_frequencies, [1, 1, 0, 3]);
+}
+
 proptest! {
     // default is 256 and I'd like some more test cases that need to pass
     #![proptest_config(ProptestConfig::with_cases(2048))]
@@ -158,4 +302,65 @@ proptest! {
             _ => unreachable!()
         }
     }
+
+    #[test]
+    fn correctness_random_small_alphabet_texts(text in prop::collection::vec(0u8..4, 0..1000), type_index in 0..2) {
+        // small alphabets lead to many recursion levels
+        match type_index {
+            0 => construct_and_test_suffix_array::<u8, u16>(&text),
+            1 => construct_and_test_suffix_array::<u8, usize>(&text),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn correctness_random_tandem_repeat_texts(
+        unit in prop::collection::vec(0u8..4, 1..8),
+        num_repeats in 1usize..150,
+    ) {
+        // worst case inputs for the comparison sort of small texts
+        construct_and_test_suffix_array::<u8, u32>(&unit.repeat(num_repeats));
+    }
+
+    #[test]
+    fn correctness_random_sparse_alphabet_texts(
+        text in prop::collection::vec(prop::sample::select(vec![3u16, 500, 501, 20_000, 65_535]), 0..1000)
+    ) {
+        // the alphabet compaction is used for these texts
+        construct_and_test_suffix_array::<u16, u32>(&text);
+    }
+
+    #[test]
+    fn bucket_granularities_random_texts(text in prop::collection::vec(any::<u8>(), 0..1000)) {
+        construct_and_test_bucket_granularities(&text);
+    }
+
+    #[test]
+    fn bucket_granularities_random_small_alphabet_texts(text in prop::collection::vec(0u8..4, 0..1000)) {
+        construct_and_test_bucket_granularities(&text);
+    }
+
+    #[test]
+    fn char_frequencies_random_texts(text in prop::collection::vec(0u8..8, 0..1000)) {
+        construct_and_test_char_frequencies::<u8, u32>(&text);
+    }
+
+    #[test]
+    fn char_frequencies_random_sparse_alphabet_texts(
+        text in prop::collection::vec(prop::sample::select(vec![3u16, 500, 501, 20_000, 65_535]), 0..1000)
+    ) {
+        construct_and_test_char_frequencies::<u16, u32>(&text);
+    }
+
+    #[test]
+    fn known_char_frequencies_random_texts(text in prop::collection::vec(0u8..8, 0..1000)) {
+        construct_and_test_with_known_char_frequencies::<u8, u32>(&text);
+    }
+
+    #[test]
+    fn known_char_frequencies_random_sparse_alphabet_texts(
+        text in prop::collection::vec(prop::sample::select(vec![3u16, 500, 501, 20_000, 65_535]), 0..1000)
+    ) {
+        construct_and_test_with_known_char_frequencies::<u16, u32>(&text);
+    }
 }